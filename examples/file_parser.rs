@@ -25,7 +25,7 @@ fn main() {
     let path = Path::new(filename.as_str());
     let display = path.display();
 
-    let mut file = match File::open(&path) {
+    let mut file = match File::open(path) {
         Err(why) => panic!("Failed to open {}: {}", display, why),
         Ok(file) => file,
     };
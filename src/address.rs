@@ -4,14 +4,14 @@
 
 extern crate url;
 use self::url::Host;
-use error::SdpParserInternalError;
+use error::{SdpParserErrorKind, SdpParserInternalError};
 use std::convert::TryFrom;
 use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Address {
     Fqdn(String),
     Ip(IpAddr),
@@ -65,8 +65,148 @@ impl PartialEq for Address {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+/*
+ * The kind of address of an ICE candidate, from the most to the least
+ * privacy sensitive for IP addresses. Candidates with an mDNS name are host
+ * candidates whose IP address is hidden, see
+ * draft-ietf-mmusic-mdns-ice-candidates.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum AddressClass {
+    Mdns,
+    Fqdn,
+    Unspecified,
+    Loopback,
+    LinkLocal,
+    Private,
+    Public,
+}
+
+impl fmt::Display for AddressClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AddressClass::Mdns => "mdns",
+            AddressClass::Fqdn => "fqdn",
+            AddressClass::Unspecified => "unspecified",
+            AddressClass::Loopback => "loopback",
+            AddressClass::LinkLocal => "link-local",
+            AddressClass::Private => "private",
+            AddressClass::Public => "public",
+        }
+        .fmt(f)
+    }
+}
+
+// The IPv4 address of an IPv4-mapped IPv6 address (::ffff:a.b.c.d), RFC 4291
+pub fn ipv4_mapped(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+    match ip.octets() {
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, a, b, c, d] => Some(Ipv4Addr::new(a, b, c, d)),
+        _ => None,
+    }
+}
+
+// Any name in the .local domain is resolved with mDNS, see RFC 6762
+pub fn is_mdns_name(name: &str) -> bool {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    name.len() > ".local".len() && name.to_lowercase().ends_with(".local")
+}
+
+/*
+ * draft-ietf-mmusic-mdns-ice-candidates section 3.1.1
+ * The mDNS name of a host candidate is a version 4 UUID, as described in
+ * RFC 4122, followed by ".local".
+ */
+pub fn validate_mdns_name(name: &str) -> Result<(), SdpParserInternalError> {
+    let uuid = match name.len().checked_sub(".local".len()) {
+        Some(n) if name.is_char_boundary(n) && name[n..].eq_ignore_ascii_case(".local") => {
+            &name[..n]
+        }
+        _ => {
+            return Err(SdpParserInternalError::Generic(format!(
+                "mDNS name {} is not in the .local domain",
+                name
+            ))
+            .with_kind(SdpParserErrorKind::MdnsNameNotLocal))
+        }
+    };
+    let groups: Vec<&str> = uuid.split('-').collect();
+    let valid_groups = groups
+        .iter()
+        .map(|g| g.len())
+        .eq([8, 4, 4, 4, 12].iter().cloned())
+        && groups
+            .iter()
+            .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid_groups {
+        return Err(SdpParserInternalError::Generic(format!(
+            "mDNS name {} is not a UUID followed by .local",
+            name
+        ))
+        .with_kind(SdpParserErrorKind::MdnsNameNotUuid));
+    }
+    if !groups[2].starts_with('4') {
+        return Err(SdpParserInternalError::Generic(format!(
+            "mDNS name {} is not a version 4 UUID",
+            name
+        ))
+        .with_kind(SdpParserErrorKind::MdnsNameNotUuid));
+    }
+    if !matches!(
+        groups[3].chars().next(),
+        Some('8'..='9') | Some('a'..='b') | Some('A'..='B')
+    ) {
+        return Err(SdpParserInternalError::Generic(format!(
+            "mDNS name {} does not have the RFC 4122 UUID variant",
+            name
+        ))
+        .with_kind(SdpParserErrorKind::MdnsNameNotUuid));
+    }
+    Ok(())
+}
+
+impl Address {
+    pub fn class(&self) -> AddressClass {
+        match self {
+            Address::Fqdn(name) if is_mdns_name(name) => AddressClass::Mdns,
+            Address::Fqdn(_) => AddressClass::Fqdn,
+            Address::Ip(ip) if ip.is_unspecified() => AddressClass::Unspecified,
+            Address::Ip(ip) if ip.is_loopback() => AddressClass::Loopback,
+            Address::Ip(IpAddr::V4(ip)) => {
+                let octets = ip.octets();
+                if ip.is_link_local() {
+                    AddressClass::LinkLocal
+                } else if ip.is_private() || (octets[0] == 100 && (octets[1] & 0xc0) == 64) {
+                    // RFC 6598 shared address space is used by carrier grade NATs
+                    AddressClass::Private
+                } else {
+                    AddressClass::Public
+                }
+            }
+            Address::Ip(IpAddr::V6(ip)) => {
+                if let Some(ip) = ipv4_mapped(ip) {
+                    return Address::Ip(IpAddr::V4(ip)).class();
+                }
+                let first = ip.segments()[0];
+                if (first & 0xffc0) == 0xfe80 {
+                    AddressClass::LinkLocal
+                } else if (first & 0xfe00) == 0xfc00 {
+                    // RFC 4193 unique local addresses
+                    AddressClass::Private
+                } else {
+                    AddressClass::Public
+                }
+            }
+        }
+    }
+
+    pub fn is_mdns(&self) -> bool {
+        self.class() == AddressClass::Mdns
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum AddressType {
     IpV4 = 4,
     IpV6 = 6,
@@ -107,7 +247,7 @@ impl AddressTyped for IpAddr {
 }
 
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum ExplicitlyTypedAddress {
     Fqdn {
         address_type: AddressType,
@@ -71,3 +71,63 @@ fn test_ipv6_address_parsing() -> Result<(), ParseTestError> {
     }
     Ok(())
 }
+
+#[test]
+fn test_address_class() {
+    let class = |s: &str| Address::from_str(s).unwrap().class();
+    assert_eq!(
+        class("1f4712db-ea17-4bcf-a596-105139dfd8bf.local"),
+        AddressClass::Mdns
+    );
+    assert_eq!(class("host.LOCAL"), AddressClass::Mdns);
+    assert_eq!(class("example.com"), AddressClass::Fqdn);
+    assert_eq!(class("0.0.0.0"), AddressClass::Unspecified);
+    assert_eq!(class("127.0.0.1"), AddressClass::Loopback);
+    assert_eq!(class("::1"), AddressClass::Loopback);
+    assert_eq!(class("169.254.1.1"), AddressClass::LinkLocal);
+    assert_eq!(class("fe80::1"), AddressClass::LinkLocal);
+    assert_eq!(class("192.168.1.2"), AddressClass::Private);
+    assert_eq!(class("100.64.0.1"), AddressClass::Private);
+    assert_eq!(class("fd00::1"), AddressClass::Private);
+    assert_eq!(class("::ffff:10.0.0.1"), AddressClass::Private);
+    assert_eq!(class("203.0.113.7"), AddressClass::Public);
+    assert_eq!(class("2001:db8::1"), AddressClass::Public);
+    assert_eq!(AddressClass::LinkLocal.to_string(), "link-local");
+}
+
+#[test]
+fn test_validate_mdns_name() {
+    assert!(validate_mdns_name("1f4712db-ea17-4bcf-a596-105139dfd8bf.local").is_ok());
+    assert!(validate_mdns_name("1F4712DB-EA17-4BCF-A596-105139DFD8BF.Local").is_ok());
+    let error_kind = |name: &str| validate_mdns_name(name).map_err(|e| e.kind()).err();
+    assert_eq!(
+        error_kind("1f4712db-ea17-4bcf-a596-105139dfd8bf.example"),
+        Some(SdpParserErrorKind::MdnsNameNotLocal)
+    );
+    assert_eq!(
+        error_kind("printer.local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+    assert_eq!(
+        error_kind("1f4712db-ea17-4bcf-a596-105139dfd8b.local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+    assert_eq!(
+        error_kind("1f4712db-ea17-4bcf-a596-105139dfd8bg.local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+    // Not version 4
+    assert_eq!(
+        error_kind("1f4712db-ea17-1bcf-a596-105139dfd8bf.local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+    // Not the RFC 4122 variant
+    assert_eq!(
+        error_kind("1f4712db-ea17-4bcf-c596-105139dfd8bf.local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+    assert_eq!(
+        error_kind(".local"),
+        Some(SdpParserErrorKind::MdnsNameNotUuid)
+    );
+}
@@ -3,10 +3,17 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 extern crate url;
-use address::{Address, ExplicitlyTypedAddress};
+use address::{
+    ipv4_mapped, is_mdns_name, Address, AddressClass, AddressType, AddressTyped,
+    ExplicitlyTypedAddress,
+};
+use error::{SdpParserErrorKind, SdpParserInternalError};
 use std::collections::HashMap;
+use std::convert::TryInto;
+use std::hash::Hash;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::num::Wrapping;
+use std::str::FromStr;
 
 pub trait AnonymizingClone {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self;
@@ -29,6 +36,191 @@ impl ToBytesVec for u64 {
     }
 }
 
+/*
+* How IP addresses are masked. Class preserving masks keep loopback,
+* link-local, private, CGNAT and unique local prefixes, which keeps NAT and
+* network topology issues visible. Prefix preserving masks of addresses in
+* the same subnet are in the same subnet too.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum IpMaskingMode {
+    // A counter or, for keyed anonymizers, a keyed hash
+    Opaque,
+    ClassPreserving,
+    PrefixPreserving,
+}
+
+impl Default for IpMaskingMode {
+    fn default() -> Self {
+        IpMaskingMode::Opaque
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum AnonymizationAction {
+    Keep,
+    Mask,
+    Redact,
+}
+
+impl AnonymizationAction {
+    /*
+     * Applies the action to a value which can not be left out of the
+     * description, redacted values are replaced with a placeholder.
+     */
+    pub fn apply<T, F>(self, value: &T, mask: F, placeholder: T) -> T
+    where
+        T: Clone,
+        F: FnOnce(&T) -> T,
+    {
+        match self {
+            AnonymizationAction::Keep => value.clone(),
+            AnonymizationAction::Mask => mask(value),
+            AnonymizationAction::Redact => placeholder,
+        }
+    }
+}
+
+/*
+* What masked_clone does with each category of values. Attributes which only
+* carry a redacted value are left out, e.g. a=ice-ufrag or a=mid. Redacted
+* connection and origin addresses are replaced by the unspecified address,
+* redacted ports by the discard port 9, and a redacted origin user or
+* session name by "-". Redacting addresses leaves out all candidates. The
+* TTL and number of addresses of a multicast c= line are left out when the
+* masked address is not a multicast address.
+*/
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct AnonymizationPolicy {
+    // IP addresses and host names of c=, o=, a=rtcp and candidates
+    pub addresses: AnonymizationAction,
+    // Candidate and remote candidate ports
+    pub ports: AnonymizationAction,
+    pub origin_user: AnonymizationAction,
+    // a=ice-ufrag, a=ice-pwd and the ufrag of candidates
+    pub ice_credentials: AnonymizationAction,
+    pub fingerprints: AnonymizationAction,
+    pub cnames: AnonymizationAction,
+    // a=msid, a=msid-semantic and the msid and mslabel of a=ssrc
+    pub msids: AnonymizationAction,
+    // a=mid and the tags of a=group
+    pub mids: AnonymizationAction,
+    // a=rid and the rids of a=simulcast
+    pub rids: AnonymizationAction,
+    pub identity: AnonymizationAction,
+    // a=label and the label of a=ssrc
+    pub labels: AnonymizationAction,
+    pub session_name: AnonymizationAction,
+    // Unknown candidate extensions and a=ssrc attributes
+    pub unknown_values: AnonymizationAction,
+}
+
+impl AnonymizationPolicy {
+    pub fn keep_all() -> AnonymizationPolicy {
+        AnonymizationPolicy::with_action(AnonymizationAction::Keep)
+    }
+
+    pub fn mask_all() -> AnonymizationPolicy {
+        AnonymizationPolicy::with_action(AnonymizationAction::Mask)
+    }
+
+    pub fn redact_all() -> AnonymizationPolicy {
+        AnonymizationPolicy::with_action(AnonymizationAction::Redact)
+    }
+
+    fn with_action(action: AnonymizationAction) -> AnonymizationPolicy {
+        AnonymizationPolicy {
+            addresses: action,
+            ports: action,
+            origin_user: action,
+            ice_credentials: action,
+            fingerprints: action,
+            cnames: action,
+            msids: action,
+            mids: action,
+            rids: action,
+            identity: action,
+            labels: action,
+            session_name: action,
+            unknown_values: action,
+        }
+    }
+}
+
+// Masks addresses, ports, credentials, fingerprints and CNAMEs
+impl Default for AnonymizationPolicy {
+    fn default() -> Self {
+        AnonymizationPolicy {
+            addresses: AnonymizationAction::Mask,
+            ports: AnonymizationAction::Mask,
+            origin_user: AnonymizationAction::Mask,
+            ice_credentials: AnonymizationAction::Mask,
+            fingerprints: AnonymizationAction::Mask,
+            cnames: AnonymizationAction::Mask,
+            ..AnonymizationPolicy::keep_all()
+        }
+    }
+}
+
+/*
+* The highest namespace of an unkeyed anonymizer. The counters of a namespace
+* start at an offset, so that anonymizers in different namespaces never
+* produce the same mask as long as they mask fewer than 1000 ports and a
+* million values of every other kind.
+*/
+pub const MAX_ANONYMIZER_NAMESPACE: u8 = 63;
+
+/*
+* The masked values of an anonymizer, as pairs of original and masked value,
+* its counters and its configuration. The key of a keyed anonymizer is not
+* part of the state, only an identifier derived from it, so the same key has
+* to be given again to restore the state.
+*/
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpAnonymizerState {
+    pub key_id: Option<u64>,
+    pub namespace: u8,
+    pub ip_masking_mode: IpMaskingMode,
+    pub policy: AnonymizationPolicy,
+    pub ips: Vec<(IpAddr, IpAddr)>,
+    pub ip_v4_counter: u32,
+    pub ip_v6_counter: u128,
+    pub host_names: Vec<(String, String)>,
+    pub host_name_counter: u64,
+    pub mdns_names: Vec<(String, String)>,
+    pub mdns_name_counter: u64,
+    pub ports: Vec<(u32, u32)>,
+    pub port_counter: u32,
+    pub origin_users: Vec<(String, String)>,
+    pub origin_user_counter: u64,
+    pub ice_passwords: Vec<(String, String)>,
+    pub ice_password_counter: u64,
+    pub ice_users: Vec<(String, String)>,
+    pub ice_user_counter: u64,
+    pub cert_finger_prints: Vec<(Vec<u8>, Vec<u8>)>,
+    pub cert_finger_print_counter: u64,
+    pub cnames: Vec<(String, String)>,
+    pub cname_counter: u64,
+    pub msids: Vec<(String, String)>,
+    pub msid_counter: u64,
+    pub mids: Vec<(String, String)>,
+    pub mid_counter: u64,
+    pub rids: Vec<(String, String)>,
+    pub rid_counter: u64,
+    pub identities: Vec<(String, String)>,
+    pub identity_counter: u64,
+    pub labels: Vec<(String, String)>,
+    pub label_counter: u64,
+    pub session_names: Vec<(String, String)>,
+    pub session_name_counter: u64,
+    pub unknown_values: Vec<(String, String)>,
+    pub unknown_value_counter: u64,
+}
+
 /*
 * Anonymizes SDP in a stateful fashion, such that a pre-anonymized value will
 * always be transformed into the same anonymized value within the context of
@@ -36,12 +228,16 @@ impl ToBytesVec for u64 {
 * Stores the opaque state necessary for intelligent anonymization of SDP. This
 * state can be stored and reused during the offer-answer period, and it
 * will maintain a stable set of masked values.
+* An anonymizer created with a key derives the masked values from the key
+* instead, so that anonymizers with the same key mask a value identically in
+* different processes.
 */
 pub struct StatefulSdpAnonymizer {
     ips: HashMap<IpAddr, IpAddr>,
     ip_v4_inc: Wrapping<u32>,
     ip_v6_inc: Wrapping<u128>,
     host_names: AnonymizationStrMap,
+    mdns_names: AnonymizationStrMap,
     ports: HashMap<u32, u32>,
     port_inc: Wrapping<u32>,
     origin_users: AnonymizationStrMap,
@@ -50,6 +246,17 @@ pub struct StatefulSdpAnonymizer {
     cert_finger_prints: HashMap<Vec<u8>, Vec<u8>>,
     cert_finger_print_inc: Wrapping<u64>,
     cnames: AnonymizationStrMap,
+    msids: AnonymizationStrMap,
+    mids: AnonymizationStrMap,
+    rids: AnonymizationStrMap,
+    identities: AnonymizationStrMap,
+    labels: AnonymizationStrMap,
+    session_names: AnonymizationStrMap,
+    unknown_values: AnonymizationStrMap,
+    policy: AnonymizationPolicy,
+    key: Option<[u8; 16]>,
+    namespace: u8,
+    ip_masking_mode: IpMaskingMode,
 }
 
 impl Default for StatefulSdpAnonymizer {
@@ -65,6 +272,7 @@ impl StatefulSdpAnonymizer {
             ip_v4_inc: Wrapping(0),
             ip_v6_inc: Wrapping(0),
             host_names: AnonymizationStrMap::new("fqdn-", 8),
+            mdns_names: AnonymizationStrMap::new("00000000-0000-4000-8000-", 12),
             ports: HashMap::new(),
             port_inc: Wrapping(0),
             origin_users: AnonymizationStrMap::new("origin-user-", 8),
@@ -73,10 +281,135 @@ impl StatefulSdpAnonymizer {
             cert_finger_prints: HashMap::new(),
             cert_finger_print_inc: Wrapping(0),
             cnames: AnonymizationStrMap::new("cname-", 8),
+            msids: AnonymizationStrMap::new("msid-", 8),
+            mids: AnonymizationStrMap::new("mid-", 8),
+            rids: AnonymizationStrMap::new("rid-", 8),
+            identities: AnonymizationStrMap::new("identity-", 8),
+            labels: AnonymizationStrMap::new("label-", 8),
+            session_names: AnonymizationStrMap::new("session-", 8),
+            unknown_values: AnonymizationStrMap::new("value-", 8),
+            policy: AnonymizationPolicy::default(),
+            key: None,
+            namespace: 0,
+            ip_masking_mode: IpMaskingMode::Opaque,
+        }
+    }
+
+    /*
+     * An unkeyed anonymizer whose masks differ from those of anonymizers in
+     * other namespaces, e.g. one namespace per session, so that their states
+     * can be merged.
+     */
+    pub fn with_namespace(namespace: u8) -> Result<Self, SdpParserInternalError> {
+        let mut anon = Self::new();
+        anon.set_namespace(namespace)?;
+        Ok(anon)
+    }
+
+    fn set_namespace(&mut self, namespace: u8) -> Result<(), SdpParserInternalError> {
+        if namespace > MAX_ANONYMIZER_NAMESPACE {
+            return Err(SdpParserInternalError::Generic(format!(
+                "anonymizer namespace {} is larger than {}",
+                namespace, MAX_ANONYMIZER_NAMESPACE
+            ))
+            .with_kind(SdpParserErrorKind::AnonymizerNamespaceOutOfRange));
+        }
+        let offset = u64::from(namespace) * 1_000_000;
+        self.namespace = namespace;
+        self.ip_v4_inc = Wrapping(u32::from(namespace) << 24);
+        self.ip_v6_inc = Wrapping(u128::from(namespace) << 64);
+        self.port_inc = Wrapping(u32::from(namespace) * 1000);
+        self.cert_finger_print_inc = Wrapping(offset);
+        for map in [
+            &mut self.host_names,
+            &mut self.mdns_names,
+            &mut self.origin_users,
+            &mut self.ice_passwords,
+            &mut self.ice_users,
+            &mut self.cnames,
+            &mut self.msids,
+            &mut self.mids,
+            &mut self.rids,
+            &mut self.identities,
+            &mut self.labels,
+            &mut self.session_names,
+            &mut self.unknown_values,
+        ]
+        .iter_mut()
+        {
+            map.counter = Wrapping(offset);
+        }
+        Ok(())
+    }
+
+    pub fn with_key(key: [u8; 16]) -> Self {
+        let mut anon = Self::new();
+        anon.key = Some(key);
+        for map in [
+            &mut anon.host_names,
+            &mut anon.origin_users,
+            &mut anon.ice_passwords,
+            &mut anon.ice_users,
+            &mut anon.cnames,
+            &mut anon.msids,
+            &mut anon.mids,
+            &mut anon.rids,
+            &mut anon.identities,
+            &mut anon.labels,
+            &mut anon.session_names,
+            &mut anon.unknown_values,
+        ]
+        .iter_mut()
+        {
+            map.key = Some(key);
+        }
+        anon
+    }
+
+    // Prefix preserving masking needs a key
+    pub fn set_ip_masking_mode(
+        &mut self,
+        mode: IpMaskingMode,
+    ) -> Result<(), SdpParserInternalError> {
+        if mode == IpMaskingMode::PrefixPreserving && self.key.is_none() {
+            return Err(SdpParserInternalError::Generic(
+                "prefix preserving IP masking needs a keyed anonymizer".to_string(),
+            )
+            .with_kind(SdpParserErrorKind::AnonymizerMissingKey));
         }
+        self.ip_masking_mode = mode;
+        Ok(())
+    }
+
+    pub fn set_policy(&mut self, policy: AnonymizationPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn policy(&self) -> &AnonymizationPolicy {
+        &self.policy
     }
 
+    /*
+     * mDNS names of host candidates are masked as version 4 UUIDs in the
+     * .local domain, so that they can still be told apart from other names.
+     */
     pub fn mask_host(&mut self, host: &str) -> String {
+        if is_mdns_name(host) {
+            let host = host.to_lowercase();
+            if let (Some(ref key), None) = (self.key, self.mdns_names.map.get(&host)) {
+                let bits = keyed_hash_128(key, "mdns", host.as_bytes());
+                let uuid = format!(
+                    "{:08x}-{:04x}-4{:03x}-{:04x}-{:012x}",
+                    bits >> 96,
+                    (bits >> 80) & 0xffff,
+                    (bits >> 64) & 0xfff,
+                    0x8000 | ((bits >> 48) & 0x3fff),
+                    bits & 0xffff_ffff_ffff
+                );
+                self.mdns_names.map.insert(host.clone(), uuid);
+            }
+            return format!("{}.local", self.mdns_names.mask(&host));
+        }
         self.host_names.mask(host)
     }
 
@@ -84,7 +417,18 @@ impl StatefulSdpAnonymizer {
         if let Some(address) = self.ips.get(addr) {
             return *address;
         }
-        let mapped = match addr {
+        let mapped = match (self.ip_masking_mode, self.key) {
+            (IpMaskingMode::ClassPreserving, _) => self.class_preserving_ip(addr),
+            (IpMaskingMode::PrefixPreserving, Some(ref key)) => prefix_preserving_ip(key, addr),
+            (_, Some(ref key)) => keyed_ip(key, addr),
+            (_, None) => self.sequential_ip(addr),
+        };
+        self.ips.insert(*addr, mapped);
+        mapped
+    }
+
+    fn sequential_ip(&mut self, addr: &IpAddr) -> IpAddr {
+        match addr {
             IpAddr::V4(_) => {
                 self.ip_v4_inc += Wrapping(1);
                 IpAddr::V4(Ipv4Addr::from(self.ip_v4_inc.0))
@@ -93,9 +437,72 @@ impl StatefulSdpAnonymizer {
                 self.ip_v6_inc += Wrapping(1);
                 IpAddr::V6(Ipv6Addr::from(self.ip_v6_inc.0))
             }
-        };
-        self.ips.insert(*addr, mapped);
-        mapped
+        }
+    }
+
+    /*
+     * Keeps the special purpose prefix of the address and masks the rest,
+     * with a keyed hash or the counter. Keyed masks of public addresses are
+     * rehashed until they are public addresses too, unkeyed ones are taken
+     * from 11.0.0.0/8 and 2000::/3.
+     */
+    fn class_preserving_ip(&mut self, addr: &IpAddr) -> IpAddr {
+        if addr.is_unspecified() || (addr.is_loopback() && addr.is_ipv6()) {
+            return *addr;
+        }
+        match addr {
+            IpAddr::V4(ip) => {
+                let prefix = class_prefix_v4(u32::from(*ip));
+                let key = match self.key {
+                    Some(key) => key,
+                    None => {
+                        let (prefix, length) = prefix.unwrap_or(PUBLIC_PREFIX_V4);
+                        let index = match self.sequential_ip(addr) {
+                            IpAddr::V4(counter) => u32::from(counter) & 0x00ff_ffff,
+                            IpAddr::V6(_) => unreachable!(),
+                        };
+                        // The namespace takes the upper 6 bits of the host part
+                        let index_bits = 32 - length - 6;
+                        let host = (u32::from(self.namespace) << index_bits)
+                            | (index & (u32::MAX >> (32 - index_bits)));
+                        return IpAddr::V4(Ipv4Addr::from(prefix | host));
+                    }
+                };
+                let mut round: u32 = 0;
+                loop {
+                    let mut value = ip.octets().to_vec();
+                    value.extend_from_slice(&round.to_be_bytes());
+                    let host = keyed_hash(&key, "ip4-class", &value) as u32;
+                    let mapped = match prefix {
+                        Some((prefix, length)) => prefix | (host & (u32::MAX >> length)),
+                        None => host,
+                    };
+                    let public = Address::Ip(IpAddr::V4(Ipv4Addr::from(mapped))).class()
+                        == AddressClass::Public;
+                    if prefix.is_some() || public {
+                        return IpAddr::V4(Ipv4Addr::from(mapped));
+                    }
+                    round += 1;
+                }
+            }
+            IpAddr::V6(ip) => {
+                if let Some(ip) = ipv4_mapped(ip) {
+                    return match self.class_preserving_ip(&IpAddr::V4(ip)) {
+                        IpAddr::V4(mapped) => IpAddr::V6(mapped.to_ipv6_mapped()),
+                        mapped => mapped,
+                    };
+                }
+                let host = match self.key {
+                    Some(ref key) => keyed_hash_128(key, "ip6-class", &ip.octets()),
+                    None => match self.sequential_ip(addr) {
+                        IpAddr::V6(counter) => u128::from(counter),
+                        IpAddr::V4(_) => unreachable!(),
+                    },
+                };
+                let (prefix, length) = class_prefix_v6(u128::from(*ip)).unwrap_or(PUBLIC_PREFIX_V6);
+                IpAddr::V6(Ipv6Addr::from(prefix | (host & (u128::MAX >> length))))
+            }
+        }
     }
 
     pub fn mask_address(&mut self, address: &Address) -> Address {
@@ -125,6 +532,11 @@ impl StatefulSdpAnonymizer {
         if let Some(stored) = self.ports.get(&port) {
             return *stored;
         }
+        if let Some(ref key) = self.key {
+            let mapped = keyed_port(key, port);
+            self.ports.insert(port, mapped);
+            return mapped;
+        }
         self.port_inc += Wrapping(1);
         self.ports.insert(port, self.port_inc.0);
         self.port_inc.0
@@ -146,6 +558,12 @@ impl StatefulSdpAnonymizer {
         if let Some(stored) = self.cert_finger_prints.get(finger_print) {
             return stored.clone();
         }
+        if let Some(ref key) = self.key {
+            let mapped = keyed_hash(key, "fingerprint", finger_print).to_byte_vec();
+            self.cert_finger_prints
+                .insert(finger_print.to_vec(), mapped.clone());
+            return mapped;
+        }
         self.cert_finger_print_inc += Wrapping(1);
         self.cert_finger_prints.insert(
             finger_print.to_vec(),
@@ -157,6 +575,782 @@ impl StatefulSdpAnonymizer {
     pub fn mask_cname(&mut self, cname: &str) -> String {
         self.cnames.mask(cname)
     }
+
+    pub fn mask_msid(&mut self, msid: &str) -> String {
+        self.msids.mask(msid)
+    }
+
+    pub fn mask_mid(&mut self, mid: &str) -> String {
+        self.mids.mask(mid)
+    }
+
+    pub fn mask_rid(&mut self, rid: &str) -> String {
+        self.rids.mask(rid)
+    }
+
+    pub fn mask_identity(&mut self, identity: &str) -> String {
+        self.identities.mask(identity)
+    }
+
+    pub fn mask_label(&mut self, label: &str) -> String {
+        self.labels.mask(label)
+    }
+
+    pub fn mask_session_name(&mut self, session_name: &str) -> String {
+        self.session_names.mask(session_name)
+    }
+
+    pub fn mask_unknown_value(&mut self, unknown_value: &str) -> String {
+        self.unknown_values.mask(unknown_value)
+    }
+
+    // Reverse lookups of values masked by this anonymizer
+
+    pub fn unmask_ip(&self, masked: &IpAddr) -> Option<IpAddr> {
+        reverse_lookup(&self.ips, masked)
+    }
+
+    pub fn unmask_host(&self, masked: &str) -> Option<String> {
+        match masked.strip_suffix(".local") {
+            Some(uuid) => self.mdns_names.unmask(uuid),
+            None => self.host_names.unmask(masked),
+        }
+    }
+
+    pub fn unmask_address(&self, masked: &Address) -> Option<Address> {
+        match masked {
+            Address::Fqdn(host) => self.unmask_host(host).map(Address::Fqdn),
+            Address::Ip(ip) => self.unmask_ip(ip).map(Address::Ip),
+        }
+    }
+
+    pub fn unmask_port(&self, masked: u32) -> Option<u32> {
+        reverse_lookup(&self.ports, &masked)
+    }
+
+    pub fn unmask_origin_user(&self, masked: &str) -> Option<String> {
+        self.origin_users.unmask(masked)
+    }
+
+    pub fn unmask_ice_password(&self, masked: &str) -> Option<String> {
+        self.ice_passwords.unmask(masked)
+    }
+
+    pub fn unmask_ice_user(&self, masked: &str) -> Option<String> {
+        self.ice_users.unmask(masked)
+    }
+
+    pub fn unmask_cert_finger_print(&self, masked: &[u8]) -> Option<Vec<u8>> {
+        reverse_lookup(&self.cert_finger_prints, &masked.to_vec())
+    }
+
+    pub fn unmask_cname(&self, masked: &str) -> Option<String> {
+        self.cnames.unmask(masked)
+    }
+
+    pub fn unmask_msid(&self, masked: &str) -> Option<String> {
+        self.msids.unmask(masked)
+    }
+
+    pub fn unmask_mid(&self, masked: &str) -> Option<String> {
+        self.mids.unmask(masked)
+    }
+
+    pub fn unmask_rid(&self, masked: &str) -> Option<String> {
+        self.rids.unmask(masked)
+    }
+
+    pub fn unmask_identity(&self, masked: &str) -> Option<String> {
+        self.identities.unmask(masked)
+    }
+
+    pub fn unmask_label(&self, masked: &str) -> Option<String> {
+        self.labels.unmask(masked)
+    }
+
+    pub fn unmask_session_name(&self, masked: &str) -> Option<String> {
+        self.session_names.unmask(masked)
+    }
+
+    pub fn unmask_unknown_value(&self, masked: &str) -> Option<String> {
+        self.unknown_values.unmask(masked)
+    }
+
+    pub fn export_state(&self) -> SdpAnonymizerState {
+        SdpAnonymizerState {
+            key_id: self.key.as_ref().map(key_id),
+            namespace: self.namespace,
+            ip_masking_mode: self.ip_masking_mode,
+            policy: self.policy.clone(),
+            ips: sorted_pairs(&self.ips),
+            ip_v4_counter: self.ip_v4_inc.0,
+            ip_v6_counter: self.ip_v6_inc.0,
+            host_names: sorted_pairs(&self.host_names.map),
+            host_name_counter: self.host_names.counter.0,
+            mdns_names: sorted_pairs(&self.mdns_names.map),
+            mdns_name_counter: self.mdns_names.counter.0,
+            ports: sorted_pairs(&self.ports),
+            port_counter: self.port_inc.0,
+            origin_users: sorted_pairs(&self.origin_users.map),
+            origin_user_counter: self.origin_users.counter.0,
+            ice_passwords: sorted_pairs(&self.ice_passwords.map),
+            ice_password_counter: self.ice_passwords.counter.0,
+            ice_users: sorted_pairs(&self.ice_users.map),
+            ice_user_counter: self.ice_users.counter.0,
+            cert_finger_prints: sorted_pairs(&self.cert_finger_prints),
+            cert_finger_print_counter: self.cert_finger_print_inc.0,
+            cnames: sorted_pairs(&self.cnames.map),
+            cname_counter: self.cnames.counter.0,
+            msids: sorted_pairs(&self.msids.map),
+            msid_counter: self.msids.counter.0,
+            mids: sorted_pairs(&self.mids.map),
+            mid_counter: self.mids.counter.0,
+            rids: sorted_pairs(&self.rids.map),
+            rid_counter: self.rids.counter.0,
+            identities: sorted_pairs(&self.identities.map),
+            identity_counter: self.identities.counter.0,
+            labels: sorted_pairs(&self.labels.map),
+            label_counter: self.labels.counter.0,
+            session_names: sorted_pairs(&self.session_names.map),
+            session_name_counter: self.session_names.counter.0,
+            unknown_values: sorted_pairs(&self.unknown_values.map),
+            unknown_value_counter: self.unknown_values.counter.0,
+        }
+    }
+
+    // The key must be the key of the anonymizer the state was exported from
+    pub fn from_state(
+        state: &SdpAnonymizerState,
+        key: Option<[u8; 16]>,
+    ) -> Result<Self, SdpParserInternalError> {
+        if key.as_ref().map(key_id) != state.key_id {
+            return Err(SdpParserInternalError::Generic(
+                "the key does not match the key of the anonymizer state".to_string(),
+            )
+            .with_kind(SdpParserErrorKind::AnonymizerKeyMismatch));
+        }
+        let mut anon = match key {
+            Some(key) => Self::with_key(key),
+            None => Self::with_namespace(state.namespace)?,
+        };
+        anon.set_ip_masking_mode(state.ip_masking_mode)?;
+        anon.set_policy(state.policy.clone());
+        anon.merge_state(state)?;
+        Ok(anon)
+    }
+
+    /*
+     * Adds the masked values of another anonymizer, e.g. of another session.
+     * Nothing is merged if a value was masked differently or two values have
+     * the same mask, which is the case for the states of unkeyed anonymizers
+     * in the same namespace. States of anonymizers with the same key and of
+     * unkeyed anonymizers in different namespaces merge. The counters are
+     * only taken over from a state of the same namespace.
+     */
+    pub fn merge_state(
+        &mut self,
+        state: &SdpAnonymizerState,
+    ) -> Result<(), SdpParserInternalError> {
+        check_merge(&self.ips, &state.ips, "IP address")?;
+        check_merge(&self.host_names.map, &state.host_names, "host name")?;
+        check_merge(&self.mdns_names.map, &state.mdns_names, "mDNS name")?;
+        check_merge(&self.ports, &state.ports, "port")?;
+        check_merge(&self.origin_users.map, &state.origin_users, "origin user")?;
+        check_merge(
+            &self.ice_passwords.map,
+            &state.ice_passwords,
+            "ICE password",
+        )?;
+        check_merge(&self.ice_users.map, &state.ice_users, "ICE user")?;
+        check_merge(
+            &self.cert_finger_prints,
+            &state.cert_finger_prints,
+            "certificate fingerprint",
+        )?;
+        check_merge(&self.cnames.map, &state.cnames, "CNAME")?;
+        check_merge(&self.msids.map, &state.msids, "msid")?;
+        check_merge(&self.mids.map, &state.mids, "mid")?;
+        check_merge(&self.rids.map, &state.rids, "rid")?;
+        check_merge(&self.identities.map, &state.identities, "identity")?;
+        check_merge(&self.labels.map, &state.labels, "label")?;
+        check_merge(
+            &self.session_names.map,
+            &state.session_names,
+            "session name",
+        )?;
+        check_merge(
+            &self.unknown_values.map,
+            &state.unknown_values,
+            "unknown value",
+        )?;
+
+        let same_namespace = self.namespace == state.namespace;
+        self.ips.extend(state.ips.iter().cloned());
+        self.ports.extend(state.ports.iter().cloned());
+        self.cert_finger_prints
+            .extend(state.cert_finger_prints.iter().cloned());
+        if same_namespace {
+            self.ip_v4_inc = Wrapping(self.ip_v4_inc.0.max(state.ip_v4_counter));
+            self.ip_v6_inc = Wrapping(self.ip_v6_inc.0.max(state.ip_v6_counter));
+            self.port_inc = Wrapping(self.port_inc.0.max(state.port_counter));
+            self.cert_finger_print_inc = Wrapping(
+                self.cert_finger_print_inc
+                    .0
+                    .max(state.cert_finger_print_counter),
+            );
+        }
+        for (map, pairs, other) in vec![
+            (
+                &mut self.host_names,
+                &state.host_names,
+                state.host_name_counter,
+            ),
+            (
+                &mut self.mdns_names,
+                &state.mdns_names,
+                state.mdns_name_counter,
+            ),
+            (
+                &mut self.origin_users,
+                &state.origin_users,
+                state.origin_user_counter,
+            ),
+            (
+                &mut self.ice_passwords,
+                &state.ice_passwords,
+                state.ice_password_counter,
+            ),
+            (
+                &mut self.ice_users,
+                &state.ice_users,
+                state.ice_user_counter,
+            ),
+            (&mut self.cnames, &state.cnames, state.cname_counter),
+            (&mut self.msids, &state.msids, state.msid_counter),
+            (&mut self.mids, &state.mids, state.mid_counter),
+            (&mut self.rids, &state.rids, state.rid_counter),
+            (
+                &mut self.identities,
+                &state.identities,
+                state.identity_counter,
+            ),
+            (&mut self.labels, &state.labels, state.label_counter),
+            (
+                &mut self.session_names,
+                &state.session_names,
+                state.session_name_counter,
+            ),
+            (
+                &mut self.unknown_values,
+                &state.unknown_values,
+                state.unknown_value_counter,
+            ),
+        ] {
+            map.map.extend(pairs.iter().cloned());
+            if same_namespace {
+                map.counter = Wrapping(map.counter.0.max(other));
+            }
+        }
+        Ok(())
+    }
+}
+
+/*
+ * Anonymization of SDP text, which also works for descriptions or lines the
+ * parser rejects, e.g. to share them in bug reports. Every value the policy
+ * covers gets the same mask as in masked_clone of the parsed description,
+ * as long as the lines are in the order of RFC 4566. The text is only
+ * changed where values are masked, and lines the policy redacts are left
+ * out. IP addresses in lines which are not understood are masked too.
+ */
+impl StatefulSdpAnonymizer {
+    pub fn mask_sdp_text(&mut self, text: &str) -> String {
+        let mut masked = String::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            let (line, next) = rest.split_at(end);
+            rest = next;
+            let content = line.trim_end_matches(&['\r', '\n'][..]);
+            if let Some(masked_line) = self.mask_sdp_line(content) {
+                masked.push_str(&masked_line);
+                masked.push_str(&line[content.len()..]);
+            }
+        }
+        masked
+    }
+
+    // The masked line, or None if the policy redacts it
+    pub fn mask_sdp_line(&mut self, line: &str) -> Option<String> {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let (line_type, value) = match split_pair(line.trim_start(), '=') {
+            Some(split) => split,
+            None => return Some(self.mask_text_ips(line)),
+        };
+        let masked = match line_type {
+            "o" => self.mask_text_origin(value),
+            "s" => Some(self.policy.session_name.apply(
+                &value.to_string(),
+                |name| self.mask_session_name(name),
+                "-".to_string(),
+            )),
+            "c" => self.mask_text_connection(value),
+            "a" => {
+                let (name, attribute_value) = split_pair(value, ':').unwrap_or((value, ""));
+                let masked = match name.to_lowercase().as_str() {
+                    "candidate" => self.mask_text_candidate(attribute_value),
+                    "remote-candidates" => self.mask_text_remote_candidates(attribute_value),
+                    "rtcp" => Some(self.mask_text_rtcp(attribute_value)),
+                    "source-filter" => self.mask_text_source_filter(attribute_value),
+                    "ice-ufrag" => self.mask_text_value(
+                        self.policy.ice_credentials,
+                        attribute_value,
+                        |a, v| a.mask_ice_user(v),
+                    ),
+                    "ice-pwd" => self.mask_text_value(
+                        self.policy.ice_credentials,
+                        attribute_value,
+                        |a, v| a.mask_ice_password(v),
+                    ),
+                    "fingerprint" => self.mask_text_fingerprint(attribute_value),
+                    "ssrc" => self.mask_text_ssrc(attribute_value),
+                    "identity" => {
+                        self.mask_text_value(self.policy.identity, attribute_value, |a, v| {
+                            a.mask_identity(v)
+                        })
+                    }
+                    "label" => self.mask_text_value(self.policy.labels, attribute_value, |a, v| {
+                        a.mask_label(v)
+                    }),
+                    "mid" => self
+                        .mask_text_value(self.policy.mids, attribute_value, |a, v| a.mask_mid(v)),
+                    "group" => {
+                        self.mask_text_tokens(self.policy.mids, attribute_value, 1, |a, v| {
+                            a.mask_mid(v)
+                        })
+                    }
+                    "msid" => {
+                        self.mask_text_tokens(self.policy.msids, attribute_value, 0, |a, v| {
+                            a.mask_msid(v)
+                        })
+                    }
+                    "msid-semantic" => {
+                        self.mask_text_tokens(self.policy.msids, attribute_value, 1, |a, v| match v
+                        {
+                            "*" => v.to_string(),
+                            _ => a.mask_msid(v),
+                        })
+                    }
+                    "rid" => self.mask_text_rid(attribute_value),
+                    "simulcast" => self.mask_text_simulcast(attribute_value),
+                    _ => Some(self.mask_text_ips(attribute_value)),
+                };
+                masked.map(|masked| match value.find(':') {
+                    Some(_) => format!("{}:{}", name, masked),
+                    None => name.to_string(),
+                })
+            }
+            _ => Some(self.mask_text_ips(value)),
+        };
+        masked.map(|masked| format!("{}{}={}", indent, line_type, masked))
+    }
+
+    fn mask_text_value<F>(
+        &mut self,
+        action: AnonymizationAction,
+        value: &str,
+        mask: F,
+    ) -> Option<String>
+    where
+        F: FnOnce(&mut Self, &str) -> String,
+    {
+        match action {
+            AnonymizationAction::Keep => Some(value.to_string()),
+            AnonymizationAction::Mask => Some(mask(self, value)),
+            AnonymizationAction::Redact => None,
+        }
+    }
+
+    // Masks the tokens from the given one on, empty tokens are not counted
+    fn mask_text_tokens<F>(
+        &mut self,
+        action: AnonymizationAction,
+        value: &str,
+        first: usize,
+        mut mask: F,
+    ) -> Option<String>
+    where
+        F: FnMut(&mut Self, &str) -> String,
+    {
+        self.mask_text_value(action, value, |anon, value| {
+            let mut position = 0;
+            value
+                .split(' ')
+                .map(|token| {
+                    if token.is_empty() {
+                        return String::new();
+                    }
+                    position += 1;
+                    if position > first {
+                        mask(anon, token)
+                    } else {
+                        token.to_string()
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+    }
+
+    fn mask_text_address(&mut self, address_type: Option<&str>, token: &str) -> String {
+        let address = Address::from_str(token).ok();
+        match self.policy.addresses {
+            AnonymizationAction::Keep => token.to_string(),
+            AnonymizationAction::Mask => match address {
+                Some(address) => self.mask_address(&address).to_string(),
+                None => self.mask_host(token),
+            },
+            AnonymizationAction::Redact => {
+                let ip6 = match (address_type, address) {
+                    (Some(address_type), _) => address_type.eq_ignore_ascii_case("IP6"),
+                    (None, Some(Address::Ip(ip))) => ip.is_ipv6(),
+                    (None, _) => false,
+                };
+                if ip6 {
+                    Ipv6Addr::UNSPECIFIED.to_string()
+                } else {
+                    Ipv4Addr::UNSPECIFIED.to_string()
+                }
+            }
+        }
+    }
+
+    // Ports which are not numbers are left untouched
+    fn mask_text_port(&mut self, token: &str) -> String {
+        match (self.policy.ports, token.parse::<u32>()) {
+            (AnonymizationAction::Mask, Ok(port)) => self.mask_port(port).to_string(),
+            (AnonymizationAction::Redact, Ok(_)) => "9".to_string(),
+            _ => token.to_string(),
+        }
+    }
+
+    // Masks all tokens which are IP addresses, optionally followed by a /
+    fn mask_text_ips(&mut self, text: &str) -> String {
+        text.split(' ')
+            .map(|token| {
+                let (address, suffix) = token.split_at(token.find('/').unwrap_or(token.len()));
+                match IpAddr::from_str(address) {
+                    Ok(_) => format!("{}{}", self.mask_text_address(None, address), suffix),
+                    Err(_) => token.to_string(),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn mask_text_origin(&mut self, value: &str) -> Option<String> {
+        let mut tokens: Vec<String> = value.split(' ').map(ToString::to_string).collect();
+        if tokens.len() != 6 {
+            return Some(self.mask_text_ips(value));
+        }
+        tokens[0] = self.policy.origin_user.apply(
+            &tokens[0],
+            |user| self.mask_origin_user(user),
+            "-".to_string(),
+        );
+        tokens[5] = self.mask_text_address(Some(&tokens[4]), &tokens[5]);
+        Some(tokens.join(" "))
+    }
+
+    fn mask_text_connection(&mut self, value: &str) -> Option<String> {
+        let tokens: Vec<&str> = value.split(' ').collect();
+        if tokens.len() != 3 {
+            return Some(self.mask_text_ips(value));
+        }
+        let (address, mut suffix) =
+            tokens[2].split_at(tokens[2].find('/').unwrap_or(tokens[2].len()));
+        let multicast = |address: &str| match IpAddr::from_str(address) {
+            Ok(ip) => ip.is_multicast(),
+            Err(_) => false,
+        };
+        let was_multicast = multicast(address);
+        let address = self.mask_text_address(Some(tokens[1]), address);
+        if self.policy.addresses == AnonymizationAction::Redact
+            || (was_multicast && !multicast(&address))
+        {
+            suffix = "";
+        }
+        Some(format!("{} {} {}{}", tokens[0], tokens[1], address, suffix))
+    }
+
+    fn mask_text_candidate(&mut self, value: &str) -> Option<String> {
+        if self.policy.addresses == AnonymizationAction::Redact {
+            return None;
+        }
+        let mut tokens: Vec<String> = value.split(' ').map(ToString::to_string).collect();
+        if tokens.len() < 6 {
+            return Some(self.mask_text_ips(value));
+        }
+        tokens[4] = self.mask_text_address(None, &tokens[4]);
+        tokens[5] = self.mask_text_port(&tokens[5]);
+        let mut i = 6;
+        while i + 1 < tokens.len() {
+            let next = tokens[i + 1].clone();
+            tokens[i + 1] = match tokens[i].to_lowercase().as_str() {
+                "raddr" => self.mask_text_address(None, &next),
+                "rport" => self.mask_text_port(&next),
+                "ufrag" => match self.policy.ice_credentials {
+                    AnonymizationAction::Keep => next,
+                    AnonymizationAction::Mask => self.mask_ice_user(&next),
+                    AnonymizationAction::Redact => {
+                        tokens.drain(i..i + 2);
+                        continue;
+                    }
+                },
+                "typ" | "tcptype" | "generation" | "network-cost" => next,
+                _ => match self.policy.unknown_values {
+                    AnonymizationAction::Keep => next,
+                    AnonymizationAction::Mask => self.mask_unknown_value(&next),
+                    AnonymizationAction::Redact => {
+                        tokens.drain(i..i + 2);
+                        continue;
+                    }
+                },
+            };
+            i += 2;
+        }
+        Some(tokens.join(" "))
+    }
+
+    fn mask_text_remote_candidates(&mut self, value: &str) -> Option<String> {
+        if self.policy.addresses == AnonymizationAction::Redact {
+            return None;
+        }
+        let mut tokens: Vec<String> = value.split(' ').map(ToString::to_string).collect();
+        for candidate in tokens.chunks_mut(3) {
+            if let [_, address, port] = candidate {
+                *address = self.mask_text_address(None, address);
+                *port = self.mask_text_port(port);
+            }
+        }
+        Some(tokens.join(" "))
+    }
+
+    // Keeps the spacing, a=source-filter: incl has a space after the colon
+    fn mask_text_source_filter(&mut self, value: &str) -> Option<String> {
+        if self.policy.addresses == AnonymizationAction::Redact {
+            return None;
+        }
+        let mut tokens: Vec<String> = value.split(' ').map(ToString::to_string).collect();
+        let positions: Vec<usize> = (0..tokens.len())
+            .filter(|i| !tokens[*i].is_empty())
+            .collect();
+        if positions.len() < 5 {
+            return Some(self.mask_text_ips(value));
+        }
+        let address_type = match tokens[positions[2]].as_str() {
+            "*" => None,
+            x => Some(x.to_string()),
+        };
+        for &i in &positions[3..] {
+            if tokens[i] != "*" {
+                tokens[i] = self.mask_text_address(address_type.as_deref(), &tokens[i]);
+            }
+        }
+        Some(tokens.join(" "))
+    }
+
+    fn mask_text_rtcp(&mut self, value: &str) -> String {
+        let tokens: Vec<&str> = value.split(' ').collect();
+        match tokens.as_slice() {
+            [port, network, address_type, address] => format!(
+                "{} {} {} {}",
+                port,
+                network,
+                address_type,
+                self.mask_text_address(Some(address_type), address)
+            ),
+            _ => self.mask_text_ips(value),
+        }
+    }
+
+    fn mask_text_fingerprint(&mut self, value: &str) -> Option<String> {
+        let (algorithm, fingerprint) = split_pair(value, ' ').unwrap_or(("", value));
+        self.mask_text_value(
+            self.policy.fingerprints,
+            fingerprint,
+            |anon, fingerprint| {
+                let bytes = fingerprint
+                    .split(':')
+                    .map(|byte| u8::from_str_radix(byte, 16))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .unwrap_or_else(|_| fingerprint.as_bytes().to_vec());
+                anon.mask_cert_finger_print(&bytes)
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<String>>()
+                    .join(":")
+            },
+        )
+        .map(|masked| match algorithm {
+            "" => masked,
+            _ => format!("{} {}", algorithm, masked),
+        })
+    }
+
+    // The values of a=ssrc are masked like in SdpAttributeSsrc::masked_clone
+    fn mask_text_ssrc(&mut self, value: &str) -> Option<String> {
+        let (id, attribute) = match split_pair(value, ' ') {
+            Some(split) => split,
+            None => return Some(value.to_string()),
+        };
+        let (name, attribute_value) = split_pair(attribute, ':').unwrap_or((attribute, ""));
+        let lower = name.to_lowercase();
+        let action = match lower.as_str() {
+            "cname" => self.policy.cnames,
+            "msid" | "mslabel" => self.policy.msids,
+            "label" => self.policy.labels,
+            _ => self.policy.unknown_values,
+        };
+        if action == AnonymizationAction::Redact {
+            return None;
+        }
+        if !attribute.contains(':') {
+            return Some(value.to_string());
+        }
+        self.mask_text_value(action, attribute_value, |anon, value| {
+            match lower.as_str() {
+                "cname" => anon.mask_cname(value),
+                "msid" => value
+                    .split(' ')
+                    .map(|id| anon.mask_msid(id))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                "mslabel" => anon.mask_msid(value),
+                "label" => anon.mask_label(value),
+                _ => anon.mask_unknown_value(value),
+            }
+        })
+        .map(|masked| format!("{} {}:{}", id, name, masked))
+    }
+
+    // The rid and the rids of its depend parameter
+    fn mask_text_rid(&mut self, value: &str) -> Option<String> {
+        self.mask_text_value(self.policy.rids, value, |anon, value| {
+            value
+                .split(' ')
+                .enumerate()
+                .map(|(i, token)| match i {
+                    0 => anon.mask_rid(token),
+                    2 => token
+                        .split(';')
+                        .map(|param| match split_pair(param, '=') {
+                            Some((name, rids)) if name == "depend" || name == "depends" => format!(
+                                "{}={}",
+                                name,
+                                rids.split(',')
+                                    .map(|rid| anon.mask_rid(rid))
+                                    .collect::<Vec<String>>()
+                                    .join(",")
+                            ),
+                            _ => param.to_string(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(";"),
+                    _ => token.to_string(),
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+    }
+
+    // The rids of the send and recv lists, keeping their ~ and rid= prefixes
+    fn mask_text_simulcast(&mut self, value: &str) -> Option<String> {
+        self.mask_text_value(self.policy.rids, value, |anon, value| {
+            value
+                .split(' ')
+                .map(|token| {
+                    if token.is_empty() || token == "send" || token == "recv" {
+                        return token.to_string();
+                    }
+                    let (prefix, list) = match token.strip_prefix("rid=") {
+                        Some(list) => ("rid=", list),
+                        None => ("", token),
+                    };
+                    let masked = list
+                        .split(';')
+                        .map(|version| {
+                            version
+                                .split(',')
+                                .map(|id| match id.strip_prefix('~') {
+                                    Some(id) => format!("~{}", anon.mask_rid(id)),
+                                    None => anon.mask_rid(id),
+                                })
+                                .collect::<Vec<String>>()
+                                .join(",")
+                        })
+                        .collect::<Vec<String>>()
+                        .join(";");
+                    format!("{}{}", prefix, masked)
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+    }
+}
+
+// Splits at the first separator, like str::split_once
+fn split_pair(text: &str, separator: char) -> Option<(&str, &str)> {
+    text.find(separator)
+        .map(|i| (&text[..i], &text[i + separator.len_utf8()..]))
+}
+
+// The unspecified address of the same family, names are replaced by 0.0.0.0
+pub fn redacted_address(address: &Address) -> Address {
+    match address {
+        Address::Ip(IpAddr::V6(_)) => Address::Ip(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+        _ => Address::Ip(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+    }
+}
+
+pub fn redacted_typed_address(address: &ExplicitlyTypedAddress) -> ExplicitlyTypedAddress {
+    match address.address_type() {
+        AddressType::IpV4 => ExplicitlyTypedAddress::from(Ipv4Addr::UNSPECIFIED),
+        AddressType::IpV6 => ExplicitlyTypedAddress::from(Ipv6Addr::UNSPECIFIED),
+    }
+}
+
+fn reverse_lookup<K: Clone, V: PartialEq>(map: &HashMap<K, V>, masked: &V) -> Option<K> {
+    map.iter()
+        .find(|&(_, value)| value == masked)
+        .map(|(key, _)| key.clone())
+}
+
+fn sorted_pairs<K: Clone + Ord, V: Clone>(map: &HashMap<K, V>) -> Vec<(K, V)> {
+    let mut pairs: Vec<(K, V)> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    pairs
+}
+
+fn check_merge<K: Eq + Hash, V: PartialEq>(
+    map: &HashMap<K, V>,
+    pairs: &[(K, V)],
+    name: &str,
+) -> Result<(), SdpParserInternalError> {
+    for (original, masked) in pairs {
+        let conflict = match map.get(original) {
+            Some(current) => current != masked,
+            None => map.values().any(|value| value == masked),
+        };
+        if conflict {
+            return Err(SdpParserInternalError::Generic(format!(
+                "anonymizer states have conflicting {} masks",
+                name
+            ))
+            .with_kind(SdpParserErrorKind::AnonymizerStateConflict));
+        }
+    }
+    Ok(())
 }
 
 struct AnonymizationStrMap {
@@ -164,6 +1358,7 @@ struct AnonymizationStrMap {
     counter: Wrapping<u64>,
     prefix: &'static str,
     padding: usize,
+    key: Option<[u8; 16]>,
 }
 
 impl AnonymizationStrMap {
@@ -173,6 +1368,7 @@ impl AnonymizationStrMap {
             counter: Wrapping(0),
             prefix,
             padding,
+            key: None,
         }
     }
 
@@ -181,6 +1377,15 @@ impl AnonymizationStrMap {
         if let Some(stored) = self.map.get(&key) {
             return stored.clone();
         }
+        if let Some(ref secret) = self.key {
+            let store = format!(
+                "{}{:016x}",
+                self.prefix,
+                keyed_hash(secret, self.prefix, value.as_bytes())
+            );
+            self.map.insert(key, store.clone());
+            return store;
+        }
         self.counter += Wrapping(1);
         let store = format!(
             "{}{:0padding$}",
@@ -191,6 +1396,191 @@ impl AnonymizationStrMap {
         self.map.insert(key, store.clone());
         store
     }
+
+    pub fn unmask(&self, masked: &str) -> Option<String> {
+        reverse_lookup(&self.map, &masked.to_string())
+    }
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13) ^ v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16) ^ v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21) ^ v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17) ^ v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+// SipHash-2-4, a keyed hash which is stable across processes and releases
+fn siphash(key: &[u8; 16], data: &[u8]) -> u64 {
+    let k0 = u64::from_le_bytes(key[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(key[8..].try_into().unwrap());
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let mut chunks = data.chunks_exact(8);
+    for chunk in &mut chunks {
+        let m = u64::from_le_bytes(chunk.try_into().unwrap());
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+    let last = chunks
+        .remainder()
+        .iter()
+        .enumerate()
+        .fold((data.len() as u64) << 56, |last, (i, byte)| {
+            last | (u64::from(*byte) << (8 * i))
+        });
+    v[3] ^= last;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= last;
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+// The label separates the masks of the different kinds of values
+fn keyed_hash(key: &[u8; 16], label: &str, value: &[u8]) -> u64 {
+    let mut data = label.as_bytes().to_vec();
+    data.push(0);
+    data.extend_from_slice(value);
+    siphash(key, &data)
+}
+
+// Identifies the key in exported states, without revealing it
+fn key_id(key: &[u8; 16]) -> u64 {
+    keyed_hash(key, "key-id", &[])
+}
+
+fn keyed_hash_128(key: &[u8; 16], label: &str, value: &[u8]) -> u128 {
+    let high = keyed_hash(key, &format!("{}-high", label), value);
+    let low = keyed_hash(key, &format!("{}-low", label), value);
+    (u128::from(high) << 64) | u128::from(low)
+}
+
+/*
+ * IPv4 masks are a keyed permutation, a four round Feistel network on 32
+ * bits, so that two addresses never get the same mask. IPv6 masks are a
+ * 128 bit hash, which makes collisions negligible.
+ */
+fn keyed_ip(key: &[u8; 16], addr: &IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V4(ip) => {
+            let value = u32::from(*ip);
+            let (mut left, mut right) = ((value >> 16) as u16, value as u16);
+            for round in 0..4u8 {
+                let right_bytes = [round, (right >> 8) as u8, right as u8];
+                let flip = keyed_hash(key, "ip4", &right_bytes) as u16;
+                let next = left ^ flip;
+                left = right;
+                right = next;
+            }
+            IpAddr::V4(Ipv4Addr::from((u32::from(left) << 16) | u32::from(right)))
+        }
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(keyed_hash_128(key, "ip6", &ip.octets()))),
+    }
+}
+
+/*
+ * A keyed permutation of the ports 1 to 65535, so that keyed port masks
+ * never collide: a four round Feistel network on 16 bits, repeated while
+ * the result is outside of the range. Other values are not ports and are
+ * left as they are.
+ */
+fn keyed_port(key: &[u8; 16], port: u32) -> u32 {
+    if port == 0 || port > 65535 {
+        return port;
+    }
+    let mut value = port - 1;
+    loop {
+        let (mut left, mut right) = ((value >> 8) as u8, value as u8);
+        for round in 0..4u8 {
+            let flip = keyed_hash(key, "port", &[round, right]) as u8;
+            let next = left ^ flip;
+            left = right;
+            right = next;
+        }
+        value = (u32::from(left) << 8) | u32::from(right);
+        if value < 65535 {
+            return value + 1;
+        }
+    }
+}
+
+/*
+ * The special purpose prefixes kept by class preserving masking: loopback,
+ * link-local, RFC 1918 private, RFC 6598 shared (CGNAT) and RFC 4193 unique
+ * local addresses.
+ */
+const CLASS_PREFIXES_V4: [(u32, u32); 6] = [
+    (0x7f00_0000, 8),
+    (0xa9fe_0000, 16),
+    (0x0a00_0000, 8),
+    (0xac10_0000, 12),
+    (0xc0a8_0000, 16),
+    (0x6440_0000, 10),
+];
+const CLASS_PREFIXES_V6: [(u128, u32); 2] = [(0xfe80 << 112, 10), (0xfc00 << 112, 7)];
+
+// Unkeyed class preserving masks of public addresses are in these blocks
+const PUBLIC_PREFIX_V4: (u32, u32) = (0x0b00_0000, 8);
+const PUBLIC_PREFIX_V6: (u128, u32) = (0x2 << 124, 3);
+
+fn class_prefix_v4(bits: u32) -> Option<(u32, u32)> {
+    CLASS_PREFIXES_V4
+        .iter()
+        .find(|&&(prefix, length)| (bits ^ prefix) >> (32 - length) == 0)
+        .cloned()
+}
+
+fn class_prefix_v6(bits: u128) -> Option<(u128, u32)> {
+    CLASS_PREFIXES_V6
+        .iter()
+        .find(|&&(prefix, length)| (bits ^ prefix) >> (128 - length) == 0)
+        .cloned()
+}
+
+/*
+ * Crypto-PAn style prefix preserving masking, with SipHash instead of AES
+ * as the pseudo random function: bit i of the address is flipped depending
+ * on the bits before it, so addresses sharing a prefix of n bits have masks
+ * sharing a prefix of n bits.
+ */
+fn prefix_preserving_bits(key: &[u8; 16], label: &str, bits: u128, width: u32) -> u128 {
+    let mut flips = 0u128;
+    for i in 0..width {
+        let prefix = bits.checked_shr(width - i).unwrap_or(0);
+        let mut value = vec![i as u8];
+        value.extend_from_slice(&prefix.to_be_bytes());
+        let flip = u128::from(keyed_hash(key, label, &value) & 1);
+        flips |= flip << (width - 1 - i);
+    }
+    bits ^ flips
+}
+
+fn prefix_preserving_ip(key: &[u8; 16], addr: &IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V4(ip) => {
+            let bits = prefix_preserving_bits(key, "ip4-prefix", u128::from(u32::from(*ip)), 32);
+            IpAddr::V4(Ipv4Addr::from(bits as u32))
+        }
+        IpAddr::V6(ip) => {
+            let bits = prefix_preserving_bits(key, "ip6-prefix", u128::from(*ip), 128);
+            IpAddr::V6(Ipv6Addr::from(bits))
+        }
+    }
 }
 
 #[cfg(test)]
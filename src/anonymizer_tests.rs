@@ -3,6 +3,11 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use super::*;
+use address::validate_mdns_name;
+use attribute_type::parse_attribute;
+use error::SdpParserErrorKind;
+use std::collections::HashSet;
+use {parse_sdp, SdpType};
 
 #[test]
 fn test_mask_ip() {
@@ -119,3 +124,487 @@ fn test_mask_cname() {
         assert_eq!(anon.mask_cname(cnames[2]), masked_cnames[2]);
     }
 }
+
+#[test]
+fn test_mask_mdns_host() {
+    let mut anon = StatefulSdpAnonymizer::default();
+    let name = "1f4712db-ea17-4bcf-a596-105139dfd8bf.local";
+    let masked = anon.mask_host(name);
+    assert_eq!(masked, "00000000-0000-4000-8000-000000000001.local");
+    assert!(validate_mdns_name(&masked).is_ok());
+    assert_eq!(anon.mask_host(&name.to_uppercase()), masked);
+    assert_eq!(
+        anon.mask_host("5a0c8e7e-5a43-4b3e-9a3c-2b4f7f0e6d21.local"),
+        "00000000-0000-4000-8000-000000000002.local"
+    );
+    assert_eq!(anon.mask_host("example.com"), "fqdn-00000001");
+    assert_eq!(
+        anon.mask_address(&Address::Fqdn(name.to_string())),
+        Address::Fqdn(masked)
+    );
+}
+
+#[test]
+fn test_siphash_reference_vectors() {
+    let key: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+    assert_eq!(siphash(&key, &[]), 0x726f_db47_dd0e_0e31);
+    assert_eq!(siphash(&key, &[0]), 0x74f8_39c5_93dc_67fd);
+    let data: Vec<u8> = (0..15).collect();
+    assert_eq!(siphash(&key, &data), 0xa129_ca61_49be_45e5);
+}
+
+#[test]
+fn test_keyed_port_masks_do_not_collide() {
+    let mut anon = StatefulSdpAnonymizer::with_key([3u8; 16]);
+    let mut seen = vec![false; 65536];
+    for port in 1..=65535 {
+        let masked = anon.mask_port(port);
+        assert!((1..=65535).contains(&masked));
+        assert!(!seen[masked as usize]);
+        seen[masked as usize] = true;
+    }
+    let masked = anon.mask_port(5000);
+    assert_eq!(anon.unmask_port(masked), Some(5000));
+    assert_eq!(anon.mask_port(0), 0);
+}
+
+#[test]
+fn test_keyed_ipv4_masks_do_not_collide() {
+    let mut anon = StatefulSdpAnonymizer::with_key([3u8; 16]);
+    let mut seen = HashSet::new();
+    for host in 0..(1u32 << 17) {
+        let ip = IpAddr::V4(Ipv4Addr::from(0x0a00_0000 | host));
+        assert!(seen.insert(anon.mask_ip(&ip)));
+    }
+    let ip = IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3));
+    let masked = anon.mask_ip(&ip);
+    assert_eq!(anon.unmask_ip(&masked), Some(ip));
+}
+
+#[test]
+fn test_keyed_masks_are_deterministic() {
+    let key = [7u8; 16];
+    let mut first = StatefulSdpAnonymizer::with_key(key);
+    let mut second = StatefulSdpAnonymizer::with_key(key);
+    let mut other = StatefulSdpAnonymizer::with_key([8u8; 16]);
+    let v4 = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1));
+    let v6 = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+
+    // The order of masking does not matter
+    let masked_v6 = second.mask_ip(&v6);
+    let masked_v4 = first.mask_ip(&v4);
+    assert_eq!(second.mask_ip(&v4), masked_v4);
+    assert_eq!(first.mask_ip(&v6), masked_v6);
+    assert_ne!(other.mask_ip(&v4), masked_v4);
+    assert_ne!(masked_v4, v4);
+    assert!(masked_v4.is_ipv4());
+    assert!(masked_v6.is_ipv6());
+
+    assert_eq!(first.mask_port(5000), second.mask_port(5000));
+    assert!(first.mask_port(5000) >= 1 && first.mask_port(5000) <= 65535);
+    assert_eq!(
+        first.mask_host("example.com"),
+        second.mask_host("example.com")
+    );
+    assert!(first.mask_host("example.com").starts_with("fqdn-"));
+    assert_eq!(first.mask_ice_user("user"), second.mask_ice_user("user"));
+    assert_eq!(
+        first.mask_cert_finger_print(&[1, 2, 3]),
+        second.mask_cert_finger_print(&[1, 2, 3])
+    );
+
+    let mdns = first.mask_host("1f4712db-ea17-4bcf-a596-105139dfd8bf.local");
+    assert_eq!(
+        mdns,
+        second.mask_host("1F4712DB-EA17-4BCF-A596-105139DFD8BF.local")
+    );
+    assert!(validate_mdns_name(&mdns).is_ok());
+}
+
+#[test]
+fn test_class_preserving_ip_masks() {
+    let mut keyed = StatefulSdpAnonymizer::with_key([42u8; 16]);
+    keyed
+        .set_ip_masking_mode(IpMaskingMode::ClassPreserving)
+        .unwrap();
+    let mut sequential = StatefulSdpAnonymizer::new();
+    sequential
+        .set_ip_masking_mode(IpMaskingMode::ClassPreserving)
+        .unwrap();
+    // The special purpose prefix which must be kept, in bits
+    for &(ip, prefix) in &[
+        ("0.0.0.0", 32),
+        ("127.0.0.1", 8),
+        ("169.254.3.4", 16),
+        ("10.1.2.3", 8),
+        ("172.16.0.1", 12),
+        ("192.168.1.2", 16),
+        ("100.64.1.1", 10),
+        ("203.0.113.7", 0),
+        ("::1", 128),
+        ("fe80::1", 10),
+        ("fd12::1", 7),
+        ("2001:db8::1", 0),
+        ("::ffff:192.168.0.1", 112),
+    ] {
+        let address: IpAddr = ip.parse().unwrap();
+        for anon in &mut [&mut keyed, &mut sequential] {
+            let masked = anon.mask_ip(&address);
+            assert_eq!(
+                Address::Ip(masked).class(),
+                Address::Ip(address).class(),
+                "{}",
+                ip
+            );
+            let shared = match (address, masked) {
+                (IpAddr::V4(a), IpAddr::V4(b)) => (u32::from(a) ^ u32::from(b)).leading_zeros(),
+                (IpAddr::V6(a), IpAddr::V6(b)) => (u128::from(a) ^ u128::from(b)).leading_zeros(),
+                _ => panic!("{} changed its family", ip),
+            };
+            assert!(shared >= prefix, "{} masked to {}", ip, masked);
+        }
+    }
+    assert_eq!(
+        sequential.mask_ip(&"192.168.1.2".parse().unwrap()),
+        "192.168.0.5".parse::<IpAddr>().unwrap()
+    );
+
+    // Unkeyed masks of public addresses are public addresses too
+    let mut sequential = StatefulSdpAnonymizer::with_namespace(2).unwrap();
+    sequential
+        .set_ip_masking_mode(IpMaskingMode::ClassPreserving)
+        .unwrap();
+    assert_eq!(
+        sequential.mask_ip(&"2001:db8::1".parse().unwrap()),
+        "2000::2:0:0:0:1".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        sequential.mask_ip(&"203.0.113.7".parse().unwrap()),
+        "11.8.0.1".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        sequential.mask_ip(&"10.1.2.3".parse().unwrap()),
+        "10.8.0.2".parse::<IpAddr>().unwrap()
+    );
+}
+
+#[test]
+fn test_prefix_preserving_ip_masks() {
+    assert_eq!(
+        StatefulSdpAnonymizer::new()
+            .set_ip_masking_mode(IpMaskingMode::PrefixPreserving)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::AnonymizerMissingKey
+    );
+    let mut anon = StatefulSdpAnonymizer::with_key([9u8; 16]);
+    anon.set_ip_masking_mode(IpMaskingMode::PrefixPreserving)
+        .unwrap();
+    let v4 = |anon: &mut StatefulSdpAnonymizer, ip: &str| match anon.mask_ip(&ip.parse().unwrap()) {
+        IpAddr::V4(ip) => u32::from(ip),
+        IpAddr::V6(_) => panic!(),
+    };
+    let first = v4(&mut anon, "192.0.2.1");
+    let second = v4(&mut anon, "192.0.2.2");
+    let other = v4(&mut anon, "198.51.100.1");
+    assert_ne!(first, u32::from(Ipv4Addr::new(192, 0, 2, 1)));
+    // 192.0.2.1 and 192.0.2.2 share 30 bits, 192.0.2.1 and 198.51.100.1 5
+    assert_eq!((first ^ second).leading_zeros(), 30);
+    assert_eq!((first ^ other).leading_zeros(), 5);
+
+    let v6 = |anon: &mut StatefulSdpAnonymizer, ip: &str| match anon.mask_ip(&ip.parse().unwrap()) {
+        IpAddr::V6(ip) => u128::from(ip),
+        IpAddr::V4(_) => panic!(),
+    };
+    let first = v6(&mut anon, "2001:db8:1::1");
+    let second = v6(&mut anon, "2001:db8:1::2");
+    assert_eq!((first ^ second).leading_zeros(), 126);
+
+    // All masking of addresses uses the mode
+    let mut text_anon = StatefulSdpAnonymizer::with_key([9u8; 16]);
+    text_anon
+        .set_ip_masking_mode(IpMaskingMode::PrefixPreserving)
+        .unwrap();
+    let masked = text_anon.mask_sdp_text("c=IN IP4 192.0.2.1\r\n");
+    assert_eq!(
+        masked,
+        format!(
+            "c=IN IP4 {}\r\n",
+            Ipv4Addr::from(v4(&mut anon, "192.0.2.1"))
+        )
+    );
+}
+
+#[test]
+fn test_unmask() {
+    let mut anon = StatefulSdpAnonymizer::new();
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 3));
+    let masked = anon.mask_ip(&ip);
+    assert_eq!(anon.unmask_ip(&masked), Some(ip));
+    assert_eq!(anon.unmask_ip(&ip), None);
+
+    let masked = anon.mask_port(5000);
+    assert_eq!(anon.unmask_port(masked), Some(5000));
+    let masked = anon.mask_host("example.com");
+    assert_eq!(anon.unmask_host(&masked), Some("example.com".to_string()));
+    let masked = anon.mask_host("1f4712db-ea17-4bcf-a596-105139dfd8bf.local");
+    assert_eq!(
+        anon.unmask_address(&Address::Fqdn(masked)),
+        Some(Address::Fqdn(
+            "1f4712db-ea17-4bcf-a596-105139dfd8bf.local".to_string()
+        ))
+    );
+    let masked = anon.mask_cert_finger_print(&[1, 2, 3]);
+    assert_eq!(anon.unmask_cert_finger_print(&masked), Some(vec![1, 2, 3]));
+    let masked = anon.mask_ice_user("user");
+    assert_eq!(anon.unmask_ice_user(&masked), Some("user".to_string()));
+    assert_eq!(anon.unmask_cname(&masked), None);
+
+    let mut keyed = StatefulSdpAnonymizer::with_key([1u8; 16]);
+    let masked = keyed.mask_host("1f4712db-ea17-4bcf-a596-105139dfd8bf.local");
+    assert_eq!(
+        keyed.unmask_host(&masked),
+        Some("1f4712db-ea17-4bcf-a596-105139dfd8bf.local".to_string())
+    );
+}
+
+#[test]
+fn test_export_and_merge_state() {
+    let mut anon = StatefulSdpAnonymizer::new();
+    let first = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    let masked_first = anon.mask_ip(&first);
+    anon.mask_cname("cname");
+    let state = anon.export_state();
+    assert_eq!(state.ips, vec![(first, masked_first)]);
+    assert_eq!(state.ip_v4_counter, 1);
+
+    // A restored anonymizer keeps masking consistently
+    let mut restored = StatefulSdpAnonymizer::from_state(&state, None).unwrap();
+    assert_eq!(restored.export_state(), state);
+    assert_eq!(restored.mask_ip(&first), masked_first);
+    let second = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+    assert_eq!(anon.mask_ip(&second), restored.mask_ip(&second));
+
+    // Unkeyed anonymizers of different sessions reuse the same masks
+    let mut other = StatefulSdpAnonymizer::new();
+    other.mask_ip(&IpAddr::V4(Ipv4Addr::new(198, 51, 100, 1)));
+    assert_eq!(
+        anon.merge_state(&other.export_state()).unwrap_err().kind(),
+        SdpParserErrorKind::AnonymizerStateConflict
+    );
+    assert_eq!(anon.export_state().ips.len(), 2);
+
+    let mut first_keyed = StatefulSdpAnonymizer::with_key([3u8; 16]);
+    let mut second_keyed = StatefulSdpAnonymizer::with_key([3u8; 16]);
+    let masked_first = first_keyed.mask_ip(&first);
+    first_keyed.mask_ice_user("user");
+    let masked_second = second_keyed.mask_ip(&second);
+    second_keyed.mask_ice_user("user");
+    first_keyed
+        .merge_state(&second_keyed.export_state())
+        .unwrap();
+    assert_eq!(first_keyed.unmask_ip(&masked_first), Some(first));
+    assert_eq!(first_keyed.unmask_ip(&masked_second), Some(second));
+    assert_eq!(first_keyed.export_state().ice_users.len(), 1);
+}
+
+#[test]
+fn test_merge_state_of_namespaces() {
+    assert!(StatefulSdpAnonymizer::with_namespace(MAX_ANONYMIZER_NAMESPACE + 1).is_err());
+    let mut first = StatefulSdpAnonymizer::new();
+    let mut second = StatefulSdpAnonymizer::with_namespace(1).unwrap();
+    let first_ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    let second_ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+    assert_eq!(first.mask_ip(&first_ip), Ipv4Addr::new(0, 0, 0, 1));
+    assert_eq!(second.mask_ip(&second_ip), Ipv4Addr::new(1, 0, 0, 1));
+    assert_eq!(first.mask_port(5000), 1);
+    assert_eq!(second.mask_port(5002), 1001);
+    assert_eq!(first.mask_ice_user("first"), "ice-user-00000001");
+    assert_eq!(second.mask_ice_user("second"), "ice-user-01000001");
+
+    first.merge_state(&second.export_state()).unwrap();
+    assert_eq!(
+        first.unmask_ip(&Ipv4Addr::new(1, 0, 0, 1).into()),
+        Some(second_ip)
+    );
+    assert_eq!(first.unmask_port(1001), Some(5002));
+    assert_eq!(
+        first.unmask_ice_user("ice-user-01000001"),
+        Some("second".to_string())
+    );
+    // The counters of the own namespace are kept
+    assert_eq!(first.mask_ice_user("third"), "ice-user-00000002");
+
+    let restored = StatefulSdpAnonymizer::from_state(&second.export_state(), None).unwrap();
+    assert_eq!(restored.export_state(), second.export_state());
+}
+
+#[test]
+fn test_from_state_restores_key_and_configuration() {
+    let key = [5u8; 16];
+    let mut anon = StatefulSdpAnonymizer::with_key(key);
+    anon.set_ip_masking_mode(IpMaskingMode::PrefixPreserving)
+        .unwrap();
+    anon.set_policy(AnonymizationPolicy::mask_all());
+    let ip = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+    anon.mask_ip(&ip);
+    let state = anon.export_state();
+
+    assert_eq!(
+        StatefulSdpAnonymizer::from_state(&state, None)
+            .err()
+            .map(|error| error.kind()),
+        Some(SdpParserErrorKind::AnonymizerKeyMismatch)
+    );
+    assert!(StatefulSdpAnonymizer::from_state(&state, Some([6u8; 16])).is_err());
+
+    let mut restored = StatefulSdpAnonymizer::from_state(&state, Some(key)).unwrap();
+    assert_eq!(restored.policy(), &AnonymizationPolicy::mask_all());
+    assert_eq!(restored.export_state(), state);
+    let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 7));
+    assert_eq!(restored.mask_ip(&other), anon.mask_ip(&other));
+}
+
+const TEXT_SDP: &str = "v=0\r\n\
+                        o=alice 1 1 IN IP4 198.51.100.1\r\n\
+                        s=Team call\r\n\
+                        t=0 0\r\n\
+                        c=IN IP4 233.252.0.2/127\r\n\
+                        a=group:BUNDLE audio\r\n\
+                        a=msid-semantic:WMS stream\r\n\
+                        a=identity:YXNzZXJ0aW9u\r\n\
+                        a=fingerprint:sha-1 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC\r\n\
+                        a=ice-ufrag:4a799b2e\r\n\
+                        a=ice-pwd:e4cc12a910f106a0a744719425510e17\r\n\
+                        m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                        c=IN IP6 2001:db8::1\r\n\
+                        a=rtcp:9 IN IP6 2001:db8::1\r\n\
+                        a=candidate:1 1 UDP 2122252543 192.168.1.2 50000 typ host ufrag 4a799b2e\r\n\
+                        a=candidate:2 1 UDP 1686052607 203.0.113.7 50001 typ srflx raddr 192.168.1.2 rport 50000\r\n\
+                        a=candidate:3 1 UDP 1686052606 203.0.113.8 50002 typ host x-secret hidden\r\n\
+                        a=remote-candidates:1 203.0.113.9 6000\r\n\
+                        a=mid:audio\r\n\
+                        a=msid:stream track\r\n\
+                        a=label:microphone\r\n\
+                        a=rid:hi send pt=111;max-br=64000;depends=lo\r\n\
+                        a=rid:lo send\r\n\
+                        a=simulcast:send hi;~lo\r\n\
+                        a=ssrc:1 cname:alice@example.com\r\n\
+                        a=ssrc:1 msid:stream track\r\n\
+                        a=ssrc:1 label:microphone\r\n\
+                        a=ssrc:1 x-note:private\r\n\
+                        a=rtpmap:111 opus/48000/2\r\n";
+
+#[test]
+fn test_mask_sdp_text_matches_masked_clone() {
+    let session = parse_sdp(TEXT_SDP, true).unwrap();
+    let masked_session = session.masked_clone(&mut StatefulSdpAnonymizer::new());
+
+    // Masked fingerprints are shorter than real ones and fail to parse
+    let masked_text = StatefulSdpAnonymizer::new().mask_sdp_text(TEXT_SDP);
+    assert_eq!(masked_text, masked_session.to_string());
+    assert!(masked_text.contains("c=IN IP4 0.0.0.2\r\n"));
+    assert!(session.connection.as_ref().unwrap().validate().is_ok());
+    assert!(masked_session
+        .connection
+        .as_ref()
+        .unwrap()
+        .validate()
+        .is_ok());
+    assert!(masked_text.contains("a=rtpmap:111 opus/48000/2\r\n"));
+
+    let mut text_anon = StatefulSdpAnonymizer::with_key([5u8; 16]);
+    let mut session_anon = StatefulSdpAnonymizer::with_key([5u8; 16]);
+    assert_eq!(
+        text_anon.mask_sdp_text(TEXT_SDP),
+        session.masked_clone(&mut session_anon).to_string()
+    );
+
+    for policy in &[
+        AnonymizationPolicy::keep_all(),
+        AnonymizationPolicy::mask_all(),
+        AnonymizationPolicy::redact_all(),
+    ] {
+        let mut text_anon = StatefulSdpAnonymizer::new();
+        let mut session_anon = StatefulSdpAnonymizer::new();
+        text_anon.set_policy(policy.clone());
+        session_anon.set_policy(policy.clone());
+        assert_eq!(
+            text_anon.mask_sdp_text(TEXT_SDP),
+            session.masked_clone(&mut session_anon).to_string()
+        );
+    }
+
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::mask_all());
+    let masked_text = anon.mask_sdp_text(TEXT_SDP);
+    for original in &[
+        "Team call",
+        "audio\r\n",
+        "stream",
+        "YXNzZXJ0aW9u",
+        "microphone",
+        "hi",
+        "hidden",
+        "private",
+    ] {
+        assert!(
+            !masked_text.contains(original),
+            "{} in {}",
+            original,
+            masked_text
+        );
+    }
+}
+
+#[test]
+fn test_mask_sdp_text_unparseable_lines() {
+    let mut anon = StatefulSdpAnonymizer::new();
+    let text = "o=alice 1 1 IN IP4 198.51.100.1\n\
+                a=candidate:1 1 UDP 99999999999 192.168.1.2 50000 typ host\n\
+                a=x-custom:relay 203.0.113.7/24 via 198.51.100.1\n\
+                garbage 203.0.113.7";
+    let masked = anon.mask_sdp_text(text);
+    assert_eq!(
+        masked,
+        "o=origin-user-00000001 1 1 IN IP4 0.0.0.1\n\
+         a=candidate:1 1 UDP 99999999999 0.0.0.2 1 typ host\n\
+         a=x-custom:relay 0.0.0.3/24 via 0.0.0.1\n\
+         garbage 0.0.0.3"
+    );
+    assert!(parse_sdp(text, false).is_err());
+
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::redact_all());
+    let masked = anon.mask_sdp_text(TEXT_SDP);
+    assert!(masked.contains("o=- 1 1 IN IP4 0.0.0.0\r\n"));
+    assert!(masked.contains("c=IN IP6 ::\r\n"));
+    assert!(!masked.contains("a=candidate"));
+    assert!(!masked.contains("a=ice-"));
+    assert!(!masked.contains("a=fingerprint"));
+    assert!(!masked.contains("a=ssrc"));
+    assert!(parse_sdp(&masked, true).is_ok());
+}
+
+#[test]
+fn test_mask_source_filter() {
+    let filter = "a=source-filter: incl IN IP4 232.3.4.5 192.0.2.10 192.0.2.11\r\n";
+    let masked_text = StatefulSdpAnonymizer::new().mask_sdp_text(filter);
+    assert_eq!(
+        masked_text,
+        "a=source-filter: incl IN IP4 0.0.0.1 0.0.0.2 0.0.0.3\r\n"
+    );
+
+    let attribute = parse_attribute("source-filter:excl IN * * 192.0.2.10").unwrap();
+    if let SdpType::Attribute(attribute) = attribute {
+        let masked = attribute.masked_clone(&mut StatefulSdpAnonymizer::new());
+        assert_eq!(masked.to_string(), "source-filter:excl IN * * 0.0.0.1");
+        assert!(attribute.redacted_by(&AnonymizationPolicy::redact_all()));
+    } else {
+        unreachable!();
+    }
+
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::redact_all());
+    assert_eq!(anon.mask_sdp_text(filter), "");
+}
@@ -8,12 +8,15 @@ use std::fmt;
 use std::iter;
 use std::str::FromStr;
 
-use error::SdpParserInternalError;
+use error::{SdpParserErrorKind, SdpParserInternalError};
 use network::{parse_network_type, parse_unicast_address};
 use SdpType;
 
 use address::{Address, AddressType, ExplicitlyTypedAddress};
-use anonymizer::{AnonymizingClone, StatefulSdpAnonymizer};
+use anonymizer::{
+    redacted_address, redacted_typed_address, AnonymizationAction, AnonymizationPolicy,
+    AnonymizingClone, StatefulSdpAnonymizer,
+};
 
 // Serialization helper marcos and functions
 #[macro_export]
@@ -78,6 +81,14 @@ where
     }
 }
 
+fn maybe_print_flag(name: &str, flag: bool) -> String {
+    if flag {
+        name.to_string()
+    } else {
+        "".to_string()
+    }
+}
+
 pub fn maybe_print_bool_param(name: &str, param: bool, default_value: bool) -> String {
     if param != default_value {
         name.to_owned() + "=" + &(param as i32).to_string()
@@ -87,13 +98,22 @@ pub fn maybe_print_bool_param(name: &str, param: bool, default_value: bool) -> S
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpSingleDirection {
     // This is explicitly 1 and 2 to match the defines in the C++ glue code.
     Send = 1,
     Recv = 2,
 }
 
+impl SdpSingleDirection {
+    pub fn reverse(&self) -> SdpSingleDirection {
+        match *self {
+            SdpSingleDirection::Send => SdpSingleDirection::Recv,
+            SdpSingleDirection::Recv => SdpSingleDirection::Send,
+        }
+    }
+}
+
 impl fmt::Display for SdpSingleDirection {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -105,7 +125,7 @@ impl fmt::Display for SdpSingleDirection {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributePayloadType {
     PayloadType(u8),
     Wildcard, // Wildcard means "*",
@@ -121,7 +141,7 @@ impl fmt::Display for SdpAttributePayloadType {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeCandidateTransport {
     Udp,
     Tcp,
@@ -138,7 +158,7 @@ impl fmt::Display for SdpAttributeCandidateTransport {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeCandidateType {
     Host,
     Srflx,
@@ -159,7 +179,7 @@ impl fmt::Display for SdpAttributeCandidateType {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeCandidateTcpType {
     Active,
     Passive,
@@ -178,7 +198,7 @@ impl fmt::Display for SdpAttributeCandidateTcpType {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeCandidate {
     pub foundation: String,
@@ -221,7 +241,7 @@ impl fmt::Display for SdpAttributeCandidate {
             unknown = self
                 .unknown_extensions
                 .iter()
-                .map(|&(ref name, ref value)| format!(" {} {}", name, value))
+                .map(|(name, value)| format!(" {} {}", name, value))
                 .collect::<String>()
         )
     }
@@ -286,20 +306,49 @@ impl SdpAttributeCandidate {
 
 impl AnonymizingClone for SdpAttributeCandidate {
     fn masked_clone(&self, anonymizer: &mut StatefulSdpAnonymizer) -> Self {
+        let policy = anonymizer.policy().clone();
         let mut masked = self.clone();
-        masked.address = anonymizer.mask_address(&self.address);
-        masked.port = anonymizer.mask_port(self.port);
-        masked.raddr = self
-            .raddr
-            .clone()
-            .map(|addr| anonymizer.mask_address(&addr));
-        masked.rport = self.rport.map(|port| anonymizer.mask_port(port));
+        masked.address = policy.addresses.apply(
+            &self.address,
+            |addr| anonymizer.mask_address(addr),
+            redacted_address(&self.address),
+        );
+        masked.port = policy
+            .ports
+            .apply(&self.port, |port| anonymizer.mask_port(*port), 9);
+        masked.raddr = self.raddr.as_ref().map(|raddr| {
+            policy.addresses.apply(
+                raddr,
+                |addr| anonymizer.mask_address(addr),
+                redacted_address(raddr),
+            )
+        });
+        masked.rport = self.rport.map(|rport| {
+            policy
+                .ports
+                .apply(&rport, |port| anonymizer.mask_port(*port), 9)
+        });
+        masked.ufrag = match policy.ice_credentials {
+            AnonymizationAction::Redact => None,
+            action => self.ufrag.as_ref().map(|ufrag| {
+                action.apply(ufrag, |user| anonymizer.mask_ice_user(user), String::new())
+            }),
+        };
+        masked.unknown_extensions = match policy.unknown_values {
+            AnonymizationAction::Keep => self.unknown_extensions.clone(),
+            AnonymizationAction::Mask => self
+                .unknown_extensions
+                .iter()
+                .map(|(name, value)| (name.clone(), anonymizer.mask_unknown_value(value)))
+                .collect(),
+            AnonymizationAction::Redact => Vec::new(),
+        };
         masked
     }
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeDtlsMessage {
     Client(String),
@@ -317,7 +366,7 @@ impl fmt::Display for SdpAttributeDtlsMessage {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRemoteCandidate {
     pub component: u32,
@@ -340,15 +389,22 @@ impl fmt::Display for SdpAttributeRemoteCandidate {
 impl AnonymizingClone for SdpAttributeRemoteCandidate {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
         SdpAttributeRemoteCandidate {
-            address: anon.mask_address(&self.address),
-            port: anon.mask_port(self.port),
+            address: anon.policy().addresses.apply(
+                &self.address,
+                |addr| anon.mask_address(addr),
+                redacted_address(&self.address),
+            ),
+            port: anon
+                .policy()
+                .ports
+                .apply(&self.port, |port| anon.mask_port(*port), 9),
             component: self.component,
         }
     }
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcastId {
     pub id: String,
@@ -382,7 +438,7 @@ impl fmt::Display for SdpAttributeSimulcastId {
 
 #[repr(C)]
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcastVersion {
     pub ids: Vec<SdpAttributeSimulcastId>,
@@ -411,7 +467,7 @@ impl fmt::Display for SdpAttributeSimulcastVersion {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcast {
     pub send: Vec<SdpAttributeSimulcastVersion>,
@@ -430,7 +486,7 @@ impl fmt::Display for SdpAttributeSimulcast {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtcp {
     pub port: u16,
@@ -459,8 +515,214 @@ impl fmt::Display for SdpAttributeRtcp {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpSourceFilterMode {
+    Include,
+    Exclude,
+}
+
+impl fmt::Display for SdpSourceFilterMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpSourceFilterMode::Include => "incl",
+            SdpSourceFilterMode::Exclude => "excl",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpT38FaxRateManagement {
+    LocalTcf,
+    TransferredTcf,
+}
+
+impl fmt::Display for SdpT38FaxRateManagement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpT38FaxRateManagement::LocalTcf => "localTCF",
+            SdpT38FaxRateManagement::TransferredTcf => "transferredTCF",
+        }
+        .fmt(f)
+    }
+}
+
+// Ordered from the weakest to the strongest error correction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpT38FaxUdpEc {
+    NoEc,
+    Redundancy,
+    Fec,
+}
+
+impl fmt::Display for SdpT38FaxUdpEc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpT38FaxUdpEc::NoEc => "t38UDPNoEC",
+            SdpT38FaxUdpEc::Redundancy => "t38UDPRedundancy",
+            SdpT38FaxUdpEc::Fec => "t38UDPFEC",
+        }
+        .fmt(f)
+    }
+}
+
+/*
+ * RFC 7273 section 4.8
+ * The reference clock of the RTP timestamps. PTP grandmasters are EUI-64s
+ * like 39-A7-94-FF-FE-07-CB-D0, a missing one stands for "traceable".
+ * SMPTE ST 2110-10 adds localmac, the MAC address of a free running sender.
+ */
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpAttributeTsRefclk {
+    Ntp(String),
+    Ptp {
+        version: String,
+        grandmaster: Option<String>,
+        domain: Option<String>,
+    },
+    Gps,
+    Gal,
+    Glonass,
+    Local,
+    Private {
+        traceable: bool,
+    },
+    LocalMac(String),
+    Extension {
+        name: String,
+        value: Option<String>,
+    },
+}
+
+impl fmt::Display for SdpAttributeTsRefclk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpAttributeTsRefclk::Ntp(ref server) => write!(f, "ntp={}", server),
+            SdpAttributeTsRefclk::Ptp {
+                ref version,
+                ref grandmaster,
+                ref domain,
+            } => write!(
+                f,
+                "ptp={}:{}{}",
+                version,
+                grandmaster.as_deref().unwrap_or("traceable"),
+                option_to_string!(":{}", domain)
+            ),
+            SdpAttributeTsRefclk::Gps => "gps".fmt(f),
+            SdpAttributeTsRefclk::Gal => "gal".fmt(f),
+            SdpAttributeTsRefclk::Glonass => "glonass".fmt(f),
+            SdpAttributeTsRefclk::Local => "local".fmt(f),
+            SdpAttributeTsRefclk::Private { traceable } => {
+                write!(f, "private{}", if traceable { ":traceable" } else { "" })
+            }
+            SdpAttributeTsRefclk::LocalMac(ref mac) => write!(f, "localmac={}", mac),
+            SdpAttributeTsRefclk::Extension {
+                ref name,
+                ref value,
+            } => write!(f, "{}{}", name, option_to_string!("={}", value)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpMediaClockSource {
+    Sender,
+    // The RTP timestamp of the reference clock epoch, and the clock rate
+    Direct {
+        offset: Option<u64>,
+        rate: Option<SdpFrameRate>,
+    },
+    // An IEEE 1722 stream id
+    Ieee1722(String),
+    Extension(String),
+}
+
+impl fmt::Display for SdpMediaClockSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpMediaClockSource::Sender => "sender".fmt(f),
+            SdpMediaClockSource::Direct { offset, rate } => write!(
+                f,
+                "direct{}{}",
+                option_to_string!("={}", offset),
+                match rate {
+                    Some(rate) => format!(" rate={}/{}", rate.numerator, rate.denominator),
+                    None => "".to_string(),
+                }
+            ),
+            SdpMediaClockSource::Ieee1722(ref stream) => write!(f, "IEEE1722={}", stream),
+            SdpMediaClockSource::Extension(ref x) => x.fmt(f),
+        }
+    }
+}
+
+/*
+ * RFC 7273 section 5
+ * How the media clock relates to the reference clock, e.g. direct=0 for
+ * ST 2110 senders.
+ */
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpAttributeMediaClk {
+    pub id: Option<String>,
+    pub source: SdpMediaClockSource,
+}
+
+impl fmt::Display for SdpAttributeMediaClk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", option_to_string!("id={} ", self.id), self.source)
+    }
+}
+
+/*
+ * RFC 4570
+ * An address type or destination of None is the * wildcard, which matches
+ * all address types or all destinations of the c= lines.
+ */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpAttributeSourceFilter {
+    pub mode: SdpSourceFilterMode,
+    pub address_type: Option<AddressType>,
+    pub destination: Option<Address>,
+    pub sources: Vec<Address>,
+}
+
+impl fmt::Display for SdpAttributeSourceFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{mode} IN {address_type} {destination} {sources}",
+            mode = self.mode,
+            address_type = self
+                .address_type
+                .map_or_else(|| "*".to_string(), |t| t.to_string()),
+            destination = self
+                .destination
+                .as_ref()
+                .map_or_else(|| "*".to_string(), ToString::to_string),
+            sources = self
+                .sources
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+                .join(" ")
+        )
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeRtcpFbType {
     Ack = 0,
@@ -488,7 +750,7 @@ impl fmt::Display for SdpAttributeRtcpFbType {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtcpFb {
     pub payload_type: SdpAttributePayloadType,
@@ -513,7 +775,7 @@ impl fmt::Display for SdpAttributeRtcpFb {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeDirection {
     Recvonly,
@@ -533,7 +795,7 @@ impl fmt::Display for SdpAttributeDirection {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeExtmap {
     pub id: u16,
@@ -556,7 +818,7 @@ impl fmt::Display for SdpAttributeExtmap {
 }
 
 #[derive(Clone, Copy)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct RtxFmtpParameters {
     pub apt: u8,
@@ -573,8 +835,197 @@ impl fmt::Display for RtxFmtpParameters {
     }
 }
 
+// An integer or a ratio like 30000/1001
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpFrameRate {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl fmt::Display for SdpFrameRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl FromStr for SdpFrameRate {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = match s.find('/') {
+            Some(i) => (s[..i].parse::<u32>()?, s[i + 1..].parse::<u32>()?),
+            None => (s.parse::<u32>()?, 1),
+        };
+        if numerator == 0 || denominator == 0 {
+            return Err(SdpParserInternalError::Generic(format!(
+                "Frame rate {} must be positive",
+                s
+            ))
+            .with_kind(SdpParserErrorKind::FmtpInvalidParameter));
+        }
+        Ok(SdpFrameRate {
+            numerator,
+            denominator,
+        })
+    }
+}
+
+// The bits per sample, e.g. 10, or 16f for 16 bit floating point samples
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpSampleDepth {
+    Integer(u8),
+    Float(u8),
+}
+
+impl Default for SdpSampleDepth {
+    fn default() -> Self {
+        SdpSampleDepth::Integer(0)
+    }
+}
+
+impl fmt::Display for SdpSampleDepth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpSampleDepth::Integer(bits) => write!(f, "{}", bits),
+            SdpSampleDepth::Float(bits) => write!(f, "{}f", bits),
+        }
+    }
+}
+
+impl FromStr for SdpSampleDepth {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_suffix('f') {
+            Some(bits) => Ok(SdpSampleDepth::Float(bits.parse::<u8>()?)),
+            None => Ok(SdpSampleDepth::Integer(s.parse::<u8>()?)),
+        }
+    }
+}
+
+/*
+ * SMPTE ST 2110-20 section 7
+ * The fmtp parameters of uncompressed video, e.g. a=rtpmap:96 raw/90000.
+ * Parameters of the payload format which are not listed here are kept in
+ * the unknown tokens of the fmtp.
+ */
+#[derive(Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct St2110VideoFmtpParameters {
+    pub sampling: String,
+    pub depth: SdpSampleDepth,
+    pub width: u32,
+    pub height: u32,
+    pub exactframerate: SdpFrameRate,
+    pub colorimetry: String,
+    // PM, the packing mode
+    pub packing_mode: Option<String>,
+    // SSN, the version of ST 2110-20
+    pub ssn: Option<String>,
+    // TCS, the transfer characteristic system
+    pub tcs: Option<String>,
+    pub range: Option<String>,
+    // PAR, the pixel aspect ratio
+    pub par: Option<String>,
+    // TP, the ST 2110-21 sender type
+    pub tp: Option<String>,
+    pub interlace: bool,
+    pub segmented: bool,
+}
+
+impl St2110VideoFmtpParameters {
+    // Returns false for parameters which ST 2110-20 does not define
+    fn parse_parameter(
+        &mut self,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<bool, SdpParserInternalError> {
+        let value = || {
+            value.ok_or_else(|| {
+                SdpParserInternalError::Generic(format!(
+                    "The fmtp parameter '{}' must have a value",
+                    name
+                ))
+                .with_kind(SdpParserErrorKind::FmtpInvalidParameter)
+            })
+        };
+        match name.to_uppercase().as_str() {
+            "SAMPLING" => self.sampling = value()?.to_string(),
+            "DEPTH" => self.depth = value()?.parse::<SdpSampleDepth>()?,
+            "WIDTH" => self.width = value()?.parse::<u32>()?,
+            "HEIGHT" => self.height = value()?.parse::<u32>()?,
+            "EXACTFRAMERATE" => self.exactframerate = value()?.parse::<SdpFrameRate>()?,
+            "COLORIMETRY" => self.colorimetry = value()?.to_string(),
+            "PM" => self.packing_mode = Some(value()?.to_string()),
+            "SSN" => self.ssn = Some(value()?.to_string()),
+            "TCS" => self.tcs = Some(value()?.to_string()),
+            "RANGE" => self.range = Some(value()?.to_string()),
+            "PAR" => self.par = Some(value()?.to_string()),
+            "TP" => self.tp = Some(value()?.to_string()),
+            // Flags which are set by being present
+            "INTERLACE" => self.interlace = true,
+            "SEGMENTED" => self.segmented = true,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    fn check_required_parameters(&self) -> Result<(), SdpParserInternalError> {
+        let missing = [
+            ("sampling", self.sampling.is_empty()),
+            ("depth", self.depth == SdpSampleDepth::default()),
+            ("width", self.width == 0),
+            ("height", self.height == 0),
+            (
+                "exactframerate",
+                self.exactframerate == SdpFrameRate::default(),
+            ),
+            ("colorimetry", self.colorimetry.is_empty()),
+        ];
+        match missing.iter().find(|(_, missing)| *missing) {
+            Some((name, _)) => Err(SdpParserInternalError::Generic(format!(
+                "ST 2110-20 video fmtp is missing the required parameter '{}'",
+                name
+            ))
+            .with_kind(SdpParserErrorKind::FmtpSt2110MissingParameter)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for St2110VideoFmtpParameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        non_empty_string_vec![
+            format!("sampling={}", self.sampling),
+            format!("width={}", self.width),
+            format!("height={}", self.height),
+            format!("exactframerate={}", self.exactframerate),
+            format!("depth={}", self.depth),
+            option_to_string!("TCS={}", self.tcs),
+            format!("colorimetry={}", self.colorimetry),
+            option_to_string!("PM={}", self.packing_mode),
+            option_to_string!("SSN={}", self.ssn),
+            option_to_string!("RANGE={}", self.range),
+            option_to_string!("PAR={}", self.par),
+            option_to_string!("TP={}", self.tp),
+            maybe_print_flag("interlace", self.interlace),
+            maybe_print_flag("segmented", self.segmented)
+        ]
+        .join("; ")
+        .fmt(f)
+    }
+}
+
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFmtpParameters {
     // H264
@@ -605,6 +1056,9 @@ pub struct SdpAttributeFmtpParameters {
     pub minptime: u32,
     pub maxptime: u32,
 
+    // T.140 https://tools.ietf.org/html/rfc4103
+    pub cps: u32,
+
     // Red
     pub encodings: Vec<u8>,
 
@@ -614,6 +1068,9 @@ pub struct SdpAttributeFmtpParameters {
     // RTX
     pub rtx: Option<RtxFmtpParameters>,
 
+    // SMPTE ST 2110-20 uncompressed video
+    pub st2110_video: Option<Box<St2110VideoFmtpParameters>>,
+
     // Unknown
     pub unknown_tokens: Vec<String>,
 }
@@ -624,6 +1081,13 @@ impl fmt::Display for SdpAttributeFmtpParameters {
             // rtx
             return write!(f, "{}", rtx);
         }
+        if let Some(ref video) = self.st2110_video {
+            // ST 2110 separates the parameters with "; "
+            let unknown_tokens: Vec<&str> = self.unknown_tokens.iter().map(|t| t.trim()).collect();
+            return non_empty_string_vec![video.to_string(), unknown_tokens.join("; ")]
+                .join("; ")
+                .fmt(f);
+        }
         if !self.dtmf_tones.is_empty() {
             // telephone-event
             return write!(f, "{}", self.dtmf_tones);
@@ -663,6 +1127,7 @@ impl fmt::Display for SdpAttributeFmtpParameters {
                 maybe_print_param("ptime=", self.ptime, 0),
                 maybe_print_param("minptime=", self.minptime, 0),
                 maybe_print_param("maxptime=", self.maxptime, 0),
+                maybe_print_param("cps=", self.cps, 0),
                 maybe_print_bool_param("usedtx", self.usedtx, false),
                 maybe_print_bool_param("stereo", self.stereo, false),
                 maybe_print_bool_param("useinbandfec", self.useinbandfec, false),
@@ -675,7 +1140,7 @@ impl fmt::Display for SdpAttributeFmtpParameters {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFmtp {
     pub payload_type: u8,
@@ -694,7 +1159,7 @@ impl fmt::Display for SdpAttributeFmtp {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeFingerprintHashType {
     Sha1,
@@ -715,7 +1180,8 @@ impl SdpAttributeFingerprintHashType {
             unknown => Err(SdpParserInternalError::Unsupported(format!(
                 "fingerprint contains an unsupported hash algorithm '{}'",
                 unknown
-            ))),
+            ))
+            .with_kind(SdpParserErrorKind::FingerprintUnsupportedHashAlgorithm)),
         }
     }
     pub fn octet_count(&self) -> usize {
@@ -735,7 +1201,8 @@ impl SdpAttributeFingerprintHashType {
                 if byte_token.len() != 2 {
                     return Err(SdpParserInternalError::Generic(
                         "fingerpint's byte tokens must have 2 hexdigits".to_string(),
-                    ));
+                    )
+                    .with_kind(SdpParserErrorKind::FingerprintInvalidByte));
                 }
                 Ok(u8::from_str_radix(byte_token, 16)?)
             })
@@ -746,7 +1213,8 @@ impl SdpAttributeFingerprintHashType {
                 "fingerprint has {} bytes but should have {} bytes",
                 bytes.len(),
                 self.octet_count(),
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::FingerprintWrongLength));
         }
 
         Ok(bytes)
@@ -767,7 +1235,7 @@ impl fmt::Display for SdpAttributeFingerprintHashType {
 }
 
 #[derive(Clone, PartialEq, Eq)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFingerprint {
     pub hash_algorithm: SdpAttributeFingerprintHashType,
@@ -788,7 +1256,8 @@ impl TryFrom<(SdpAttributeFingerprintHashType, Vec<u8>)> for SdpAttributeFingerp
             (a, b) => Err(SdpParserInternalError::Generic(format!(
                 "Hash algoritm expects {} fingerprint bytes not {}",
                 a, b
-            ))),
+            ))
+            .with_kind(SdpParserErrorKind::FingerprintWrongLength)),
         }
     }
 }
@@ -813,7 +1282,11 @@ impl AnonymizingClone for SdpAttributeFingerprint {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
         SdpAttributeFingerprint {
             hash_algorithm: self.hash_algorithm,
-            fingerprint: anon.mask_cert_finger_print(&self.fingerprint),
+            fingerprint: anon.policy().fingerprints.apply(
+                &self.fingerprint,
+                |fingerprint| anon.mask_cert_finger_print(fingerprint),
+                vec![0; self.fingerprint.len()],
+            ),
         }
     }
 }
@@ -836,7 +1309,7 @@ where
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeImageAttrXyRange {
     Range(u32, u32, Option<u32>), // min, max, step
     DiscreteValues(Vec<u32>),
@@ -860,7 +1333,7 @@ impl fmt::Display for SdpAttributeImageAttrXyRange {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeImageAttrSRange {
     Range(f32, f32), // min, max
     DiscreteValues(Vec<f32>),
@@ -878,7 +1351,7 @@ impl fmt::Display for SdpAttributeImageAttrSRange {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct SdpAttributeImageAttrPRange {
     pub min: f32,
     pub max: f32,
@@ -891,7 +1364,7 @@ impl fmt::Display for SdpAttributeImageAttrPRange {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct SdpAttributeImageAttrSet {
     pub x: SdpAttributeImageAttrXyRange,
     pub y: SdpAttributeImageAttrXyRange,
@@ -911,7 +1384,7 @@ impl fmt::Display for SdpAttributeImageAttrSet {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpAttributeImageAttrSetList {
     Sets(Vec<SdpAttributeImageAttrSet>),
     Wildcard,
@@ -932,7 +1405,7 @@ impl fmt::Display for SdpAttributeImageAttrSetList {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeImageAttr {
     pub pt: SdpAttributePayloadType,
@@ -956,7 +1429,7 @@ impl fmt::Display for SdpAttributeImageAttr {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSctpmap {
     pub port: u16,
@@ -975,7 +1448,7 @@ impl fmt::Display for SdpAttributeSctpmap {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeGroupSemantic {
     LipSynchronization,          // RFC5888
@@ -1003,7 +1476,7 @@ impl fmt::Display for SdpAttributeGroupSemantic {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeGroup {
     pub semantics: SdpAttributeGroupSemantic,
@@ -1022,7 +1495,7 @@ impl fmt::Display for SdpAttributeGroup {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeMsid {
     pub id: String,
@@ -1037,7 +1510,7 @@ impl fmt::Display for SdpAttributeMsid {
 }
 
 #[derive(Clone, Debug)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct SdpAttributeMsidSemantic {
     pub semantic: String,
     pub msids: Vec<String>,
@@ -1053,8 +1526,8 @@ impl fmt::Display for SdpAttributeMsidSemantic {
     }
 }
 
-#[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRidParameters {
     pub max_width: u32,
@@ -1067,6 +1540,27 @@ pub struct SdpAttributeRidParameters {
     pub unknown: Vec<String>,
 }
 
+impl SdpAttributeRidParameters {
+    // Combines two sets of restrictions into the stricter one. A value of 0
+    // means that the parameter does not restrict anything. Unknown
+    // parameters can't be honored by the other side and are dropped.
+    pub fn intersect(&self, other: &SdpAttributeRidParameters) -> SdpAttributeRidParameters {
+        let min_restriction = |a: u32, b: u32| match (a, b) {
+            (0, x) | (x, 0) => x,
+            (x, y) => x.min(y),
+        };
+        SdpAttributeRidParameters {
+            max_width: min_restriction(self.max_width, other.max_width),
+            max_height: min_restriction(self.max_height, other.max_height),
+            max_fps: min_restriction(self.max_fps, other.max_fps),
+            max_fs: min_restriction(self.max_fs, other.max_fs),
+            max_br: min_restriction(self.max_br, other.max_br),
+            max_pps: min_restriction(self.max_pps, other.max_pps),
+            unknown: Vec::new(),
+        }
+    }
+}
+
 impl fmt::Display for SdpAttributeRidParameters {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         non_empty_string_vec![
@@ -1084,7 +1578,7 @@ impl fmt::Display for SdpAttributeRidParameters {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRid {
     pub id: String,
@@ -1117,7 +1611,7 @@ impl fmt::Display for SdpAttributeRid {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtpmap {
     pub payload_type: u8,
@@ -1154,8 +1648,8 @@ impl fmt::Display for SdpAttributeRtpmap {
     }
 }
 
-#[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeSetup {
     Active,
@@ -1177,7 +1671,7 @@ impl fmt::Display for SdpAttributeSetup {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSsrc {
     pub id: u32,
@@ -1195,7 +1689,7 @@ impl SdpAttributeSsrc {
     }
 
     fn set_attribute(&mut self, a: &str) {
-        if a.find(':') == None {
+        if a.find(':').is_none() {
             self.attribute = Some(a.to_string());
         } else {
             let v: Vec<&str> = a.splitn(2, ':').collect();
@@ -1213,24 +1707,53 @@ impl fmt::Display for SdpAttributeSsrc {
     }
 }
 
+impl SdpAttributeSsrc {
+    // The anonymization policy category of the value
+    fn anonymization_action(&self, policy: &AnonymizationPolicy) -> AnonymizationAction {
+        match self.attribute.as_ref().map(|a| a.to_lowercase()) {
+            None => AnonymizationAction::Keep,
+            Some(attribute) => match attribute.as_str() {
+                "cname" => policy.cnames,
+                "msid" | "mslabel" => policy.msids,
+                "label" => policy.labels,
+                _ => policy.unknown_values,
+            },
+        }
+    }
+}
+
 impl AnonymizingClone for SdpAttributeSsrc {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
+        let action = self.anonymization_action(anon.policy());
+        let mask = |anon: &mut StatefulSdpAnonymizer, attribute: &str, value: &str| match attribute
+        {
+            "cname" => anon.mask_cname(value),
+            "msid" => value
+                .split(' ')
+                .map(|id| anon.mask_msid(id))
+                .collect::<Vec<String>>()
+                .join(" "),
+            "mslabel" => anon.mask_msid(value),
+            "label" => anon.mask_label(value),
+            _ => anon.mask_unknown_value(value),
+        };
         Self {
             id: self.id,
             attribute: self.attribute.clone(),
-            value: self.attribute.as_ref().and_then(|attribute| {
-                match (attribute.to_lowercase().as_str(), &self.value) {
-                    ("cname", Some(ref cname)) => Some(anon.mask_cname(cname.as_str())),
-                    (_, Some(_)) => self.value.clone(),
-                    (_, None) => None,
-                }
-            }),
+            value: match (&self.attribute, &self.value) {
+                (Some(attribute), Some(value)) => Some(action.apply(
+                    value,
+                    |value| mask(anon, &attribute.to_lowercase(), value),
+                    String::new(),
+                )),
+                (_, value) => value.clone(),
+            },
         }
     }
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpSsrcGroupSemantic {
     Duplication,              // RFC7104
@@ -1254,7 +1777,7 @@ impl fmt::Display for SdpSsrcGroupSemantic {
 }
 
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttribute {
     BundleOnly,
@@ -1278,6 +1801,7 @@ pub enum SdpAttribute {
     Label(String),
     MaxMessageSize(u64),
     MaxPtime(u64),
+    MediaClk(SdpAttributeMediaClk),
     Mid(String),
     Msid(SdpAttributeMsid),
     MsidSemantic(SdpAttributeMsidSemantic),
@@ -1297,8 +1821,20 @@ pub enum SdpAttribute {
     Sendrecv,
     Setup(SdpAttributeSetup),
     Simulcast(SdpAttributeSimulcast),
+    SourceFilter(SdpAttributeSourceFilter),
     Ssrc(SdpAttributeSsrc),
     SsrcGroup(SdpSsrcGroupSemantic, Vec<SdpAttributeSsrc>),
+    // ITU-T T.38 Annex D, the flags can be turned off with a value of 0
+    T38FaxFillBitRemoval(bool),
+    T38FaxMaxBuffer(u32),
+    T38FaxMaxDatagram(u32),
+    T38FaxRateManagement(SdpT38FaxRateManagement),
+    T38FaxTranscodingJbig(bool),
+    T38FaxTranscodingMmr(bool),
+    T38FaxUdpEc(SdpT38FaxUdpEc),
+    T38FaxVersion(u8),
+    T38MaxBitRate(u32),
+    TsRefclk(SdpAttributeTsRefclk),
 }
 
 impl SdpAttribute {
@@ -1327,7 +1863,16 @@ impl SdpAttribute {
             | SdpAttribute::SctpPort(..)
             | SdpAttribute::Simulcast(..)
             | SdpAttribute::Ssrc(..)
-            | SdpAttribute::SsrcGroup(..) => false,
+            | SdpAttribute::SsrcGroup(..)
+            | SdpAttribute::T38FaxFillBitRemoval(..)
+            | SdpAttribute::T38FaxMaxBuffer(..)
+            | SdpAttribute::T38FaxMaxDatagram(..)
+            | SdpAttribute::T38FaxRateManagement(..)
+            | SdpAttribute::T38FaxTranscodingJbig(..)
+            | SdpAttribute::T38FaxTranscodingMmr(..)
+            | SdpAttribute::T38FaxUdpEc(..)
+            | SdpAttribute::T38FaxVersion(..)
+            | SdpAttribute::T38MaxBitRate(..) => false,
 
             SdpAttribute::DtlsMessage { .. }
             | SdpAttribute::EndOfCandidates
@@ -1346,7 +1891,10 @@ impl SdpAttribute {
             | SdpAttribute::Recvonly
             | SdpAttribute::Sendonly
             | SdpAttribute::Sendrecv
-            | SdpAttribute::Setup(..) => true,
+            | SdpAttribute::MediaClk(..)
+            | SdpAttribute::Setup(..)
+            | SdpAttribute::SourceFilter(..)
+            | SdpAttribute::TsRefclk(..) => true,
         }
     }
 
@@ -1375,6 +1923,7 @@ impl SdpAttribute {
             | SdpAttribute::Label(..)
             | SdpAttribute::MaxMessageSize(..)
             | SdpAttribute::MaxPtime(..)
+            | SdpAttribute::MediaClk(..)
             | SdpAttribute::Mid(..)
             | SdpAttribute::Msid(..)
             | SdpAttribute::Ptime(..)
@@ -1393,8 +1942,19 @@ impl SdpAttribute {
             | SdpAttribute::Sendrecv
             | SdpAttribute::Setup(..)
             | SdpAttribute::Simulcast(..)
+            | SdpAttribute::SourceFilter(..)
             | SdpAttribute::Ssrc(..)
-            | SdpAttribute::SsrcGroup(..) => true,
+            | SdpAttribute::SsrcGroup(..)
+            | SdpAttribute::T38FaxFillBitRemoval(..)
+            | SdpAttribute::T38FaxMaxBuffer(..)
+            | SdpAttribute::T38FaxMaxDatagram(..)
+            | SdpAttribute::T38FaxRateManagement(..)
+            | SdpAttribute::T38FaxTranscodingJbig(..)
+            | SdpAttribute::T38FaxTranscodingMmr(..)
+            | SdpAttribute::T38FaxUdpEc(..)
+            | SdpAttribute::T38FaxVersion(..)
+            | SdpAttribute::T38MaxBitRate(..)
+            | SdpAttribute::TsRefclk(..) => true,
         }
     }
 }
@@ -1417,12 +1977,13 @@ impl FromStr for SdpAttribute {
                     return Err(SdpParserInternalError::Generic(format!(
                         "{} attribute is not allowed to have a value",
                         name
-                    )));
+                    ))
+                    .with_kind(SdpParserErrorKind::AttributeUnexpectedValue));
                 }
                 _ => (),
             }
         }
-        match name.as_str() {
+        let attribute = match name.as_str() {
             "bundle-only" => Ok(SdpAttribute::BundleOnly),
             "dtls-message" => parse_dtls_message(val),
             "end-of-candidates" => Ok(SdpAttribute::EndOfCandidates),
@@ -1437,6 +1998,7 @@ impl FromStr for SdpAttribute {
             "label" => Ok(SdpAttribute::Label(string_or_empty(val)?)),
             "max-message-size" => Ok(SdpAttribute::MaxMessageSize(val.parse()?)),
             "maxptime" => Ok(SdpAttribute::MaxPtime(val.parse()?)),
+            "mediaclk" => parse_mediaclk(val),
             "mid" => Ok(SdpAttribute::Mid(string_or_empty(val)?)),
             "msid-semantic" => parse_msid_semantic(val),
             "ptime" => Ok(SdpAttribute::Ptime(val.parse()?)),
@@ -1464,12 +2026,26 @@ impl FromStr for SdpAttribute {
             "sctpmap" => parse_sctpmap(val),
             "setup" => parse_setup(val),
             "simulcast" => parse_simulcast(val),
+            "source-filter" => parse_source_filter(val),
             "ssrc" => parse_ssrc(val),
+            "t38faxfillbitremoval" => parse_t38_flag(val).map(SdpAttribute::T38FaxFillBitRemoval),
+            "t38faxmaxbuffer" => Ok(SdpAttribute::T38FaxMaxBuffer(val.parse()?)),
+            "t38faxmaxdatagram" => Ok(SdpAttribute::T38FaxMaxDatagram(val.parse()?)),
+            "t38faxratemanagement" => parse_t38_rate_management(val),
+            "t38faxtranscodingjbig" => parse_t38_flag(val).map(SdpAttribute::T38FaxTranscodingJbig),
+            "t38faxtranscodingmmr" => parse_t38_flag(val).map(SdpAttribute::T38FaxTranscodingMmr),
+            "t38faxudpec" => parse_t38_udp_ec(val),
+            "t38faxversion" => parse_t38_version(val),
+            "t38maxbitrate" => parse_t38_max_bit_rate(val),
+            "ts-refclk" => parse_ts_refclk(val),
             _ => Err(SdpParserInternalError::Unsupported(format!(
                 "Unknown attribute type {}",
                 name
-            ))),
-        }
+            ))
+            .with_kind(SdpParserErrorKind::AttributeUnknown)),
+        };
+        // Locations of the value parsers are relative to the value
+        attribute.map_err(|e| e.rebase(line, val))
     }
 }
 
@@ -1499,6 +2075,7 @@ impl fmt::Display for SdpAttribute {
             SdpAttribute::Label(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MaxMessageSize(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MaxPtime(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::MediaClk(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Mid(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Msid(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MsidSemantic(ref a) => attr_to_string(a.to_string()),
@@ -1518,25 +2095,169 @@ impl fmt::Display for SdpAttribute {
             SdpAttribute::Sendrecv => SdpAttributeType::Sendrecv.to_string(),
             SdpAttribute::Setup(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Simulcast(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::SourceFilter(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Ssrc(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::SsrcGroup(ref a, ref ssrcs) => {
                 let stringified_ssrcs: Vec<String> =
                     ssrcs.iter().map(|ssrc| ssrc.to_string()).collect();
                 attr_to_string(a.to_string()) + " " + &stringified_ssrcs.join(" ")
             }
+            SdpAttribute::T38FaxFillBitRemoval(a)
+            | SdpAttribute::T38FaxTranscodingJbig(a)
+            | SdpAttribute::T38FaxTranscodingMmr(a) => {
+                if a {
+                    SdpAttributeType::from(self).to_string()
+                } else {
+                    attr_to_string("0".to_string())
+                }
+            }
+            SdpAttribute::T38FaxMaxBuffer(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::T38FaxMaxDatagram(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::T38FaxRateManagement(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::T38FaxUdpEc(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::T38FaxVersion(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::T38MaxBitRate(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::TsRefclk(ref a) => attr_to_string(a.to_string()),
         }
         .fmt(f)
     }
 }
 
+impl SdpAttribute {
+    /*
+     * Whether anonymized descriptions leave out the attribute, because it
+     * only carries values which the policy redacts.
+     */
+    pub fn redacted_by(&self, policy: &AnonymizationPolicy) -> bool {
+        let action = match *self {
+            SdpAttribute::Candidate(..)
+            | SdpAttribute::RemoteCandidate(..)
+            | SdpAttribute::SourceFilter(..) => policy.addresses,
+            SdpAttribute::Fingerprint(..) => policy.fingerprints,
+            SdpAttribute::IcePwd(..) | SdpAttribute::IceUfrag(..) => policy.ice_credentials,
+            SdpAttribute::Identity(..) => policy.identity,
+            SdpAttribute::Label(..) => policy.labels,
+            SdpAttribute::Group(..) | SdpAttribute::Mid(..) => policy.mids,
+            SdpAttribute::Msid(..) | SdpAttribute::MsidSemantic(..) => policy.msids,
+            SdpAttribute::Rid(..) | SdpAttribute::Simulcast(..) => policy.rids,
+            SdpAttribute::Ssrc(ref ssrc) => ssrc.anonymization_action(policy),
+            _ => AnonymizationAction::Keep,
+        };
+        action == AnonymizationAction::Redact
+    }
+}
+
+fn mask_simulcast_versions(
+    versions: &[SdpAttributeSimulcastVersion],
+    anon: &mut StatefulSdpAnonymizer,
+) -> Vec<SdpAttributeSimulcastVersion> {
+    versions
+        .iter()
+        .map(|version| SdpAttributeSimulcastVersion {
+            ids: version
+                .ids
+                .iter()
+                .map(|id| SdpAttributeSimulcastId {
+                    id: anon.mask_rid(&id.id),
+                    paused: id.paused,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
 impl AnonymizingClone for SdpAttribute {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
+        let policy = anon.policy().clone();
+        let redacted = "redacted".to_string();
         match self {
             SdpAttribute::Candidate(i) => SdpAttribute::Candidate(i.masked_clone(anon)),
             SdpAttribute::Fingerprint(i) => SdpAttribute::Fingerprint(i.masked_clone(anon)),
-            SdpAttribute::IcePwd(i) => SdpAttribute::IcePwd(anon.mask_ice_password(i)),
-            SdpAttribute::IceUfrag(i) => SdpAttribute::IceUfrag(anon.mask_ice_user(i)),
+            SdpAttribute::Group(i) if policy.mids == AnonymizationAction::Mask => {
+                SdpAttribute::Group(SdpAttributeGroup {
+                    semantics: i.semantics.clone(),
+                    tags: i.tags.iter().map(|tag| anon.mask_mid(tag)).collect(),
+                })
+            }
+            SdpAttribute::IcePwd(i) => SdpAttribute::IcePwd(policy.ice_credentials.apply(
+                i,
+                |pwd| anon.mask_ice_password(pwd),
+                redacted,
+            )),
+            SdpAttribute::IceUfrag(i) => SdpAttribute::IceUfrag(policy.ice_credentials.apply(
+                i,
+                |ufrag| anon.mask_ice_user(ufrag),
+                redacted,
+            )),
+            SdpAttribute::Identity(i) => SdpAttribute::Identity(policy.identity.apply(
+                i,
+                |identity| anon.mask_identity(identity),
+                redacted,
+            )),
+            SdpAttribute::Label(i) => SdpAttribute::Label(policy.labels.apply(
+                i,
+                |label| anon.mask_label(label),
+                redacted,
+            )),
+            SdpAttribute::Mid(i) => {
+                SdpAttribute::Mid(policy.mids.apply(i, |mid| anon.mask_mid(mid), redacted))
+            }
+            SdpAttribute::Msid(i) if policy.msids == AnonymizationAction::Mask => {
+                SdpAttribute::Msid(SdpAttributeMsid {
+                    id: anon.mask_msid(&i.id),
+                    appdata: i.appdata.as_ref().map(|appdata| anon.mask_msid(appdata)),
+                })
+            }
+            SdpAttribute::MsidSemantic(i) if policy.msids == AnonymizationAction::Mask => {
+                SdpAttribute::MsidSemantic(SdpAttributeMsidSemantic {
+                    semantic: i.semantic.clone(),
+                    msids: i
+                        .msids
+                        .iter()
+                        .map(|msid| match msid.as_str() {
+                            "*" => msid.clone(),
+                            _ => anon.mask_msid(msid),
+                        })
+                        .collect(),
+                })
+            }
             SdpAttribute::RemoteCandidate(i) => SdpAttribute::RemoteCandidate(i.masked_clone(anon)),
+            SdpAttribute::Rid(i) if policy.rids == AnonymizationAction::Mask => {
+                let mut masked = i.clone();
+                masked.id = anon.mask_rid(&i.id);
+                masked.depends = i.depends.iter().map(|rid| anon.mask_rid(rid)).collect();
+                SdpAttribute::Rid(masked)
+            }
+            SdpAttribute::Rtcp(i) => SdpAttribute::Rtcp(SdpAttributeRtcp {
+                port: i.port,
+                unicast_addr: i.unicast_addr.as_ref().map(|addr| {
+                    policy.addresses.apply(
+                        addr,
+                        |addr| anon.mask_typed_address(addr),
+                        redacted_typed_address(addr),
+                    )
+                }),
+            }),
+            SdpAttribute::Simulcast(i) if policy.rids == AnonymizationAction::Mask => {
+                SdpAttribute::Simulcast(SdpAttributeSimulcast {
+                    send: mask_simulcast_versions(&i.send, anon),
+                    receive: mask_simulcast_versions(&i.receive, anon),
+                })
+            }
+            SdpAttribute::SourceFilter(i) if policy.addresses == AnonymizationAction::Mask => {
+                SdpAttribute::SourceFilter(SdpAttributeSourceFilter {
+                    destination: i
+                        .destination
+                        .as_ref()
+                        .map(|destination| anon.mask_address(destination)),
+                    sources: i
+                        .sources
+                        .iter()
+                        .map(|source| anon.mask_address(source))
+                        .collect(),
+                    ..i.clone()
+                })
+            }
             SdpAttribute::Ssrc(i) => SdpAttribute::Ssrc(i.masked_clone(anon)),
             _ => self.clone(),
         }
@@ -1566,6 +2287,7 @@ pub enum SdpAttributeType {
     Label,
     MaxMessageSize,
     MaxPtime,
+    MediaClk,
     Mid,
     Msid,
     MsidSemantic,
@@ -1585,55 +2307,78 @@ pub enum SdpAttributeType {
     Sendrecv,
     Setup,
     Simulcast,
+    SourceFilter,
     Ssrc,
     SsrcGroup,
-}
-
-impl<'a> From<&'a SdpAttribute> for SdpAttributeType {
+    T38FaxFillBitRemoval,
+    T38FaxMaxBuffer,
+    T38FaxMaxDatagram,
+    T38FaxRateManagement,
+    T38FaxTranscodingJbig,
+    T38FaxTranscodingMmr,
+    T38FaxUdpEc,
+    T38FaxVersion,
+    T38MaxBitRate,
+    TsRefclk,
+}
+
+impl From<&SdpAttribute> for SdpAttributeType {
     fn from(other: &SdpAttribute) -> Self {
         match *other {
-            SdpAttribute::BundleOnly { .. } => SdpAttributeType::BundleOnly,
+            SdpAttribute::BundleOnly => SdpAttributeType::BundleOnly,
             SdpAttribute::Candidate { .. } => SdpAttributeType::Candidate,
             SdpAttribute::DtlsMessage { .. } => SdpAttributeType::DtlsMessage,
-            SdpAttribute::EndOfCandidates { .. } => SdpAttributeType::EndOfCandidates,
+            SdpAttribute::EndOfCandidates => SdpAttributeType::EndOfCandidates,
             SdpAttribute::Extmap { .. } => SdpAttributeType::Extmap,
-            SdpAttribute::ExtmapAllowMixed { .. } => SdpAttributeType::ExtmapAllowMixed,
+            SdpAttribute::ExtmapAllowMixed => SdpAttributeType::ExtmapAllowMixed,
             SdpAttribute::Fingerprint { .. } => SdpAttributeType::Fingerprint,
             SdpAttribute::Fmtp { .. } => SdpAttributeType::Fmtp,
             SdpAttribute::Group { .. } => SdpAttributeType::Group,
-            SdpAttribute::IceLite { .. } => SdpAttributeType::IceLite,
-            SdpAttribute::IceMismatch { .. } => SdpAttributeType::IceMismatch,
+            SdpAttribute::IceLite => SdpAttributeType::IceLite,
+            SdpAttribute::IceMismatch => SdpAttributeType::IceMismatch,
             SdpAttribute::IceOptions { .. } => SdpAttributeType::IceOptions,
             SdpAttribute::IcePacing { .. } => SdpAttributeType::IcePacing,
             SdpAttribute::IcePwd { .. } => SdpAttributeType::IcePwd,
             SdpAttribute::IceUfrag { .. } => SdpAttributeType::IceUfrag,
             SdpAttribute::Identity { .. } => SdpAttributeType::Identity,
             SdpAttribute::ImageAttr { .. } => SdpAttributeType::ImageAttr,
-            SdpAttribute::Inactive { .. } => SdpAttributeType::Inactive,
+            SdpAttribute::Inactive => SdpAttributeType::Inactive,
             SdpAttribute::Label { .. } => SdpAttributeType::Label,
             SdpAttribute::MaxMessageSize { .. } => SdpAttributeType::MaxMessageSize,
             SdpAttribute::MaxPtime { .. } => SdpAttributeType::MaxPtime,
+            SdpAttribute::MediaClk { .. } => SdpAttributeType::MediaClk,
             SdpAttribute::Mid { .. } => SdpAttributeType::Mid,
             SdpAttribute::Msid { .. } => SdpAttributeType::Msid,
             SdpAttribute::MsidSemantic { .. } => SdpAttributeType::MsidSemantic,
             SdpAttribute::Ptime { .. } => SdpAttributeType::Ptime,
             SdpAttribute::Rid { .. } => SdpAttributeType::Rid,
-            SdpAttribute::Recvonly { .. } => SdpAttributeType::Recvonly,
+            SdpAttribute::Recvonly => SdpAttributeType::Recvonly,
             SdpAttribute::RemoteCandidate { .. } => SdpAttributeType::RemoteCandidate,
             SdpAttribute::Rtcp { .. } => SdpAttributeType::Rtcp,
             SdpAttribute::Rtcpfb { .. } => SdpAttributeType::Rtcpfb,
-            SdpAttribute::RtcpMux { .. } => SdpAttributeType::RtcpMux,
-            SdpAttribute::RtcpMuxOnly { .. } => SdpAttributeType::RtcpMuxOnly,
-            SdpAttribute::RtcpRsize { .. } => SdpAttributeType::RtcpRsize,
+            SdpAttribute::RtcpMux => SdpAttributeType::RtcpMux,
+            SdpAttribute::RtcpMuxOnly => SdpAttributeType::RtcpMuxOnly,
+            SdpAttribute::RtcpRsize => SdpAttributeType::RtcpRsize,
             SdpAttribute::Rtpmap { .. } => SdpAttributeType::Rtpmap,
             SdpAttribute::Sctpmap { .. } => SdpAttributeType::Sctpmap,
             SdpAttribute::SctpPort { .. } => SdpAttributeType::SctpPort,
-            SdpAttribute::Sendonly { .. } => SdpAttributeType::Sendonly,
-            SdpAttribute::Sendrecv { .. } => SdpAttributeType::Sendrecv,
+            SdpAttribute::Sendonly => SdpAttributeType::Sendonly,
+            SdpAttribute::Sendrecv => SdpAttributeType::Sendrecv,
             SdpAttribute::Setup { .. } => SdpAttributeType::Setup,
             SdpAttribute::Simulcast { .. } => SdpAttributeType::Simulcast,
+            SdpAttribute::SourceFilter { .. } => SdpAttributeType::SourceFilter,
             SdpAttribute::Ssrc { .. } => SdpAttributeType::Ssrc,
             SdpAttribute::SsrcGroup { .. } => SdpAttributeType::SsrcGroup,
+            SdpAttribute::T38FaxFillBitRemoval { .. } => SdpAttributeType::T38FaxFillBitRemoval,
+            SdpAttribute::T38FaxMaxBuffer { .. } => SdpAttributeType::T38FaxMaxBuffer,
+            SdpAttribute::T38FaxMaxDatagram { .. } => SdpAttributeType::T38FaxMaxDatagram,
+            SdpAttribute::T38FaxRateManagement { .. } => SdpAttributeType::T38FaxRateManagement,
+            SdpAttribute::T38FaxTranscodingJbig { .. } => SdpAttributeType::T38FaxTranscodingJbig,
+            SdpAttribute::T38FaxTranscodingMmr { .. } => SdpAttributeType::T38FaxTranscodingMmr,
+            SdpAttribute::T38FaxUdpEc { .. } => SdpAttributeType::T38FaxUdpEc,
+            SdpAttribute::T38FaxVersion { .. } => SdpAttributeType::T38FaxVersion,
+            SdpAttribute::T38MaxBitRate { .. } => SdpAttributeType::T38MaxBitRate,
+            SdpAttribute::TsRefclk { .. } => SdpAttributeType::TsRefclk,
         }
     }
 }
@@ -1662,6 +2407,7 @@ impl fmt::Display for SdpAttributeType {
             SdpAttributeType::Label => "label",
             SdpAttributeType::MaxMessageSize => "max-message-size",
             SdpAttributeType::MaxPtime => "maxptime",
+            SdpAttributeType::MediaClk => "mediaclk",
             SdpAttributeType::Mid => "mid",
             SdpAttributeType::Msid => "msid",
             SdpAttributeType::MsidSemantic => "msid-semantic",
@@ -1681,18 +2427,41 @@ impl fmt::Display for SdpAttributeType {
             SdpAttributeType::Sendrecv => "sendrecv",
             SdpAttributeType::Setup => "setup",
             SdpAttributeType::Simulcast => "simulcast",
+            SdpAttributeType::SourceFilter => "source-filter",
             SdpAttributeType::Ssrc => "ssrc",
             SdpAttributeType::SsrcGroup => "ssrc-group",
+            SdpAttributeType::T38FaxFillBitRemoval => "T38FaxFillBitRemoval",
+            SdpAttributeType::T38FaxMaxBuffer => "T38FaxMaxBuffer",
+            SdpAttributeType::T38FaxMaxDatagram => "T38FaxMaxDatagram",
+            SdpAttributeType::T38FaxRateManagement => "T38FaxRateManagement",
+            SdpAttributeType::T38FaxTranscodingJbig => "T38FaxTranscodingJBIG",
+            SdpAttributeType::T38FaxTranscodingMmr => "T38FaxTranscodingMMR",
+            SdpAttributeType::T38FaxUdpEc => "T38FaxUdpEC",
+            SdpAttributeType::T38FaxVersion => "T38FaxVersion",
+            SdpAttributeType::T38MaxBitRate => "T38MaxBitRate",
+            SdpAttributeType::TsRefclk => "ts-refclk",
         }
         .fmt(f)
     }
 }
 
+// Parses a token of to_parse, locating any error at the token
+fn parse_token<T>(to_parse: &str, token: &str) -> Result<T, SdpParserInternalError>
+where
+    T: FromStr,
+    SdpParserInternalError: From<T::Err>,
+{
+    token
+        .parse::<T>()
+        .map_err(|e| SdpParserInternalError::from(e).located_in(to_parse, token))
+}
+
 fn string_or_empty(to_parse: &str) -> Result<String, SdpParserInternalError> {
     if to_parse.is_empty() {
         Err(SdpParserInternalError::Generic(
             "This attribute is required to have a value".to_string(),
-        ))
+        )
+        .with_kind(SdpParserErrorKind::AttributeMissingValue))
     } else {
         Ok(to_parse.to_string())
     }
@@ -1712,7 +2481,8 @@ fn parse_single_direction(to_parse: &str) -> Result<SdpSingleDirection, SdpParse
         x => Err(SdpParserInternalError::Generic(format!(
             "Unknown direction description found: '{:}'",
             x
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::DirectionUnknown)),
     }
 }
 
@@ -1726,7 +2496,8 @@ fn parse_ssrc_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Ssrc group attribute is missing semantics".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::SsrcGroupMissingSemantics));
         }
         Some(x) => match x.to_uppercase().as_ref() {
             "DUP" => SdpSsrcGroupSemantic::Duplication,
@@ -1738,7 +2509,8 @@ fn parse_ssrc_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
                 return Err(SdpParserInternalError::Unsupported(format!(
                     "Unknown ssrc semantic '{:?}' found",
                     unknown
-                )));
+                ))
+                .with_kind(SdpParserErrorKind::SsrcGroupUnknownSemantics));
             }
         },
     };
@@ -1759,7 +2531,8 @@ fn parse_ssrc_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     if ssrcs.is_empty() {
         return Err(SdpParserInternalError::Generic(
             "Ssrc group must contain at least one ssrc".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::SsrcGroupEmpty));
     }
 
     Ok(SdpAttribute::SsrcGroup(semantics, ssrcs))
@@ -1775,7 +2548,8 @@ fn parse_sctp_port(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         return Err(SdpParserInternalError::Generic(format!(
             "Sctpport port {} can only be a bit 16bit number",
             port
-        )));
+        ))
+        .with_kind(SdpParserErrorKind::SctpPortOutOfRange));
     }
     Ok(SdpAttribute::SctpPort(port))
 }
@@ -1811,32 +2585,39 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
     if tokens.len() < 8 {
         return Err(SdpParserInternalError::Generic(
             "Candidate needs to have minimum eigth tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::CandidateTooFewTokens));
     }
-    let component = tokens[1].parse::<u32>()?;
+    let component = parse_token::<u32>(to_parse, tokens[1])?;
     let transport = match tokens[2].to_lowercase().as_ref() {
         "udp" => SdpAttributeCandidateTransport::Udp,
         "tcp" => SdpAttributeCandidateTransport::Tcp,
         _ => {
             return Err(SdpParserInternalError::Generic(
                 "Unknonw candidate transport value".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::CandidateUnknownTransport)
+            .located_in(to_parse, tokens[2]));
         }
     };
-    let priority = tokens[3].parse::<u64>()?;
-    let address = Address::from_str(tokens[4])?;
-    let port = tokens[5].parse::<u32>()?;
+    let priority = parse_token::<u64>(to_parse, tokens[3])?;
+    let address = parse_token::<Address>(to_parse, tokens[4])?;
+    let port = parse_token::<u32>(to_parse, tokens[5])?;
     if port > 65535 {
         return Err(SdpParserInternalError::Generic(
             "ICE candidate port can only be a bit 16bit number".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::CandidatePortOutOfRange)
+        .located_in(to_parse, tokens[5]));
     }
     match tokens[6].to_lowercase().as_ref() {
         "typ" => (),
         _ => {
             return Err(SdpParserInternalError::Generic(
                 "Candidate attribute token must be 'typ'".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::CandidateMissingTyp)
+            .located_in(to_parse, tokens[6]));
         }
     };
     let cand_type = match tokens[7].to_lowercase().as_ref() {
@@ -1845,9 +2626,11 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         "prflx" => SdpAttributeCandidateType::Prflx,
         "relay" => SdpAttributeCandidateType::Relay,
         _ => {
-            return Err(SdpParserInternalError::Generic(
-                "Unknow candidate type value".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("Unknow candidate type value".to_string())
+                    .with_kind(SdpParserErrorKind::CandidateUnknownType)
+                    .located_in(to_parse, tokens[7]),
+            );
         }
     };
     let mut cand = SdpAttributeCandidate::new(
@@ -1864,26 +2647,29 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         while tokens.len() > index + 1 {
             match tokens[index].to_lowercase().as_ref() {
                 "generation" => {
-                    let generation = tokens[index + 1].parse::<u32>()?;
+                    let generation = parse_token::<u32>(to_parse, tokens[index + 1])?;
                     cand.set_generation(generation);
                     index += 2;
                 }
                 "network-cost" => {
-                    let cost = tokens[index + 1].parse::<u32>()?;
+                    let cost = parse_token::<u32>(to_parse, tokens[index + 1])?;
                     cand.set_network_cost(cost);
                     index += 2;
                 }
                 "raddr" => {
-                    let addr = parse_unicast_address(tokens[index + 1])?;
+                    let addr = parse_unicast_address(tokens[index + 1])
+                        .map_err(|e| e.located_in(to_parse, tokens[index + 1]))?;
                     cand.set_remote_address(addr);
                     index += 2;
                 }
                 "rport" => {
-                    let port = tokens[index + 1].parse::<u32>()?;
+                    let port = parse_token::<u32>(to_parse, tokens[index + 1])?;
                     if port > 65535 {
                         return Err(SdpParserInternalError::Generic(
                             "ICE candidate rport can only be a bit 16bit number".to_string(),
-                        ));
+                        )
+                        .with_kind(SdpParserErrorKind::CandidateRportOutOfRange)
+                        .located_in(to_parse, tokens[index + 1]));
                     }
                     cand.set_remote_port(port);
                     index += 2;
@@ -1896,7 +2682,9 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
                         _ => {
                             return Err(SdpParserInternalError::Generic(
                                 "Unknown tcptype value in candidate line".to_string(),
-                            ));
+                            )
+                            .with_kind(SdpParserErrorKind::CandidateUnknownTcpType)
+                            .located_in(to_parse, tokens[index + 1]));
                         }
                     });
                     index += 2;
@@ -1917,7 +2705,9 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         if tokens.len() > index {
             return Err(SdpParserInternalError::Unsupported(
                 "Ice candidate extension name without value".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::CandidateExtensionWithoutValue)
+            .located_in(to_parse, tokens[index]));
         }
     }
     Ok(SdpAttribute::Candidate(cand))
@@ -1940,7 +2730,8 @@ fn parse_dtls_message(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalE
     if tokens.len() != 2 {
         return Err(SdpParserInternalError::Generic(
             "dtls-message must have a role token and a value token.".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::DtlsMessageTokenCount));
     }
 
     Ok(SdpAttribute::DtlsMessage(match tokens[0] {
@@ -1950,7 +2741,8 @@ fn parse_dtls_message(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalE
             return Err(SdpParserInternalError::Generic(format!(
                 "dtls-message has unknown role token '{}'",
                 e
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::DtlsMessageUnknownRole));
         }
     }))
 }
@@ -1987,11 +2779,12 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     if tokens.len() < 2 {
         return Err(SdpParserInternalError::Generic(
             "Extmap needs to have at least two tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::ExtmapTooFewTokens));
     }
     let id: u16;
     let mut direction: Option<SdpAttributeDirection> = None;
-    if tokens[0].find('/') == None {
+    if tokens[0].find('/').is_none() {
         id = tokens[0].parse::<u16>()?;
     } else {
         let id_dir: Vec<&str> = tokens[0].splitn(2, '/').collect();
@@ -2003,7 +2796,8 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
             _ => {
                 return Err(SdpParserInternalError::Generic(
                     "Unsupported direction in extmap value".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::ExtmapUnknownDirection));
             }
         })
     }
@@ -2015,7 +2809,8 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
         if !valid_byte_string(&ext_string) {
             return Err(SdpParserInternalError::Generic(
                 "Illegal character in extmap extension attributes".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::ExtmapInvalidExtensionAttributes));
         }
         Some(ext_string)
     };
@@ -2048,7 +2843,8 @@ fn parse_fingerprint(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
     if tokens.len() != 2 {
         return Err(SdpParserInternalError::Generic(
             "Fingerprint needs to have two tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::FingerprintTokenCount));
     }
 
     let hash_algorithm = SdpAttributeFingerprintHashType::try_from_name(tokens[0])?;
@@ -2061,6 +2857,116 @@ fn parse_fingerprint(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
 // a=fmtp, RFC4566, RFC5576
 //-------------------------------------------------------------------------
 //       a=fmtp:<format> <format specific parameters>
+fn parse_fmtp_parameter(
+    parameters: &mut SdpAttributeFmtpParameters,
+    parameter_token: &str,
+) -> Result<(), SdpParserInternalError> {
+    if let Some(ref mut video) = parameters.st2110_video {
+        // ST 2110 has flags without a value and often a trailing "; "
+        let mut name_value = parameter_token.trim().splitn(2, '=');
+        let name = name_value.next().unwrap_or_default();
+        if name.is_empty() || video.parse_parameter(name, name_value.next())? {
+            return Ok(());
+        }
+    }
+
+    let name_value_pair: Vec<&str> = parameter_token.splitn(2, '=').collect();
+    if name_value_pair.len() != 2 {
+        return Err(SdpParserInternalError::Generic(
+            "A fmtp parameter must be either a telephone event, a parameter list or a red \
+             codec list"
+                .to_string(),
+        )
+        .with_kind(SdpParserErrorKind::FmtpInvalidParameter));
+    }
+
+    let parse_bool = |val: &str, param_name: &str| -> Result<bool, SdpParserInternalError> {
+        match val.parse::<u8>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SdpParserInternalError::Generic(format!(
+                "The fmtp parameter '{:}' must be 0 or 1",
+                param_name
+            ))
+            .with_kind(SdpParserErrorKind::FmtpInvalidBoolean)),
+        }
+    };
+
+    let parameter_name = name_value_pair[0];
+    let parameter_val = name_value_pair[1];
+
+    match parameter_name.to_uppercase().as_str() {
+        // H264
+        "PROFILE-LEVEL-ID" => {
+            parameters.profile_level_id = match u32::from_str_radix(parameter_val, 16)? {
+                x @ 0..=0x00ff_ffff => x,
+                _ => {
+                    return Err(SdpParserInternalError::Generic(
+                        "The fmtp parameter 'profile-level-id' must be in range [0,0xffffff]"
+                            .to_string(),
+                    )
+                    .with_kind(SdpParserErrorKind::FmtpProfileLevelIdOutOfRange))
+                }
+            }
+        }
+        "PACKETIZATION-MODE" => {
+            parameters.packetization_mode = match parameter_val.parse::<u32>()? {
+                x @ 0..=2 => x,
+                _ => {
+                    return Err(SdpParserInternalError::Generic(
+                        "The fmtp parameter 'packetization-mode' must be 0,1 or 2".to_string(),
+                    )
+                    .with_kind(SdpParserErrorKind::FmtpPacketizationModeOutOfRange));
+                }
+            }
+        }
+        "LEVEL-ASYMMETRY-ALLOWED" => {
+            parameters.level_asymmetry_allowed =
+                parse_bool(parameter_val, "level-asymmetry-allowed")?
+        }
+        "MAX-MBPS" => parameters.max_mbps = parameter_val.parse::<u32>()?,
+        "MAX-FS" => parameters.max_fs = parameter_val.parse::<u32>()?,
+        "MAX-CPB" => parameters.max_cpb = parameter_val.parse::<u32>()?,
+        "MAX-DPB" => parameters.max_dpb = parameter_val.parse::<u32>()?,
+        "MAX-BR" => parameters.max_br = parameter_val.parse::<u32>()?,
+
+        // VP8 and VP9
+        "MAX-FR" => parameters.max_fr = parameter_val.parse::<u32>()?,
+
+        //Opus https://tools.ietf.org/html/rfc7587
+        "MAXPLAYBACKRATE" => parameters.maxplaybackrate = parameter_val.parse::<u32>()?,
+        "MAXAVERAGEBITRATE" => parameters.maxaveragebitrate = parameter_val.parse::<u32>()?,
+        "PTIME" => parameters.ptime = parameter_val.parse::<u32>()?,
+        "MAXPTIME" => parameters.maxptime = parameter_val.parse::<u32>()?,
+        "MINPTIME" => parameters.minptime = parameter_val.parse::<u32>()?,
+
+        // T.140 https://tools.ietf.org/html/rfc4103
+        "CPS" => parameters.cps = parameter_val.parse::<u32>()?,
+        "USEDTX" => parameters.usedtx = parse_bool(parameter_val, "usedtx")?,
+        "STEREO" => parameters.stereo = parse_bool(parameter_val, "stereo")?,
+        "USEINBANDFEC" => parameters.useinbandfec = parse_bool(parameter_val, "useinbandfec")?,
+        "CBR" => parameters.cbr = parse_bool(parameter_val, "cbr")?,
+        "APT" => {
+            parameters.rtx = Some(RtxFmtpParameters {
+                apt: parameter_val.parse::<u8>()?,
+                rtx_time: None,
+            })
+        }
+        "RTX-TIME" => {
+            if let Some(ref mut rtx) = parameters.rtx {
+                rtx.rtx_time = Some(parameter_val.parse::<u32>()?)
+            } else {
+                return Err(SdpParserInternalError::Generic(
+                    "RTX codec must have an APT field".to_string(),
+                )
+                .with_kind(SdpParserErrorKind::FmtpRtxMissingApt));
+            }
+        }
+        _ => parameters.unknown_tokens.push(parameter_token.to_string()),
+    }
+    Ok(())
+}
+
 fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<&str> = to_parse.splitn(2, ' ').collect();
 
@@ -2068,7 +2974,8 @@ fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     if tokens.len() < 2 {
         return Err(SdpParserInternalError::Unsupported(
             "Fmtp attributes require a payload type and a parameter block.".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::FmtpMissingParameters));
     }
 
     let payload_token = tokens[0];
@@ -2093,12 +3000,22 @@ fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
         ptime: 0,
         minptime: 0,
         maxptime: 0,
+        cps: 0,
         encodings: Vec::new(),
         dtmf_tones: "".to_string(),
         rtx: None,
+        st2110_video: None,
         unknown_tokens: Vec::new(),
     };
 
+    // ST 2110-20 video is recognized by its sampling parameter
+    if tokens[1].split(';').any(|parameter| {
+        let name = parameter.trim().split('=').next().unwrap_or_default();
+        name.eq_ignore_ascii_case("sampling")
+    }) {
+        parameters.st2110_video = Some(Box::default());
+    }
+
     for parameter_token in tokens[1..].iter() {
         if parameter_token.contains('=') {
             // Permit Leading/Trailing/Inner ';' by filtering out empty splits
@@ -2107,111 +3024,21 @@ fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
                 .filter(|token| !token.is_empty())
                 .collect();
             for parameter_token in parameter_tokens.iter() {
-                let name_value_pair: Vec<&str> = parameter_token.splitn(2, '=').collect();
-                if name_value_pair.len() != 2 {
-                    return Err(SdpParserInternalError::Generic(
-                        "A fmtp parameter must be either a telephone event, a parameter list or a red codec list"
-                            .to_string(),
-                    ));
-                }
-
-                let parse_bool =
-                    |val: &str, param_name: &str| -> Result<bool, SdpParserInternalError> {
-                        match val.parse::<u8>()? {
-                            0 => Ok(false),
-                            1 => Ok(true),
-                            _ => Err(SdpParserInternalError::Generic(format!(
-                                "The fmtp parameter '{:}' must be 0 or 1",
-                                param_name
-                            ))),
-                        }
-                    };
-
-                let parameter_name = name_value_pair[0];
-                let parameter_val = name_value_pair[1];
-
-                match parameter_name.to_uppercase().as_str() {
-                    // H264
-                    "PROFILE-LEVEL-ID" => parameters.profile_level_id = match u32::from_str_radix(
-                        parameter_val,
-                        16,
-                    )? {
-                        x @ 0..=0x00ff_ffff => x,
-                        _ => return Err(SdpParserInternalError::Generic(
-                            "The fmtp parameter 'profile-level-id' must be in range [0,0xffffff]"
-                                .to_string(),
-                        )),
-                    },
-                    "PACKETIZATION-MODE" => {
-                        parameters.packetization_mode = match parameter_val.parse::<u32>()? {
-                            x @ 0..=2 => x,
-                            _ => {
-                                return Err(SdpParserInternalError::Generic(
-                                    "The fmtp parameter 'packetization-mode' must be 0,1 or 2"
-                                        .to_string(),
-                                ));
-                            }
-                        }
-                    }
-                    "LEVEL-ASYMMETRY-ALLOWED" => {
-                        parameters.level_asymmetry_allowed =
-                            parse_bool(parameter_val, "level-asymmetry-allowed")?
-                    }
-                    "MAX-MBPS" => parameters.max_mbps = parameter_val.parse::<u32>()?,
-                    "MAX-FS" => parameters.max_fs = parameter_val.parse::<u32>()?,
-                    "MAX-CPB" => parameters.max_cpb = parameter_val.parse::<u32>()?,
-                    "MAX-DPB" => parameters.max_dpb = parameter_val.parse::<u32>()?,
-                    "MAX-BR" => parameters.max_br = parameter_val.parse::<u32>()?,
-
-                    // VP8 and VP9
-                    "MAX-FR" => parameters.max_fr = parameter_val.parse::<u32>()?,
-
-                    //Opus https://tools.ietf.org/html/rfc7587
-                    "MAXPLAYBACKRATE" => {
-                        parameters.maxplaybackrate = parameter_val.parse::<u32>()?
-                    }
-                    "MAXAVERAGEBITRATE" => {
-                        parameters.maxaveragebitrate = parameter_val.parse::<u32>()?
-                    }
-                    "PTIME" => parameters.ptime = parameter_val.parse::<u32>()?,
-                    "MAXPTIME" => parameters.maxptime = parameter_val.parse::<u32>()?,
-                    "MINPTIME" => parameters.minptime = parameter_val.parse::<u32>()?,
-                    "USEDTX" => parameters.usedtx = parse_bool(parameter_val, "usedtx")?,
-                    "STEREO" => parameters.stereo = parse_bool(parameter_val, "stereo")?,
-                    "USEINBANDFEC" => {
-                        parameters.useinbandfec = parse_bool(parameter_val, "useinbandfec")?
-                    }
-                    "CBR" => parameters.cbr = parse_bool(parameter_val, "cbr")?,
-                    "APT" => {
-                        parameters.rtx = Some(RtxFmtpParameters {
-                            apt: parameter_val.parse::<u8>()?,
-                            rtx_time: None,
-                        })
-                    }
-                    "RTX-TIME" => {
-                        if let Some(ref mut rtx) = parameters.rtx {
-                            rtx.rtx_time = Some(parameter_val.parse::<u32>()?)
-                        } else {
-                            return Err(SdpParserInternalError::Generic(
-                                "RTX codec must have an APT field".to_string(),
-                            ));
-                        }
-                    }
-                    _ => parameters
-                        .unknown_tokens
-                        .push((*parameter_token).to_string()),
-                }
+                parse_fmtp_parameter(&mut parameters, parameter_token)
+                    .map_err(|e| e.located_in(to_parse, parameter_token))?;
             }
         } else if parameter_token.contains('/') {
             let encodings: Vec<&str> = parameter_token.split('/').collect();
 
             for encoding in encodings {
-                match encoding.parse::<u8>()? {
+                match parse_token::<u8>(to_parse, encoding)? {
                     x @ 0..=128 => parameters.encodings.push(x),
                     _ => {
                         return Err(SdpParserInternalError::Generic(
                             "Red codec must be in range [0,128]".to_string(),
-                        ));
+                        )
+                        .with_kind(SdpParserErrorKind::FmtpRedOutOfRange)
+                        .located_in(to_parse, encoding));
                     }
                 }
             }
@@ -2268,6 +3095,9 @@ fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             };
         }
     }
+    if let Some(ref video) = parameters.st2110_video {
+        video.check_required_parameters()?;
+    }
     Ok(SdpAttribute::Fmtp(SdpAttributeFmtp {
         payload_type: payload_token.parse::<u8>()?,
         parameters,
@@ -2288,7 +3118,8 @@ fn parse_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Group attribute is missing semantics token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::GroupMissingSemantics));
         }
         Some(x) => match x.to_uppercase().as_ref() {
             "LS" => SdpAttributeGroupSemantic::LipSynchronization,
@@ -2302,7 +3133,8 @@ fn parse_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
                 return Err(SdpParserInternalError::Unsupported(format!(
                     "Unknown group semantic '{:?}' found",
                     unknown
-                )));
+                ))
+                .with_kind(SdpParserErrorKind::GroupUnknownSemantics));
             }
         },
     };
@@ -2322,7 +3154,8 @@ fn parse_ice_options(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
     if to_parse.is_empty() {
         return Err(SdpParserInternalError::Generic(
             "ice-options is required to have a value".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::IceOptionsMissingValue));
     }
     Ok(SdpAttribute::IceOptions(
         to_parse
@@ -2342,7 +3175,8 @@ fn parse_ice_pacing(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     if parsed >= 1_00_00_00_00_00 {
         return Err(SdpParserInternalError::Generic(
             "ice-pacing value is not a 10 digit integer".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::IcePacingOutOfRange));
     }
     Ok(SdpAttribute::IcePacing(parsed))
 }
@@ -2391,6 +3225,7 @@ fn parse_image_attr_xyrange(
             SdpParserInternalError::Generic(
                 "imageattr's xyrange has no closing tag ']'".to_string(),
             )
+            .with_kind(SdpParserErrorKind::ImageAttrUnclosedRange)
         })?;
 
         if to_parse.contains(':') {
@@ -2412,7 +3247,8 @@ fn parse_image_attr_xyrange(
             } else {
                 Err(SdpParserInternalError::Generic(
                     "imageattr's xyrange must contain 2 or 3 fields".to_string(),
-                ))
+                )
+                .with_kind(SdpParserErrorKind::ImageAttrRangeFieldCount))
             }
         } else {
             // Discrete values
@@ -2424,7 +3260,8 @@ fn parse_image_attr_xyrange(
             if values.len() < 2 {
                 return Err(SdpParserInternalError::Generic(
                     "imageattr's discrete value list must have at least two elements".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::ImageAttrDiscreteListTooShort));
             }
 
             Ok(SdpAttributeImageAttrXyRange::DiscreteValues(values))
@@ -2443,21 +3280,25 @@ fn parse_image_attr_set(
 
     let x_token = tokens.next().ok_or_else(|| {
         SdpParserInternalError::Generic("imageattr set is missing the 'x=' token".to_string())
+            .with_kind(SdpParserErrorKind::ImageAttrMissingX)
     })?;
     if !x_token.starts_with("x=") {
         return Err(SdpParserInternalError::Generic(
             "The first token in an imageattr set must begin with 'x='".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::ImageAttrMissingX));
     }
     let x = parse_image_attr_xyrange(&x_token[2..])?;
 
     let y_token = tokens.next().ok_or_else(|| {
         SdpParserInternalError::Generic("imageattr set is missing the 'y=' token".to_string())
+            .with_kind(SdpParserErrorKind::ImageAttrMissingY)
     })?;
     if !y_token.starts_with("y=") {
         return Err(SdpParserInternalError::Generic(
             "The second token in an imageattr set must begin with 'y='".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::ImageAttrMissingY));
     }
     let y = parse_image_attr_xyrange(&y_token[2..])?;
 
@@ -2471,7 +3312,8 @@ fn parse_image_attr_set(
         if minmax_pair.len() != 2 {
             return Err(SdpParserInternalError::Generic(
                 "imageattr's par and sar ranges must have two components".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::ImageAttrRatioRangeComponents));
         }
 
         let min = minmax_pair[0].parse::<f32>()?;
@@ -2480,7 +3322,8 @@ fn parse_image_attr_set(
         if min >= max {
             return Err(SdpParserInternalError::Generic(
                 "In imageattr's par and sar ranges, first must be < than the second".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::ImageAttrRatioRangeOrder));
         }
 
         Ok((min, max))
@@ -2493,6 +3336,7 @@ fn parse_image_attr_set(
                     SdpParserInternalError::Generic(
                         "imageattr's sar value is missing closing tag ']'".to_string(),
                     )
+                    .with_kind(SdpParserErrorKind::ImageAttrUnclosedSar)
                 })?;
 
                 if value_token.contains('-') {
@@ -2510,7 +3354,8 @@ fn parse_image_attr_set(
                         return Err(SdpParserInternalError::Generic(
                             "imageattr's sar discrete value list must have at least two values"
                                 .to_string(),
-                        ));
+                        )
+                        .with_kind(SdpParserErrorKind::ImageAttrDiscreteListTooShort));
                     }
 
                     // Check that all the values are ascending
@@ -2520,7 +3365,8 @@ fn parse_image_attr_set(
                             return Err(SdpParserInternalError::Generic(
                                 "imageattr's sar discrete value list must contain ascending values"
                                     .to_string(),
-                            ));
+                            )
+                            .with_kind(SdpParserErrorKind::ImageAttrSarNotAscending));
                         }
                         last_value = *value;
                     }
@@ -2535,13 +3381,15 @@ fn parse_image_attr_set(
             if !braced_value_token.starts_with('[') {
                 return Err(SdpParserInternalError::Generic(
                     "imageattr's par value must start with '['".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::ImageAttrParNotOpened));
             }
 
             let par_values = parse_imagettr_braced_token(braced_value_token).ok_or_else(|| {
                 SdpParserInternalError::Generic(
                     "imageattr's par value must be enclosed with ']'".to_string(),
                 )
+                .with_kind(SdpParserErrorKind::ImageAttrUnclosedPar)
             })?;
             let range = parse_ps_range(par_values)?;
             par = Some(SdpAttributeImageAttrPRange {
@@ -2565,6 +3413,7 @@ where
     let parse_set = |set_token: &str| -> Result<SdpAttributeImageAttrSet, SdpParserInternalError> {
         parse_image_attr_set(parse_imagettr_braced_token(set_token).ok_or_else(|| {
             SdpParserInternalError::Generic("imageattr sets must be enclosed by ']'".to_string())
+                .with_kind(SdpParserErrorKind::ImageAttrUnclosedSet)
         })?)
     };
 
@@ -2574,6 +3423,7 @@ where
             SdpParserInternalError::Generic(
                 "imageattr must have a parameter set after a direction token".to_string(),
             )
+            .with_kind(SdpParserErrorKind::ImageAttrMissingSet)
         })?
         .as_str()
     {
@@ -2669,6 +3519,7 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
             .next()
             .ok_or_else(|| {
                 SdpParserInternalError::Generic("imageattr requires a payload token".to_string())
+                    .with_kind(SdpParserErrorKind::ImageAttrMissingPayloadType)
             })?
             .as_str(),
     )?;
@@ -2679,6 +3530,7 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
                 SdpParserInternalError::Generic(
                     "imageattr's second token must be a direction token".to_string(),
                 )
+                .with_kind(SdpParserErrorKind::ImageAttrMissingDirection)
             })?
             .as_str(),
     )?;
@@ -2693,7 +3545,8 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
             return Err(SdpParserInternalError::Generic(
                 "imageattr's second direction token must be different from the first one"
                     .to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::ImageAttrDuplicateDirection));
         }
 
         second_set_list = parse_image_attr_set_list(&mut tokens)?;
@@ -2702,7 +3555,8 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     if tokens.next().is_some() {
         return Err(SdpParserInternalError::Generic(
             "imageattr must not contain any token after the second set list".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::ImageAttrTrailingTokens));
     }
 
     Ok(SdpAttribute::ImageAttr(match first_direction {
@@ -2731,7 +3585,8 @@ fn parse_msid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Msid attribute is missing msid-id token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::MsidMissingId));
         }
         Some(x) => x.to_string(),
     };
@@ -2750,7 +3605,8 @@ fn parse_msid_semantic(to_parse: &str) -> Result<SdpAttribute, SdpParserInternal
     if tokens.is_empty() {
         return Err(SdpParserInternalError::Generic(
             "Msid-semantic attribute is missing msid-semantic token".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::MsidSemanticMissingToken));
     }
     // TODO: Should msids be checked to ensure they are non empty?
     let semantic = SdpAttributeMsidSemantic {
@@ -2801,7 +3657,8 @@ fn parse_rid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     if tokens.len() < 2 {
         return Err(SdpParserInternalError::Generic(
             "A rid attribute must at least have an id and a direction token.".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::RidTooFewTokens));
     }
 
     // Default initilize
@@ -2837,7 +3694,8 @@ fn parse_rid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             if param_value_pair.len() != 2 {
                 return Err(SdpParserInternalError::Generic(
                     "A rid parameter needs to be of form 'param=value'".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::RidInvalidParameter));
             }
 
             match param_value_pair[0] {
@@ -2876,7 +3734,8 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Remote-candidate attribute is missing component ID".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::RemoteCandidateMissingComponent));
         }
         Some(x) => x.parse::<u32>()?,
     };
@@ -2884,7 +3743,8 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Remote-candidate attribute is missing connection address".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::RemoteCandidateMissingAddress));
         }
         Some(x) => parse_unicast_address(x)?,
     };
@@ -2892,14 +3752,16 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Remote-candidate attribute is missing port number".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::RemoteCandidateMissingPort));
         }
         Some(x) => x.parse::<u32>()?,
     };
     if port > 65535 {
         return Err(SdpParserInternalError::Generic(
             "Remote-candidate port can only be a bit 16bit number".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::RemoteCandidatePortOutOfRange));
     };
     Ok(SdpAttribute::RemoteCandidate(SdpAttributeRemoteCandidate {
         component,
@@ -2916,41 +3778,46 @@ fn parse_rtpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     let mut tokens = to_parse.split_whitespace();
     let payload_type: u8 = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
-                "Rtpmap missing payload type".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("Rtpmap missing payload type".to_string())
+                    .with_kind(SdpParserErrorKind::RtpmapMissingPayloadType),
+            );
         }
         Some(x) => {
             let pt = x.parse::<u8>()?;
             if pt > 127 {
                 return Err(SdpParserInternalError::Generic(
                     "Rtpmap payload type must be less then 127".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::RtpmapPayloadTypeOutOfRange));
             };
             pt
         }
     };
     let mut parameters = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
-                "Rtpmap missing payload type".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("Rtpmap missing payload type".to_string())
+                    .with_kind(SdpParserErrorKind::RtpmapMissingPayloadType),
+            );
         }
         Some(x) => x.split('/'),
     };
     let name = match parameters.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
-                "Rtpmap missing codec name".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("Rtpmap missing codec name".to_string())
+                    .with_kind(SdpParserErrorKind::RtpmapMissingCodecName),
+            );
         }
         Some(x) => x.to_string(),
     };
     let frequency = match parameters.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
-                "Rtpmap missing codec name".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("Rtpmap missing codec name".to_string())
+                    .with_kind(SdpParserErrorKind::RtpmapMissingCodecName),
+            );
         }
         Some(x) => x.parse::<u32>()?,
     };
@@ -2972,7 +3839,8 @@ fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Rtcp attribute is missing port number".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::RtcpMissingPort));
         }
         Some(x) => x.parse::<u16>()?,
     };
@@ -2985,7 +3853,8 @@ fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
                 None => {
                     return Err(SdpParserInternalError::Generic(
                         "Rtcp attribute is missing address type token".to_string(),
-                    ));
+                    )
+                    .with_kind(SdpParserErrorKind::RtcpMissingAddressType));
                 }
                 Some(x) => {
                     let addrtype = AddressType::from_str(x)?;
@@ -2993,12 +3862,10 @@ fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
                         None => {
                             return Err(SdpParserInternalError::Generic(
                                 "Rtcp attribute is missing ip address token".to_string(),
-                            ));
+                            )
+                            .with_kind(SdpParserErrorKind::RtcpMissingAddress));
                         }
-                        Some(x) => match ExplicitlyTypedAddress::try_from((addrtype, x)) {
-                            Ok(address) => address,
-                            Err(e) => return Err(e),
-                        },
+                        Some(x) => ExplicitlyTypedAddress::try_from((addrtype, x))?,
                     };
                     rtcp.set_addr(addr);
                 }
@@ -3008,6 +3875,260 @@ fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     Ok(SdpAttribute::Rtcp(rtcp))
 }
 
+///////////////////////////////////////////////////////////////////////////
+// a=T38FaxVersion and friends, ITU-T T.38 Annex D
+//-------------------------------------------------------------------------
+//   T38FaxVersion:<0-3>
+//   T38MaxBitRate:<bits per second>
+//   T38FaxRateManagement:localTCF / transferredTCF
+//   T38FaxUdpEC:t38UDPFEC / t38UDPRedundancy / t38UDPNoEC
+//   T38FaxFillBitRemoval, T38FaxTranscodingMMR, T38FaxTranscodingJBIG
+fn parse_t38_flag(to_parse: &str) -> Result<bool, SdpParserInternalError> {
+    match to_parse {
+        "" | "1" => Ok(true),
+        "0" => Ok(false),
+        x => Err(SdpParserInternalError::Generic(format!(
+            "T.38 flag attribute value must be 0 or 1, found '{}'",
+            x
+        ))
+        .with_kind(SdpParserErrorKind::T38InvalidValue)),
+    }
+}
+
+fn parse_t38_version(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    match to_parse.parse::<u8>()? {
+        version @ 0..=3 => Ok(SdpAttribute::T38FaxVersion(version)),
+        version => Err(SdpParserInternalError::Generic(format!(
+            "T38FaxVersion must be in range [0,3], found {}",
+            version
+        ))
+        .with_kind(SdpParserErrorKind::T38InvalidValue)),
+    }
+}
+
+fn parse_t38_max_bit_rate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    match to_parse.parse::<u32>()? {
+        0 => Err(
+            SdpParserInternalError::Generic("T38MaxBitRate must be positive".to_string())
+                .with_kind(SdpParserErrorKind::T38InvalidValue),
+        ),
+        rate => Ok(SdpAttribute::T38MaxBitRate(rate)),
+    }
+}
+
+fn parse_t38_rate_management(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    Ok(SdpAttribute::T38FaxRateManagement(
+        match to_parse.to_lowercase().as_str() {
+            "localtcf" => SdpT38FaxRateManagement::LocalTcf,
+            "transferredtcf" => SdpT38FaxRateManagement::TransferredTcf,
+            _ => {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "Unknown T38FaxRateManagement '{}'",
+                    to_parse
+                ))
+                .with_kind(SdpParserErrorKind::T38InvalidValue));
+            }
+        },
+    ))
+}
+
+fn parse_t38_udp_ec(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    Ok(SdpAttribute::T38FaxUdpEc(
+        match to_parse.to_lowercase().as_str() {
+            "t38udpnoec" => SdpT38FaxUdpEc::NoEc,
+            "t38udpredundancy" => SdpT38FaxUdpEc::Redundancy,
+            "t38udpfec" => SdpT38FaxUdpEc::Fec,
+            _ => {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "Unknown T38FaxUdpEC '{}'",
+                    to_parse
+                ))
+                .with_kind(SdpParserErrorKind::T38InvalidValue));
+            }
+        },
+    ))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=ts-refclk, RFC7273
+//-------------------------------------------------------------------------
+//   ts-refclk  = "ts-refclk:" clksrc
+//   clksrc     = ntp / ptp / gps / gal / glonass / local / private /
+//                clksrc-ext
+//   ntp        = "ntp=" ntp-server / "ntp=/traceable/"
+//   ptp        = "ptp=" ptp-version ":" ptp-gmid [":" ptp-domain] /
+//                "ptp=" ptp-version ":traceable"
+//   private    = "private" [":traceable"]
+//   clksrc-ext = clksrc-param-name ["=" clksrc-param-value]
+fn parse_ts_refclk(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let (name, value) = match to_parse.find('=') {
+        Some(i) => (&to_parse[..i], Some(&to_parse[i + 1..])),
+        None => (to_parse, None),
+    };
+    let invalid = |message: &str| {
+        SdpParserInternalError::Generic(format!("{} in ts-refclk '{}'", message, to_parse))
+            .with_kind(SdpParserErrorKind::TsRefclkInvalidSource)
+    };
+    let refclk = match (name.to_lowercase().as_str(), value) {
+        ("", _) => return Err(invalid("Missing clock source")),
+        ("ntp", Some(server)) if !server.is_empty() => {
+            SdpAttributeTsRefclk::Ntp(server.to_string())
+        }
+        ("ntp", _) => return Err(invalid("Missing NTP server")),
+        ("ptp", Some(ptp)) => {
+            let mut tokens = ptp.splitn(3, ':');
+            let version = tokens.next().unwrap_or_default();
+            let grandmaster = match tokens.next() {
+                Some("traceable") => None,
+                Some(grandmaster) if !grandmaster.is_empty() => Some(grandmaster.to_string()),
+                _ => return Err(invalid("Missing PTP grandmaster")),
+            };
+            let domain = tokens.next().map(ToString::to_string);
+            if version.is_empty() || (grandmaster.is_none() && domain.is_some()) {
+                return Err(invalid("Invalid PTP server"));
+            }
+            SdpAttributeTsRefclk::Ptp {
+                version: version.to_string(),
+                grandmaster,
+                domain,
+            }
+        }
+        ("ptp", None) => return Err(invalid("Missing PTP server")),
+        ("gps", None) => SdpAttributeTsRefclk::Gps,
+        ("gal", None) => SdpAttributeTsRefclk::Gal,
+        ("glonass", None) => SdpAttributeTsRefclk::Glonass,
+        ("local", None) => SdpAttributeTsRefclk::Local,
+        ("private", None) => SdpAttributeTsRefclk::Private { traceable: false },
+        ("private:traceable", None) => SdpAttributeTsRefclk::Private { traceable: true },
+        ("localmac", Some(mac)) if !mac.is_empty() => {
+            SdpAttributeTsRefclk::LocalMac(mac.to_string())
+        }
+        ("gps", _) | ("gal", _) | ("glonass", _) | ("local", _) | ("private", _) => {
+            return Err(invalid("Unexpected value"))
+        }
+        ("localmac", _) => return Err(invalid("Missing MAC address")),
+        _ => SdpAttributeTsRefclk::Extension {
+            name: name.to_string(),
+            value: value.map(ToString::to_string),
+        },
+    };
+    Ok(SdpAttribute::TsRefclk(refclk))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=mediaclk, RFC7273
+//-------------------------------------------------------------------------
+//   mediaclk    = "mediaclk:" [media-clkid SP] mediaclock
+//   media-clkid = "id=" mediaclk-id
+//   mediaclock  = "sender" / direct / IEEE1722 / mediaclock-ext
+//   direct      = "direct" ["=" 1*20DIGIT] [SP rate]
+//   rate        = "rate=" integer "/" integer
+//   IEEE1722    = "IEEE1722=" stream-id
+fn parse_mediaclk(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let mut tokens: Vec<&str> = to_parse.split_whitespace().collect();
+    let id = match tokens.first() {
+        Some(token) if token.starts_with("id=") => {
+            let id = token["id=".len()..].to_string();
+            tokens.remove(0);
+            Some(id)
+        }
+        _ => None,
+    };
+    let invalid = || {
+        SdpParserInternalError::Generic(format!("Invalid media clock '{}'", to_parse))
+            .with_kind(SdpParserErrorKind::MediaClkInvalidSource)
+    };
+    let clock = tokens.first().ok_or_else(invalid)?;
+    let (name, value) = match clock.find('=') {
+        Some(i) => (&clock[..i], Some(&clock[i + 1..])),
+        None => (*clock, None),
+    };
+    let source = match (name, value, tokens.len()) {
+        ("sender", None, 1) => SdpMediaClockSource::Sender,
+        ("direct", _, 1..=2) => {
+            let offset = value
+                .map(|offset| parse_token::<u64>(to_parse, offset))
+                .transpose()?;
+            let rate = match tokens.get(1) {
+                Some(rate) => match rate.strip_prefix("rate=") {
+                    Some(rate) if rate.contains('/') => Some(
+                        rate.parse::<SdpFrameRate>()
+                            .map_err(|e| e.located_in(to_parse, rate))?,
+                    ),
+                    _ => return Err(invalid()),
+                },
+                None => None,
+            };
+            SdpMediaClockSource::Direct { offset, rate }
+        }
+        ("IEEE1722", Some(stream), 1) if !stream.is_empty() => {
+            SdpMediaClockSource::Ieee1722(stream.to_string())
+        }
+        ("sender", _, _) | ("direct", _, _) | ("IEEE1722", _, _) => return Err(invalid()),
+        _ => SdpMediaClockSource::Extension(tokens.join(" ")),
+    };
+    Ok(SdpAttribute::MediaClk(SdpAttributeMediaClk { id, source }))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=source-filter, RFC4570
+//-------------------------------------------------------------------------
+//   source-filter = "source-filter" ":" SP filter-mode SP filter-spec
+//   filter-mode   = "excl" / "incl"
+//   filter-spec   = nettype SP address-types SP dest-address SP src-list
+//   address-types = "*" / addrtype
+//   dest-address  = "*" / basic-multicast-address / FQDN
+//   src-list      = *(unicast-address SP) unicast-address
+fn parse_source_filter(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let tokens: Vec<&str> = to_parse.split_whitespace().collect();
+    if tokens.len() < 5 {
+        return Err(SdpParserInternalError::Generic(
+            "Source-filter attribute must have a mode, network type, address type, destination \
+             and at least one source"
+                .to_string(),
+        )
+        .with_kind(SdpParserErrorKind::SourceFilterTooFewTokens));
+    }
+    let mode = match tokens[0] {
+        "incl" => SdpSourceFilterMode::Include,
+        "excl" => SdpSourceFilterMode::Exclude,
+        x => {
+            return Err(SdpParserInternalError::Generic(format!(
+                "Unknown source-filter mode '{}'",
+                x
+            ))
+            .with_kind(SdpParserErrorKind::SourceFilterUnknownMode)
+            .located_in(to_parse, tokens[0]));
+        }
+    };
+    parse_network_type(tokens[1]).map_err(|e| e.located_in(to_parse, tokens[1]))?;
+    let address_type = match tokens[2] {
+        "*" => None,
+        x => Some(AddressType::from_str(x).map_err(|e| e.located_in(to_parse, x))?),
+    };
+    let parse_address = |token: &str| -> Result<Address, SdpParserInternalError> {
+        match address_type {
+            Some(t) => ExplicitlyTypedAddress::try_from((t, token)).map(Address::from),
+            None => Address::from_str(token),
+        }
+        .map_err(|e| e.located_in(to_parse, token))
+    };
+    let destination = match tokens[3] {
+        "*" => None,
+        x => Some(parse_address(x)?),
+    };
+    let sources = tokens[4..]
+        .iter()
+        .map(|token| parse_address(token))
+        .collect::<Result<Vec<Address>, _>>()?;
+    Ok(SdpAttribute::SourceFilter(SdpAttributeSourceFilter {
+        mode,
+        address_type,
+        destination,
+        sources,
+    }))
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // a=rtcp-fb, RFC4585
 //-------------------------------------------------------------------------
@@ -3054,13 +4175,15 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                 return Err(SdpParserInternalError::Unsupported(format!(
                     "Unknown rtcpfb feedback type: {:?}",
                     x
-                )));
+                ))
+                .with_kind(SdpParserErrorKind::RtcpFbUnknownType));
             }
         },
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Error parsing rtcpfb: no feedback type".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::RtcpFbMissingType));
         }
     };
 
@@ -3073,13 +4196,15 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                     return Err(SdpParserInternalError::Unsupported(format!(
                         "Unknown rtcpfb ack parameter: {:?}",
                         x
-                    )));
+                    ))
+                    .with_kind(SdpParserErrorKind::RtcpFbUnknownParameter));
                 }
             },
             None => {
                 return Err(SdpParserInternalError::Unsupported(
                     "The rtcpfb ack feeback type needs a parameter:".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::RtcpFbMissingParameter));
             }
         },
         SdpAttributeRtcpFbType::Ccm => match tokens.get(2) {
@@ -3089,7 +4214,8 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                     return Err(SdpParserInternalError::Unsupported(format!(
                         "Unknown rtcpfb ccm parameter: {:?}",
                         x
-                    )));
+                    ))
+                    .with_kind(SdpParserErrorKind::RtcpFbUnknownParameter));
                 }
             },
             None => "".to_string(),
@@ -3101,7 +4227,8 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                     return Err(SdpParserInternalError::Unsupported(format!(
                         "Unknown rtcpfb nack parameter: {:?}",
                         x
-                    )));
+                    ))
+                    .with_kind(SdpParserErrorKind::RtcpFbUnknownParameter));
                 }
             },
             None => "".to_string(),
@@ -3113,13 +4240,15 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                     return Err(SdpParserInternalError::Generic(format!(
                         "Unknown rtcpfb trr-int parameter: {:?}",
                         x
-                    )));
+                    ))
+                    .with_kind(SdpParserErrorKind::RtcpFbUnknownParameter));
                 }
             },
             None => {
                 return Err(SdpParserInternalError::Generic(
                     "The rtcpfb trr-int feedback type needs a parameter".to_string(),
-                ));
+                )
+                .with_kind(SdpParserErrorKind::RtcpFbMissingParameter));
             }
         },
         SdpAttributeRtcpFbType::Remb | SdpAttributeRtcpFbType::TransCc => match tokens.get(2) {
@@ -3127,7 +4256,8 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                 return Err(SdpParserInternalError::Unsupported(format!(
                     "Unknown rtcpfb {} parameter: {:?}",
                     feedback_type, x
-                )));
+                ))
+                .with_kind(SdpParserErrorKind::RtcpFbUnknownParameter));
             }
             None => "".to_string(),
         },
@@ -3161,13 +4291,15 @@ fn parse_sctpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     if tokens.len() != 3 {
         return Err(SdpParserInternalError::Generic(
             "Sctpmap needs to have three tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::SctpmapTokenCount));
     }
     let port = tokens[0].parse::<u16>()?;
     if tokens[1].to_lowercase() != "webrtc-datachannel" {
-        return Err(SdpParserInternalError::Generic(
-            "Unsupported sctpmap type token".to_string(),
-        ));
+        return Err(
+            SdpParserInternalError::Generic("Unsupported sctpmap type token".to_string())
+                .with_kind(SdpParserErrorKind::SctpmapUnknownProtocol),
+        );
     }
     Ok(SdpAttribute::Sctpmap(SdpAttributeSctpmap {
         port,
@@ -3188,9 +4320,10 @@ fn parse_setup(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             "holdconn" => SdpAttributeSetup::Holdconn,
             "passive" => SdpAttributeSetup::Passive,
             _ => {
-                return Err(SdpParserInternalError::Generic(
-                    "Unsupported setup value".to_string(),
-                ));
+                return Err(
+                    SdpParserInternalError::Generic("Unsupported setup value".to_string())
+                        .with_kind(SdpParserErrorKind::SetupUnknownValue),
+                );
             }
         },
     ))
@@ -3215,7 +4348,8 @@ fn parse_simulcast_version_list(
             descriptor => Err(SdpParserInternalError::Generic(format!(
                 "Simulcast attribute has unknown list descriptor '{:?}'",
                 descriptor
-            ))),
+            ))
+            .with_kind(SdpParserErrorKind::SimulcastUnknownDescriptor)),
         }
     } else {
         Ok(make_version_list(to_parse))
@@ -3249,13 +4383,14 @@ fn parse_simulcast_version_list(
 // ; rid-id defined in [I-D.ietf-mmusic-rid]
 fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     // TODO: Bug 1225877: Stop accepting all kinds of whitespace here, and only accept SP
-    let mut tokens = to_parse.trim().split_whitespace();
+    let mut tokens = to_parse.split_whitespace();
     let first_direction = match tokens.next() {
         Some(x) => parse_single_direction(x)?,
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Simulcast attribute is missing send/recv value".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::SimulcastMissingDirection));
         }
     };
 
@@ -3265,7 +4400,8 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
             return Err(SdpParserInternalError::Generic(
                 "Simulcast attribute must have an alternatives list after the direction token"
                     .to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::SimulcastMissingList));
         }
     };
 
@@ -3274,7 +4410,8 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         if parse_single_direction(x)? == first_direction {
             return Err(SdpParserInternalError::Generic(
                 "Simulcast attribute has defined two times the same direction".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::SimulcastDuplicateDirection));
         }
 
         second_version_list = match tokens.next() {
@@ -3284,7 +4421,8 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
                     "{:?}{:?}",
                     "Simulcast has defined a second direction but",
                     "no second list of simulcast stream versions"
-                )));
+                ))
+                .with_kind(SdpParserErrorKind::SimulcastMissingList));
             }
         }
     }
@@ -3315,7 +4453,8 @@ fn parse_ssrc(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Ssrc attribute is missing ssrc-id value".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::SsrcMissingId));
         }
         Some(x) => x.parse::<u32>()?,
     };
@@ -3328,7 +4467,11 @@ fn parse_ssrc(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
 }
 
 pub fn parse_attribute(value: &str) -> Result<SdpType, SdpParserInternalError> {
-    Ok(SdpType::Attribute(value.trim().parse()?))
+    let trimmed = value.trim();
+    let attribute = trimmed
+        .parse()
+        .map_err(|e: SdpParserInternalError| e.rebase(value, trimmed))?;
+    Ok(SdpType::Attribute(attribute))
 }
 
 #[cfg(test)]
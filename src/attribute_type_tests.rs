@@ -356,9 +356,22 @@ fn test_parse_attribute_fmtp() {
     check_parse_and_serialize("fmtp:8 maxplaybackrate=46000");
     check_parse_and_serialize("fmtp:8 maxaveragebitrate=46000");
     check_parse_and_serialize("fmtp:8 maxaveragebitrate=46000;ptime=60;minptime=20;maxptime=120");
+    check_parse_and_serialize("fmtp:98 cps=30");
+    check_parse_and_serialize("fmtp:100 98/98/98");
+    assert_eq!(check_parse("fmtp:98 CPS=60").parameters.cps, 60);
+    assert!(parse_attribute("fmtp:98 cps=fast").is_err());
     check_parse_and_serialize(
         "fmtp:8 max-cpb=1234;max-dpb=32000;max-br=3;max-mbps=46000;usedtx=1;cbr=1",
     );
+    check_parse_and_serialize(
+        "fmtp:96 sampling=YCbCr-4:2:2; width=1920; height=1080; exactframerate=30000/1001; \
+         depth=10; TCS=SDR; colorimetry=BT709; PM=2110GPM; SSN=ST2110-20:2017; TP=2110TPN; \
+         interlace",
+    );
+    check_parse_and_serialize(
+        "fmtp:96 sampling=RGB; width=1280; height=720; exactframerate=50; depth=8; \
+         colorimetry=BT709; segmented; MAXUDP=8960",
+    );
     assert!(parse_attribute("fmtp:77 ").is_err());
     assert!(parse_attribute("fmtp:109 stereo=2;").is_err());
     assert!(parse_attribute("fmtp:109 111/129;").is_err());
@@ -405,10 +418,12 @@ fn test_parse_attribute_group() {
     check_parse_and_serialize("group:BUNDLE sdparta_0 sdparta_1 sdparta_2");
 
     assert!(parse_attribute("group:").is_err());
+    let error = parse_attribute("group:NEVER_SUPPORTED_SEMANTICS").unwrap_err();
     assert!(matches!(
-        parse_attribute("group:NEVER_SUPPORTED_SEMANTICS"),
-        Err(SdpParserInternalError::Unsupported(_))
+        *error.inner(),
+        SdpParserInternalError::Unsupported(_)
     ));
+    assert_eq!(error.kind(), SdpParserErrorKind::GroupUnknownSemantics);
 }
 
 #[test]
@@ -867,6 +882,68 @@ fn test_parse_attribute_setup() {
     assert!(parse_attribute("setup:foobar").is_err());
 }
 
+#[test]
+fn test_parse_attribute_fmtp_st2110_video() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+    let fmtp = check_parse(
+        "fmtp:112 sampling=YCbCr-4:2:2; width=1920; height=1080; exactframerate=25; depth=10; \
+         TCS=SDR; colorimetry=BT709; PM=2110GPM; SSN=ST2110-20:2017; TP=2110TPN; ",
+    );
+    let video = fmtp.parameters.st2110_video.unwrap();
+    assert_eq!(video.sampling, "YCbCr-4:2:2");
+    assert_eq!(
+        (video.width, video.height, video.depth),
+        (1920, 1080, SdpSampleDepth::Integer(10))
+    );
+    assert_eq!(
+        video.exactframerate,
+        SdpFrameRate {
+            numerator: 25,
+            denominator: 1
+        }
+    );
+    assert_eq!(video.colorimetry, "BT709");
+    assert_eq!(video.packing_mode, Some("2110GPM".to_string()));
+    assert_eq!(video.ssn, Some("ST2110-20:2017".to_string()));
+    assert_eq!(video.tcs, Some("SDR".to_string()));
+    assert_eq!(video.tp, Some("2110TPN".to_string()));
+    assert!(!video.interlace);
+    assert!(fmtp.parameters.unknown_tokens.is_empty());
+
+    let fmtp = check_parse(
+        "fmtp:112 sampling=RGB; width=3840; height=2160; exactframerate=60000/1001; depth=16f; \
+         TCS=ST2065-1; colorimetry=ALLCOLOR",
+    );
+    let video = fmtp.parameters.st2110_video.unwrap();
+    assert_eq!(video.depth, SdpSampleDepth::Float(16));
+    assert!(video.to_string().contains("depth=16f"));
+
+    // Without sampling these are not ST 2110 parameters
+    let fmtp = check_parse("fmtp:97 width=1920;height=1080");
+    assert!(fmtp.parameters.st2110_video.is_none());
+    assert_eq!(fmtp.parameters.unknown_tokens.len(), 2);
+
+    assert!(parse_attribute(
+        "fmtp:96 sampling=YCbCr-4:2:2; width=1920; height=1080; exactframerate=25; depth=10"
+    )
+    .is_err());
+    assert!(parse_attribute(
+        "fmtp:96 sampling=YCbCr-4:2:2; width=1920; height=1080; exactframerate=25/0; \
+         depth=10; colorimetry=BT709"
+    )
+    .is_err());
+    assert!(parse_attribute(
+        "fmtp:96 sampling=YCbCr-4:2:2; width=wide; height=1080; exactframerate=25; depth=10; \
+         colorimetry=BT709"
+    )
+    .is_err());
+    assert!(parse_attribute(
+        "fmtp:96 sampling; width=1920; height=1080; exactframerate=25; depth=10; \
+         colorimetry=BT709"
+    )
+    .is_err());
+}
+
 #[test]
 fn test_parse_attribute_rtcp() {
     let check_parse = make_check_parse!(SdpAttributeRtcp, SdpAttribute::Rtcp);
@@ -884,6 +961,176 @@ fn test_parse_attribute_rtcp() {
     assert!(parse_attribute("rtcp:9 IN IP4 ::1").is_err());
 }
 
+#[test]
+fn test_parse_attribute_source_filter() {
+    let check_parse = make_check_parse!(SdpAttributeSourceFilter, SdpAttribute::SourceFilter);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::SourceFilter);
+
+    check_parse_and_serialize("source-filter:incl IN IP4 232.3.4.5 192.0.2.10");
+    check_parse_and_serialize("source-filter:excl IN IP6 ff15::101 2001:db8::1 2001:db8::2");
+    check_parse_and_serialize("source-filter:incl IN IP4 * 192.0.2.10");
+    check_parse_and_serialize("source-filter:excl IN * * 192.0.2.10 2001:db8::1");
+    check_parse_and_serialize("source-filter:incl IN IP4 channel.example.com source.example.com");
+
+    let filter = check_parse("source-filter: incl IN IP4 232.3.4.5 192.0.2.10 192.0.2.11");
+    assert_eq!(filter.mode, SdpSourceFilterMode::Include);
+    assert_eq!(filter.address_type, Some(AddressType::IpV4));
+    assert_eq!(
+        filter.destination,
+        Some(Address::Ip(IpAddr::V4(Ipv4Addr::new(232, 3, 4, 5))))
+    );
+    assert_eq!(filter.sources.len(), 2);
+
+    assert!(parse_attribute("source-filter:").is_err());
+    assert!(parse_attribute("source-filter:incl IN IP4 232.3.4.5").is_err());
+    assert!(parse_attribute("source-filter:both IN IP4 232.3.4.5 192.0.2.10").is_err());
+    assert!(parse_attribute("source-filter:incl ATM IP4 232.3.4.5 192.0.2.10").is_err());
+    assert!(parse_attribute("source-filter:incl IN IP4 232.3.4.5 2001:db8::1").is_err());
+    assert!(parse_attribute("source-filter:incl IN IP6 232.3.4.5 2001:db8::1").is_err());
+}
+
+#[test]
+fn test_parse_attribute_ts_refclk() {
+    let check_parse = make_check_parse!(SdpAttributeTsRefclk, SdpAttribute::TsRefclk);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::TsRefclk);
+
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:37");
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0");
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2002:39-A7-94-FF-FE-07-CB-D0:domain-name=x");
+    check_parse_and_serialize("ts-refclk:ptp=IEEE802.1AS-2011:traceable");
+    check_parse_and_serialize("ts-refclk:ntp=203.0.113.10");
+    check_parse_and_serialize("ts-refclk:ntp=/traceable/");
+    check_parse_and_serialize("ts-refclk:gps");
+    check_parse_and_serialize("ts-refclk:gal");
+    check_parse_and_serialize("ts-refclk:glonass");
+    check_parse_and_serialize("ts-refclk:local");
+    check_parse_and_serialize("ts-refclk:private");
+    check_parse_and_serialize("ts-refclk:private:traceable");
+    check_parse_and_serialize("ts-refclk:localmac=CA-FE-01-CA-FE-02");
+    check_parse_and_serialize("ts-refclk:x-clock=1");
+
+    assert_eq!(
+        check_parse("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:37"),
+        SdpAttributeTsRefclk::Ptp {
+            version: "IEEE1588-2008".to_string(),
+            grandmaster: Some("39-A7-94-FF-FE-07-CB-D0".to_string()),
+            domain: Some("37".to_string()),
+        }
+    );
+
+    assert!(parse_attribute("ts-refclk:").is_err());
+    assert!(parse_attribute("ts-refclk:ntp").is_err());
+    assert!(parse_attribute("ts-refclk:ptp").is_err());
+    assert!(parse_attribute("ts-refclk:ptp=IEEE1588-2008").is_err());
+    assert!(parse_attribute("ts-refclk:ptp=IEEE1588-2008:traceable:37").is_err());
+    assert!(parse_attribute("ts-refclk:gps=1").is_err());
+    assert!(parse_attribute("ts-refclk:localmac=").is_err());
+}
+
+#[test]
+fn test_parse_attribute_mediaclk() {
+    let check_parse = make_check_parse!(SdpAttributeMediaClk, SdpAttribute::MediaClk);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::MediaClk);
+
+    check_parse_and_serialize("mediaclk:direct=0");
+    check_parse_and_serialize("mediaclk:direct");
+    check_parse_and_serialize("mediaclk:direct=963214424 rate=1000/1001");
+    check_parse_and_serialize("mediaclk:id=src1 direct=0");
+    check_parse_and_serialize("mediaclk:sender");
+    check_parse_and_serialize("mediaclk:IEEE1722=38-D6-6D-8E-D2-78-13-2F");
+    check_parse_and_serialize("mediaclk:x-clock 1");
+
+    let mediaclk = check_parse("mediaclk:id=src1 direct=0 rate=90000/1");
+    assert_eq!(mediaclk.id, Some("src1".to_string()));
+    assert_eq!(
+        mediaclk.source,
+        SdpMediaClockSource::Direct {
+            offset: Some(0),
+            rate: Some(SdpFrameRate {
+                numerator: 90000,
+                denominator: 1
+            }),
+        }
+    );
+
+    assert!(parse_attribute("mediaclk:").is_err());
+    assert!(parse_attribute("mediaclk:id=src1").is_err());
+    assert!(parse_attribute("mediaclk:direct=x").is_err());
+    assert!(parse_attribute("mediaclk:direct=0 rate=90000").is_err());
+    assert!(parse_attribute("mediaclk:direct=0 rate=90000/0").is_err());
+    assert!(parse_attribute("mediaclk:sender=1").is_err());
+    assert!(parse_attribute("mediaclk:IEEE1722=").is_err());
+}
+
+#[test]
+fn test_parse_attribute_t38() {
+    let check_version = make_check_parse!(u8, SdpAttribute::T38FaxVersion);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_version, SdpAttribute::T38FaxVersion);
+    check_parse_and_serialize("T38FaxVersion:0");
+    check_parse_and_serialize("T38FaxVersion:3");
+    assert_eq!(check_version("t38faxversion:2"), 2);
+    assert!(parse_attribute("T38FaxVersion:4").is_err());
+    assert!(parse_attribute("T38FaxVersion:").is_err());
+
+    let check_rate = make_check_parse!(u32, SdpAttribute::T38MaxBitRate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_rate, SdpAttribute::T38MaxBitRate);
+    check_parse_and_serialize("T38MaxBitRate:14400");
+    assert!(parse_attribute("T38MaxBitRate:0").is_err());
+    assert!(parse_attribute("T38MaxBitRate:fast").is_err());
+
+    let check_buffer = make_check_parse!(u32, SdpAttribute::T38FaxMaxBuffer);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_buffer, SdpAttribute::T38FaxMaxBuffer);
+    check_parse_and_serialize("T38FaxMaxBuffer:262");
+
+    let check_datagram = make_check_parse!(u32, SdpAttribute::T38FaxMaxDatagram);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_datagram, SdpAttribute::T38FaxMaxDatagram);
+    check_parse_and_serialize("T38FaxMaxDatagram:90");
+
+    let check_rm = make_check_parse!(SdpT38FaxRateManagement, SdpAttribute::T38FaxRateManagement);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_rm, SdpAttribute::T38FaxRateManagement);
+    check_parse_and_serialize("T38FaxRateManagement:localTCF");
+    check_parse_and_serialize("T38FaxRateManagement:transferredTCF");
+    assert_eq!(
+        check_rm("T38FaxRateManagement:LOCALTCF"),
+        SdpT38FaxRateManagement::LocalTcf
+    );
+    assert!(parse_attribute("T38FaxRateManagement:remoteTCF").is_err());
+
+    let check_ec = make_check_parse!(SdpT38FaxUdpEc, SdpAttribute::T38FaxUdpEc);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_ec, SdpAttribute::T38FaxUdpEc);
+    check_parse_and_serialize("T38FaxUdpEC:t38UDPNoEC");
+    check_parse_and_serialize("T38FaxUdpEC:t38UDPRedundancy");
+    check_parse_and_serialize("T38FaxUdpEC:t38UDPFEC");
+    assert!(SdpT38FaxUdpEc::NoEc < SdpT38FaxUdpEc::Fec);
+    assert!(parse_attribute("T38FaxUdpEC:t38UDPParity").is_err());
+
+    let check_flag = make_check_parse!(bool, SdpAttribute::T38FaxFillBitRemoval);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_flag, SdpAttribute::T38FaxFillBitRemoval);
+    check_parse_and_serialize("T38FaxFillBitRemoval");
+    check_parse_and_serialize("T38FaxFillBitRemoval:0");
+    assert!(check_flag("T38FaxFillBitRemoval:1"));
+    assert!(!check_flag("T38FaxFillBitRemoval:0"));
+    assert!(parse_attribute("T38FaxFillBitRemoval:2").is_err());
+
+    let check_mmr = make_check_parse!(bool, SdpAttribute::T38FaxTranscodingMmr);
+    assert!(check_mmr("T38FaxTranscodingMMR"));
+    let check_jbig = make_check_parse!(bool, SdpAttribute::T38FaxTranscodingJbig);
+    assert!(!check_jbig("T38FaxTranscodingJBIG:0"));
+
+    assert!(!SdpAttribute::T38FaxVersion(0).allowed_at_session_level());
+    assert!(SdpAttribute::T38FaxVersion(0).allowed_at_media_level());
+}
+
 #[test]
 fn test_parse_attribute_rtcp_fb() {
     let check_parse = make_check_parse!(SdpAttributeRtcpFb, SdpAttribute::Rtcpfb);
@@ -1100,3 +1347,26 @@ fn test_parse_attribute_ssrc_group() {
 fn test_parse_unknown_attribute() {
     assert!(parse_attribute("unknown").is_err())
 }
+
+#[test]
+fn test_rid_parameters_intersect() {
+    let offered = SdpAttributeRidParameters {
+        max_width: 1920,
+        max_height: 1080,
+        max_br: 0,
+        unknown: vec!["scale-down-by=2".to_string()],
+        ..Default::default()
+    };
+    let local = SdpAttributeRidParameters {
+        max_width: 1280,
+        max_br: 500_000,
+        ..Default::default()
+    };
+    let intersected = offered.intersect(&local);
+    assert_eq!(intersected.max_width, 1280);
+    assert_eq!(intersected.max_height, 1080);
+    assert_eq!(intersected.max_br, 500_000);
+    assert_eq!(intersected.max_fps, 0);
+    assert!(intersected.unknown.is_empty());
+    assert_eq!(SdpSingleDirection::Send.reverse(), SdpSingleDirection::Recv);
+}
@@ -0,0 +1,214 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use address::{Address, AddressClass};
+use attribute_type::{
+    SdpAttribute, SdpAttributeCandidate, SdpAttributeCandidateTransport, SdpAttributeCandidateType,
+};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use std::convert::TryFrom;
+use std::fmt;
+use SdpSession;
+
+// RFC 8445 section 5.1.2.1: priorities are positive 32 bit signed integers
+pub const MAX_CANDIDATE_PRIORITY: u64 = (1 << 31) - 1;
+
+impl SdpAttributeCandidateType {
+    // The type preferences recommended by RFC 8445 section 5.1.2.2
+    pub fn type_preference(&self) -> u8 {
+        match *self {
+            SdpAttributeCandidateType::Host => 126,
+            SdpAttributeCandidateType::Prflx => 110,
+            SdpAttributeCandidateType::Srflx => 100,
+            SdpAttributeCandidateType::Relay => 0,
+        }
+    }
+}
+
+/*
+ * RFC 8445 section 5.1.2.1
+ * priority = (2^24)*(type preference) +
+ *            (2^8)*(local preference) +
+ *            (2^0)*(256 - component ID)
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpCandidatePriority {
+    pub type_preference: u8,
+    pub local_preference: u16,
+    pub component: u16,
+}
+
+impl SdpCandidatePriority {
+    pub fn new(
+        type_preference: u8,
+        local_preference: u16,
+        component: u16,
+    ) -> Result<SdpCandidatePriority, SdpParserInternalError> {
+        if type_preference > 126 {
+            return Err(SdpParserInternalError::Generic(
+                "ICE type preference must be in range [0,126]".to_string(),
+            ));
+        }
+        if component == 0 || component > 256 {
+            return Err(SdpParserInternalError::Generic(
+                "ICE component ID must be in range [1,256]".to_string(),
+            ));
+        }
+        Ok(SdpCandidatePriority {
+            type_preference,
+            local_preference,
+            component,
+        })
+    }
+
+    pub fn for_candidate_type(
+        c_type: &SdpAttributeCandidateType,
+        local_preference: u16,
+        component: u16,
+    ) -> Result<SdpCandidatePriority, SdpParserInternalError> {
+        SdpCandidatePriority::new(c_type.type_preference(), local_preference, component)
+    }
+
+    pub fn value(&self) -> u64 {
+        (u64::from(self.type_preference) << 24)
+            + (u64::from(self.local_preference) << 8)
+            + (256 - u64::from(self.component))
+    }
+
+    /*
+     * Splits a priority into its parts. This only gives meaningful values
+     * for priorities computed with the formula above, other ICE agents may
+     * use different ones.
+     */
+    pub fn decode(priority: u64) -> Result<SdpCandidatePriority, SdpParserInternalError> {
+        validate_candidate_priority(priority)?;
+        Ok(SdpCandidatePriority {
+            type_preference: (priority >> 24) as u8,
+            local_preference: ((priority >> 8) & 0xffff) as u16,
+            component: 256 - (priority & 0xff) as u16,
+        })
+    }
+}
+
+// RFC 8445 section 5.1.2.1: the priority is a positive 32 bit signed integer
+fn validate_candidate_priority(priority: u64) -> Result<(), SdpParserInternalError> {
+    if priority == 0 || priority > MAX_CANDIDATE_PRIORITY {
+        return Err(SdpParserInternalError::Generic(format!(
+            "ICE candidate priority must be in range [1,{}]",
+            MAX_CANDIDATE_PRIORITY
+        ))
+        .with_kind(SdpParserErrorKind::CandidatePriorityOutOfRange));
+    }
+    Ok(())
+}
+
+impl fmt::Display for SdpCandidatePriority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "type preference {}, local preference {}, component {}",
+            self.type_preference, self.local_preference, self.component
+        )
+    }
+}
+
+/*
+ * RFC 8445 section 5.1.1.3
+ * Candidates share a foundation if they have the same type, base address,
+ * transport protocol and, for server reflexive and relayed candidates, the
+ * same STUN or TURN server. The foundation is a 32 bit FNV-1a hash of these,
+ * which is stable across runs and releases.
+ */
+pub fn candidate_foundation(
+    c_type: &SdpAttributeCandidateType,
+    base: &Address,
+    transport: &SdpAttributeCandidateTransport,
+    server: Option<&Address>,
+) -> String {
+    let server = server.map(ToString::to_string).unwrap_or_default();
+    let key = format!("{} {} {} {}", c_type, base, transport, server);
+    let hash = key.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    hash.to_string()
+}
+
+impl SdpAttributeCandidate {
+    // Sets the RFC 8445 priority from the type and component of the candidate
+    pub fn set_computed_priority(
+        &mut self,
+        local_preference: u16,
+    ) -> Result<(), SdpParserInternalError> {
+        let component = u16::try_from(self.component).unwrap_or(0);
+        self.priority =
+            SdpCandidatePriority::for_candidate_type(&self.c_type, local_preference, component)?
+                .value();
+        Ok(())
+    }
+
+    /*
+     * Sets the foundation from the type, base and transport of the candidate
+     * and the STUN or TURN server it was obtained from. The base of reflexive
+     * candidates is their related address, host and relayed candidates are
+     * their own base.
+     */
+    pub fn set_computed_foundation(&mut self, server: Option<&Address>) {
+        let base = match self.c_type {
+            SdpAttributeCandidateType::Host | SdpAttributeCandidateType::Relay => &self.address,
+            SdpAttributeCandidateType::Srflx | SdpAttributeCandidateType::Prflx => {
+                self.raddr.as_ref().unwrap_or(&self.address)
+            }
+        };
+        self.foundation = candidate_foundation(&self.c_type, base, &self.transport, server);
+    }
+
+    /*
+     * Candidates with a priority outside of the RFC 8445 range are parsed, so
+     * that they survive a round trip, this reports them.
+     */
+    pub fn validate_priority(&self) -> Result<(), SdpParserInternalError> {
+        validate_candidate_priority(self.priority)
+    }
+
+    pub fn decode_priority(&self) -> Result<SdpCandidatePriority, SdpParserInternalError> {
+        SdpCandidatePriority::decode(self.priority)
+    }
+
+    pub fn address_class(&self) -> AddressClass {
+        self.address.class()
+    }
+}
+
+/*
+ * Removes the candidates of all m-sections for which keep returns false and
+ * returns the number of removed candidates.
+ */
+pub fn retain_candidates<F>(session: &mut SdpSession, mut keep: F) -> usize
+where
+    F: FnMut(&SdpAttributeCandidate) -> bool,
+{
+    let mut removed = 0;
+    for media in &mut session.media {
+        media.retain_attributes(|attribute| match attribute {
+            SdpAttribute::Candidate(candidate) if !keep(candidate) => {
+                removed += 1;
+                false
+            }
+            _ => true,
+        });
+    }
+    removed
+}
+
+// Only keeps the candidates with an address of one of the given classes
+pub fn filter_candidates(session: &mut SdpSession, classes: &[AddressClass]) -> usize {
+    retain_candidates(session, |candidate| {
+        classes.contains(&candidate.address_class())
+    })
+}
+
+#[cfg(test)]
+#[path = "./candidate_tests.rs"]
+mod tests;
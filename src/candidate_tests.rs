@@ -0,0 +1,208 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use attribute_type::{parse_attribute, SdpAttribute};
+use std::net::{IpAddr, Ipv4Addr};
+use {parse_sdp, SdpType};
+
+fn ip(a: u8, b: u8, c: u8, d: u8) -> Address {
+    Address::Ip(IpAddr::V4(Ipv4Addr::new(a, b, c, d)))
+}
+
+#[test]
+fn test_candidate_priority() {
+    let priority =
+        SdpCandidatePriority::for_candidate_type(&SdpAttributeCandidateType::Host, 65535, 1)
+            .unwrap();
+    assert_eq!(priority.value(), 2_130_706_431);
+    assert_eq!(
+        SdpCandidatePriority::decode(2_130_706_431).unwrap(),
+        priority
+    );
+
+    let priority =
+        SdpCandidatePriority::for_candidate_type(&SdpAttributeCandidateType::Relay, 1000, 2)
+            .unwrap();
+    assert_eq!(priority.value(), (1000 << 8) + 254);
+    assert_eq!(
+        SdpCandidatePriority::decode(priority.value())
+            .unwrap()
+            .to_string(),
+        "type preference 0, local preference 1000, component 2"
+    );
+
+    assert!(SdpCandidatePriority::new(127, 0, 1).is_err());
+    assert!(SdpCandidatePriority::new(126, 0, 0).is_err());
+    assert!(SdpCandidatePriority::new(126, 0, 257).is_err());
+    assert!(SdpCandidatePriority::decode(0).is_err());
+    assert_eq!(
+        SdpCandidatePriority::decode(MAX_CANDIDATE_PRIORITY + 1)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::CandidatePriorityOutOfRange
+    );
+}
+
+#[test]
+fn test_candidate_foundation() {
+    let udp = SdpAttributeCandidateTransport::Udp;
+    let host = SdpAttributeCandidateType::Host;
+    let srflx = SdpAttributeCandidateType::Srflx;
+    let base = ip(192, 168, 1, 2);
+    let stun = ip(198, 51, 100, 1);
+
+    let foundation = candidate_foundation(&host, &base, &udp, None);
+    assert_eq!(foundation, candidate_foundation(&host, &base, &udp, None));
+    assert!(foundation.len() <= 32);
+    assert_ne!(
+        foundation,
+        candidate_foundation(&host, &base, &SdpAttributeCandidateTransport::Tcp, None)
+    );
+    assert_ne!(
+        foundation,
+        candidate_foundation(&srflx, &base, &udp, Some(&stun))
+    );
+    assert_ne!(
+        candidate_foundation(&srflx, &base, &udp, Some(&stun)),
+        candidate_foundation(&srflx, &base, &udp, Some(&ip(198, 51, 100, 2)))
+    );
+}
+
+#[test]
+fn test_set_computed_candidate_values() {
+    let mut candidate = SdpAttributeCandidate::new(
+        String::new(),
+        1,
+        SdpAttributeCandidateTransport::Udp,
+        0,
+        ip(203, 0, 113, 7),
+        40000,
+        SdpAttributeCandidateType::Srflx,
+    );
+    candidate.raddr = Some(ip(192, 168, 1, 2));
+    candidate.set_computed_priority(65535).unwrap();
+    candidate.set_computed_foundation(Some(&ip(198, 51, 100, 1)));
+    assert_eq!(candidate.decode_priority().unwrap().type_preference, 100);
+    assert_eq!(
+        candidate.foundation,
+        candidate_foundation(
+            &SdpAttributeCandidateType::Srflx,
+            &ip(192, 168, 1, 2),
+            &SdpAttributeCandidateTransport::Udp,
+            Some(&ip(198, 51, 100, 1))
+        )
+    );
+
+    candidate.component = 300;
+    assert!(candidate.set_computed_priority(65535).is_err());
+}
+
+#[test]
+fn test_parse_candidate_priority_range() {
+    let candidate = |priority: u64| match parse_attribute(&format!(
+        "candidate:0 1 UDP {} 192.0.2.1 50000 typ host",
+        priority
+    )) {
+        Ok(SdpType::Attribute(SdpAttribute::Candidate(candidate))) => candidate,
+        _ => unreachable!(),
+    };
+    assert!(candidate(MAX_CANDIDATE_PRIORITY).decode_priority().is_ok());
+
+    // Out of range priorities are parsed, but can not be decoded
+    let error = candidate(MAX_CANDIDATE_PRIORITY + 1)
+        .decode_priority()
+        .unwrap_err();
+    assert_eq!(
+        error.kind(),
+        SdpParserErrorKind::CandidatePriorityOutOfRange
+    );
+    assert!(candidate(0).decode_priority().is_err());
+}
+
+#[test]
+fn test_validate_candidate_priority() {
+    let session = parse_sdp(
+        "v=0\r\n\
+         o=- 0 0 IN IP4 0.0.0.0\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         a=candidate:1 1 UDP 2147483647 192.0.2.1 50000 typ host\r\n\
+         a=candidate:2 1 UDP 0 192.0.2.1 50001 typ host\r\n\
+         a=candidate:3 1 UDP 2147483648 192.0.2.1 50002 typ host\r\n\
+         a=rtpmap:109 opus/48000/2\r\n",
+        true,
+    )
+    .unwrap();
+    let results = session.media[0]
+        .get_attributes()
+        .iter()
+        .filter_map(|a| match a {
+            SdpAttribute::Candidate(c) => Some(c.validate_priority().map_err(|e| e.kind())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        vec![
+            Ok(()),
+            Err(SdpParserErrorKind::CandidatePriorityOutOfRange),
+            Err(SdpParserErrorKind::CandidatePriorityOutOfRange)
+        ]
+    );
+    assert!(session.to_string().contains("UDP 2147483648 192.0.2.1"));
+}
+
+#[test]
+fn test_filter_candidates_by_address_class() {
+    let mut session = parse_sdp(
+        "v=0\r\n\
+         o=- 0 0 IN IP4 0.0.0.0\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         a=candidate:1 1 UDP 2122252543 1f4712db-ea17-4bcf-a596-105139dfd8bf.local 50000 typ host\r\n\
+         a=candidate:2 1 UDP 2122252543 192.168.1.2 50001 typ host\r\n\
+         a=candidate:3 1 UDP 1686052607 203.0.113.7 50002 typ srflx raddr 0.0.0.0 rport 0\r\n\
+         a=rtpmap:109 opus/48000/2\r\n",
+        true,
+    )
+    .unwrap();
+    let classes = |session: &SdpSession| {
+        session.media[0]
+            .get_attributes()
+            .iter()
+            .filter_map(|a| match a {
+                SdpAttribute::Candidate(c) => Some(c.address_class()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        classes(&session),
+        vec![
+            AddressClass::Mdns,
+            AddressClass::Private,
+            AddressClass::Public
+        ]
+    );
+
+    assert_eq!(
+        filter_candidates(&mut session, &[AddressClass::Mdns, AddressClass::Public]),
+        1
+    );
+    assert_eq!(
+        classes(&session),
+        vec![AddressClass::Mdns, AddressClass::Public]
+    );
+    assert_eq!(
+        retain_candidates(&mut session, |c| c.address_class() != AddressClass::Mdns),
+        1
+    );
+    assert_eq!(classes(&session), vec![AddressClass::Public]);
+    assert_eq!(session.media[0].get_attributes().len(), 2);
+}
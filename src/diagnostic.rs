@@ -0,0 +1,617 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use error::{SdpParserError, SdpParserErrorKind, SdpSpan};
+use std::fmt;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_ERROR: &str = "\x1b[1;31m";
+const ANSI_WARNING: &str = "\x1b[1;33m";
+const ANSI_GUTTER: &str = "\x1b[1;34m";
+
+/*
+ * Renders a parser error together with the lines of the SDP around it,
+ * similar to compiler diagnostics:
+ *
+ * error[E004]: Integer parsing error: ...
+ *  --> line 7, column 23
+ *   |
+ * 6 | m=video 9 UDP/TLS/RTP/SAVPF 96
+ * 7 | a=fmtp:96 max-fs=1200;max-fr=foo
+ *   |                       ^^^^^^^^^^
+ *   = hint: RFC 8866 section 6.15: ...
+ *
+ * Line numbers are shown one based.
+ */
+pub struct SdpDiagnostic<'a> {
+    error: &'a SdpParserError,
+    sdp: &'a str,
+    context: usize,
+    colored: bool,
+}
+
+impl<'a> SdpDiagnostic<'a> {
+    pub fn new(error: &'a SdpParserError, sdp: &'a str) -> SdpDiagnostic<'a> {
+        SdpDiagnostic {
+            error,
+            sdp,
+            context: 1,
+            colored: false,
+        }
+    }
+
+    // Number of lines shown before and after the offending lines
+    pub fn context(mut self, lines: usize) -> SdpDiagnostic<'a> {
+        self.context = lines;
+        self
+    }
+
+    // Use ANSI escape sequences for terminal output
+    pub fn colored(mut self, colored: bool) -> SdpDiagnostic<'a> {
+        self.colored = colored;
+        self
+    }
+
+    pub fn severity(&self) -> &'static str {
+        match *self.error {
+            SdpParserError::Unsupported { .. } => "warning",
+            _ => "error",
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        error_code(self.error)
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        error_hint(self.error)
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.colored {
+            format!("{}{}{}", style, text, ANSI_RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    // The offending lines with the span to underline in each of them
+    fn marks(&self, lines: &[&str]) -> Vec<(usize, SdpSpan)> {
+        let whole_line = |line_number: usize| {
+            lines
+                .get(line_number)
+                .map(|line| (line_number, SdpSpan::new(0, line.len())))
+        };
+        match *self.error {
+            SdpParserError::Line { line_number, .. }
+            | SdpParserError::Unsupported { line_number, .. } => self
+                .error
+                .span()
+                .map(|span| (line_number, span))
+                .or_else(|| whole_line(line_number))
+                .into_iter()
+                .collect(),
+            SdpParserError::Sequence {
+                ref line_numbers, ..
+            } => line_numbers.iter().filter_map(|l| whole_line(*l)).collect(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for SdpDiagnostic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines: Vec<&str> = self.sdp.lines().collect();
+        let marks = self.marks(&lines);
+        let message = match *self.error {
+            SdpParserError::Line { ref error, .. }
+            | SdpParserError::Unsupported { ref error, .. } => error.to_string(),
+            SdpParserError::Sequence { ref message, .. } => message.clone(),
+        };
+        let style = match self.severity() {
+            "warning" => ANSI_WARNING,
+            _ => ANSI_ERROR,
+        };
+
+        writeln!(
+            f,
+            "{}{}",
+            self.paint(style, &format!("{}[{}]", self.severity(), self.code())),
+            self.paint(ANSI_BOLD, &format!(": {}", message))
+        )?;
+
+        let shown: Vec<usize> = (0..lines.len())
+            .filter(|i| {
+                marks
+                    .iter()
+                    .any(|(l, _)| *i + self.context >= *l && *i <= *l + self.context)
+            })
+            .collect();
+        let width = shown.last().map_or(1, |l| (l + 1).to_string().len());
+        let gutter =
+            |number: &str| self.paint(ANSI_GUTTER, &format!("{:>w$} |", number, w = width));
+
+        if let Some(&(line_number, span)) = marks.first() {
+            let column = lines
+                .get(line_number)
+                .and_then(|line| line.get(..span.start))
+                .map_or(span.start, |prefix| prefix.chars().count())
+                + 1;
+            writeln!(
+                f,
+                "{}{} line {}, column {}",
+                " ".repeat(width),
+                self.paint(ANSI_GUTTER, "-->"),
+                line_number + 1,
+                column
+            )?;
+            writeln!(f, "{}", gutter(""))?;
+        }
+        let mut previous: Option<usize> = None;
+        for i in shown {
+            if matches!(previous, Some(p) if p + 1 != i) {
+                writeln!(f, "{}", self.paint(ANSI_GUTTER, "..."))?;
+            }
+            previous = Some(i);
+            writeln!(f, "{} {}", gutter(&(i + 1).to_string()), lines[i])?;
+            for &(_, span) in marks.iter().filter(|(l, _)| *l == i) {
+                let chars = |range: Option<&str>| range.map_or(0, |s| s.chars().count());
+                let offset = chars(lines[i].get(..span.start));
+                let length = chars(lines[i].get(span.start..span.end)).max(1);
+                writeln!(
+                    f,
+                    "{} {}{}",
+                    gutter(""),
+                    " ".repeat(offset),
+                    self.paint(style, &"^".repeat(length))
+                )?;
+            }
+        }
+        if let Some(hint) = self.hint() {
+            writeln!(
+                f,
+                "{} {}",
+                self.paint(ANSI_GUTTER, &format!("{:>w$} =", "", w = width)),
+                self.paint(ANSI_BOLD, &format!("hint: {}", hint))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/*
+ * The code of every error kind. Codes are stable and never reused: new kinds
+ * get the next free number of their group. The prefix is E for errors, W for
+ * unsupported SDP and S for sequence errors.
+ */
+pub fn error_code(error: &SdpParserError) -> &'static str {
+    match error.kind() {
+        // Errors which were not given a more specific kind
+        SdpParserErrorKind::Generic => "E003",
+        SdpParserErrorKind::Unsupported => "W001",
+        SdpParserErrorKind::UnknownAddressType => "E001",
+        SdpParserErrorKind::AddressTypeMismatch => "E002",
+        SdpParserErrorKind::InvalidInteger => "E004",
+        SdpParserErrorKind::InvalidFloat => "E005",
+        SdpParserErrorKind::InvalidDomain => "E006",
+        SdpParserErrorKind::InvalidIpAddress => "E007",
+        SdpParserErrorKind::UnresolvedAddress => "E008",
+        SdpParserErrorKind::MdnsNameNotLocal => "E009",
+        SdpParserErrorKind::MdnsNameNotUuid => "E010",
+        // Whole SDP and line structure
+        SdpParserErrorKind::SdpEmpty => "E101",
+        SdpParserErrorKind::SdpTooShort => "E102",
+        SdpParserErrorKind::LineMissingEquals => "E103",
+        SdpParserErrorKind::LineMissingType => "E104",
+        SdpParserErrorKind::LineTypeTooLong => "E105",
+        SdpParserErrorKind::LineTypeEmpty => "E106",
+        SdpParserErrorKind::LineMissingValue => "E107",
+        SdpParserErrorKind::LineEmptyValue => "E108",
+        SdpParserErrorKind::LineTypeUnsupported => "W109",
+        SdpParserErrorKind::LineTypeUnknown => "E110",
+        // Session level lines
+        SdpParserErrorKind::VersionUnsupported => "E201",
+        SdpParserErrorKind::OriginMissingUsername => "E202",
+        SdpParserErrorKind::OriginMissingSessionId => "E203",
+        SdpParserErrorKind::OriginMissingSessionVersion => "E204",
+        SdpParserErrorKind::OriginMissingNetworkType => "E205",
+        SdpParserErrorKind::OriginMissingAddressType => "E206",
+        SdpParserErrorKind::OriginMissingAddress => "E207",
+        SdpParserErrorKind::OriginAddressTypeMismatch => "E208",
+        SdpParserErrorKind::NetworkTypeUnsupported => "E209",
+        SdpParserErrorKind::ConnectionTokenCount => "E210",
+        SdpParserErrorKind::ConnectionNotMulticast => "E211",
+        SdpParserErrorKind::ConnectionMissingTtl => "E212",
+        SdpParserErrorKind::ConnectionTtlNotAllowed => "E213",
+        SdpParserErrorKind::ConnectionAddressCountOutOfRange => "E214",
+        SdpParserErrorKind::BandwidthTokenCount => "E215",
+        SdpParserErrorKind::TimingTokenCount => "E216",
+        // Media lines
+        SdpParserErrorKind::MediaUnknownType => "W301",
+        SdpParserErrorKind::MediaUnknownProtocol => "W302",
+        SdpParserErrorKind::MediaTooFewTokens => "E303",
+        SdpParserErrorKind::MediaMissingPort => "E304",
+        SdpParserErrorKind::MediaPortOutOfRange => "E305",
+        SdpParserErrorKind::MediaFormatOutOfRange => "E306",
+        // Attributes
+        SdpParserErrorKind::AttributeUnknown => "W401",
+        SdpParserErrorKind::AttributeUnexpectedValue => "E402",
+        SdpParserErrorKind::AttributeMissingValue => "E403",
+        SdpParserErrorKind::AttributeNotAllowedAtSessionLevel => "S404",
+        SdpParserErrorKind::AttributeNotAllowedAtMediaLevel => "S405",
+        SdpParserErrorKind::DirectionUnknown => "E406",
+        SdpParserErrorKind::CandidateTooFewTokens => "E407",
+        SdpParserErrorKind::CandidateUnknownTransport => "E408",
+        SdpParserErrorKind::CandidatePortOutOfRange => "E409",
+        SdpParserErrorKind::CandidatePriorityOutOfRange => "E410",
+        SdpParserErrorKind::CandidateMissingTyp => "E411",
+        SdpParserErrorKind::CandidateUnknownType => "E412",
+        SdpParserErrorKind::CandidateRportOutOfRange => "E413",
+        SdpParserErrorKind::CandidateUnknownTcpType => "E414",
+        SdpParserErrorKind::CandidateExtensionWithoutValue => "W415",
+        SdpParserErrorKind::DtlsMessageTokenCount => "E416",
+        SdpParserErrorKind::DtlsMessageUnknownRole => "E417",
+        SdpParserErrorKind::ExtmapTooFewTokens => "E418",
+        SdpParserErrorKind::ExtmapUnknownDirection => "E419",
+        SdpParserErrorKind::ExtmapInvalidExtensionAttributes => "E420",
+        SdpParserErrorKind::FingerprintTokenCount => "E421",
+        SdpParserErrorKind::FingerprintUnsupportedHashAlgorithm => "W422",
+        SdpParserErrorKind::FingerprintInvalidByte => "E423",
+        SdpParserErrorKind::FingerprintWrongLength => "E424",
+        SdpParserErrorKind::FmtpMissingParameters => "W425",
+        SdpParserErrorKind::FmtpInvalidParameter => "E426",
+        SdpParserErrorKind::FmtpInvalidBoolean => "E427",
+        SdpParserErrorKind::FmtpProfileLevelIdOutOfRange => "E428",
+        SdpParserErrorKind::FmtpPacketizationModeOutOfRange => "E429",
+        SdpParserErrorKind::FmtpRtxMissingApt => "E430",
+        SdpParserErrorKind::FmtpRedOutOfRange => "E431",
+        SdpParserErrorKind::FmtpSt2110MissingParameter => "E432",
+        SdpParserErrorKind::GroupMissingSemantics => "E433",
+        SdpParserErrorKind::GroupUnknownSemantics => "W434",
+        SdpParserErrorKind::IceOptionsMissingValue => "E435",
+        SdpParserErrorKind::IcePacingOutOfRange => "E436",
+        SdpParserErrorKind::ImageAttrMissingPayloadType => "E437",
+        SdpParserErrorKind::ImageAttrMissingDirection => "E438",
+        SdpParserErrorKind::ImageAttrDuplicateDirection => "E439",
+        SdpParserErrorKind::ImageAttrMissingSet => "E440",
+        SdpParserErrorKind::ImageAttrUnclosedSet => "E441",
+        SdpParserErrorKind::ImageAttrMissingX => "E442",
+        SdpParserErrorKind::ImageAttrMissingY => "E443",
+        SdpParserErrorKind::ImageAttrUnclosedRange => "E444",
+        SdpParserErrorKind::ImageAttrRangeFieldCount => "E445",
+        SdpParserErrorKind::ImageAttrDiscreteListTooShort => "E446",
+        SdpParserErrorKind::ImageAttrRatioRangeComponents => "E447",
+        SdpParserErrorKind::ImageAttrRatioRangeOrder => "E448",
+        SdpParserErrorKind::ImageAttrUnclosedSar => "E449",
+        SdpParserErrorKind::ImageAttrSarNotAscending => "E450",
+        SdpParserErrorKind::ImageAttrParNotOpened => "E451",
+        SdpParserErrorKind::ImageAttrUnclosedPar => "E452",
+        SdpParserErrorKind::ImageAttrTrailingTokens => "E453",
+        SdpParserErrorKind::MsidMissingId => "E454",
+        SdpParserErrorKind::MsidSemanticMissingToken => "E455",
+        SdpParserErrorKind::RemoteCandidateMissingComponent => "E456",
+        SdpParserErrorKind::RemoteCandidateMissingAddress => "E457",
+        SdpParserErrorKind::RemoteCandidateMissingPort => "E458",
+        SdpParserErrorKind::RemoteCandidatePortOutOfRange => "E459",
+        SdpParserErrorKind::RidTooFewTokens => "E460",
+        SdpParserErrorKind::RidInvalidParameter => "E461",
+        SdpParserErrorKind::RtcpMissingPort => "E462",
+        SdpParserErrorKind::RtcpMissingAddressType => "E463",
+        SdpParserErrorKind::RtcpMissingAddress => "E464",
+        SdpParserErrorKind::RtcpFbMissingType => "E465",
+        SdpParserErrorKind::RtcpFbUnknownType => "W466",
+        SdpParserErrorKind::RtcpFbMissingParameter => "E467",
+        SdpParserErrorKind::RtcpFbUnknownParameter => "W468",
+        SdpParserErrorKind::RtpmapMissingPayloadType => "E469",
+        SdpParserErrorKind::RtpmapPayloadTypeOutOfRange => "E470",
+        SdpParserErrorKind::RtpmapMissingCodecName => "E471",
+        SdpParserErrorKind::SctpmapTokenCount => "E472",
+        SdpParserErrorKind::SctpmapUnknownProtocol => "E473",
+        SdpParserErrorKind::SctpPortOutOfRange => "E474",
+        SdpParserErrorKind::SetupUnknownValue => "E475",
+        SdpParserErrorKind::SimulcastMissingDirection => "E476",
+        SdpParserErrorKind::SimulcastDuplicateDirection => "E477",
+        SdpParserErrorKind::SimulcastMissingList => "E478",
+        SdpParserErrorKind::SimulcastUnknownDescriptor => "E479",
+        SdpParserErrorKind::SourceFilterTooFewTokens => "E480",
+        SdpParserErrorKind::SourceFilterUnknownMode => "E481",
+        SdpParserErrorKind::TsRefclkInvalidSource => "E482",
+        SdpParserErrorKind::MediaClkInvalidSource => "E483",
+        SdpParserErrorKind::T38InvalidValue => "E484",
+        SdpParserErrorKind::SsrcMissingId => "E485",
+        SdpParserErrorKind::SsrcGroupMissingSemantics => "E486",
+        SdpParserErrorKind::SsrcGroupUnknownSemantics => "W487",
+        SdpParserErrorKind::SsrcGroupEmpty => "E488",
+        SdpParserErrorKind::T38AnswerNotAllowed => "E489",
+        // Sequence errors
+        SdpParserErrorKind::TooFewLines => "S601",
+        SdpParserErrorKind::MissingVersion => "S602",
+        SdpParserErrorKind::MissingOrigin => "S603",
+        SdpParserErrorKind::MissingSession => "S604",
+        SdpParserErrorKind::SessionLevelInvalidLine => "S605",
+        SdpParserErrorKind::MediaLineInSession => "S606",
+        SdpParserErrorKind::MediaSectionMissingMediaLine => "S607",
+        SdpParserErrorKind::MediaSectionInvalidLine => "S608",
+        SdpParserErrorKind::MediaDuplicateConnection => "S609",
+        SdpParserErrorKind::MediaPortCountMismatch => "S610",
+        SdpParserErrorKind::MissingTiming => "S611",
+        SdpParserErrorKind::MissingConnection => "S612",
+        SdpParserErrorKind::ExtmapAtSessionAndMediaLevel => "S613",
+        SdpParserErrorKind::SimulcastReceiveForSendonly => "S614",
+        SdpParserErrorKind::SimulcastSendForRecvonly => "S615",
+        SdpParserErrorKind::RtcpMuxOnlyWithoutRtcpMux => "S616",
+        SdpParserErrorKind::RidPtUndeclared => "S617",
+        SdpParserErrorKind::SimulcastRidUndeclared => "S618",
+        // Trickle ICE fragments
+        SdpParserErrorKind::FragmentInvalidLine => "S701",
+        SdpParserErrorKind::FragmentMissingMid => "S702",
+        SdpParserErrorKind::FragmentUnknownMid => "E703",
+        SdpParserErrorKind::FragmentUfragMismatch => "E704",
+        // Anonymizer configuration and state
+        SdpParserErrorKind::AnonymizerStateConflict => "E801",
+        SdpParserErrorKind::AnonymizerKeyMismatch => "E802",
+        SdpParserErrorKind::AnonymizerNamespaceOutOfRange => "E803",
+        SdpParserErrorKind::AnonymizerMissingKey => "E804",
+        // Offer/answer and interoperability helpers
+        SdpParserErrorKind::ExtmapNoFreeId => "E901",
+        SdpParserErrorKind::CandidateInitMediaIndexOutOfRange => "E902",
+        SdpParserErrorKind::CandidateInitUnknownMid => "E903",
+        SdpParserErrorKind::CandidateInitMissingMedia => "E904",
+        SdpParserErrorKind::CandidateInitNotACandidate => "E905",
+        SdpParserErrorKind::CandidateInitUfragMismatch => "E906",
+        SdpParserErrorKind::WhipOfferBundleGroupCount => "E907",
+        SdpParserErrorKind::WhipOfferMissingMid => "E908",
+        SdpParserErrorKind::WhipOfferNotBundled => "E909",
+        SdpParserErrorKind::WhipOfferWrongDirection => "E910",
+        SdpParserErrorKind::WhipOfferMissingTransport => "E911",
+        SdpParserErrorKind::WhipAnswerMissingCandidate => "E912",
+        SdpParserErrorKind::WhipSessionNotBundled => "E913",
+        SdpParserErrorKind::WhipIncompleteIceCredentials => "E914",
+        SdpParserErrorKind::DtlsSetupConflict => "E915",
+    }
+}
+
+// The type of the line and for attributes the attribute name, e.g. "a=fmtp"
+fn line_key(line: &str) -> String {
+    let mut splitted = line.splitn(2, '=');
+    let line_type = splitted.next().unwrap_or("").trim().to_lowercase();
+    match (line_type.as_str(), splitted.next()) {
+        ("a", Some(value)) => {
+            let name = value.trim().split(':').next().unwrap_or("");
+            format!("a={}", name.to_lowercase())
+        }
+        _ => line_type,
+    }
+}
+
+fn sequence_hint(kind: SdpParserErrorKind) -> Option<&'static str> {
+    Some(match kind {
+        SdpParserErrorKind::TooFewLines
+        | SdpParserErrorKind::MissingVersion
+        | SdpParserErrorKind::MissingOrigin
+        | SdpParserErrorKind::MissingSession
+        | SdpParserErrorKind::SessionLevelInvalidLine
+        | SdpParserErrorKind::MediaLineInSession
+        | SdpParserErrorKind::MediaSectionMissingMediaLine
+        | SdpParserErrorKind::MediaSectionInvalidLine => {
+            "RFC 8866 section 5: lines must appear in the order v, o, s, c, b, t, a \
+             followed by the media descriptions"
+        }
+        SdpParserErrorKind::MissingTiming => "RFC 8866 section 5.9: a session needs a t= line",
+        SdpParserErrorKind::MissingConnection => {
+            "RFC 8866 section 5.7: c= is needed at the session level or in every media \
+             description"
+        }
+        SdpParserErrorKind::MediaDuplicateConnection => {
+            "RFC 8866 section 5.7: only a single c= line per media description is supported"
+        }
+        SdpParserErrorKind::AttributeNotAllowedAtSessionLevel => {
+            "RFC 8866 section 6: this attribute is only allowed in media descriptions"
+        }
+        SdpParserErrorKind::AttributeNotAllowedAtMediaLevel => {
+            "RFC 8866 section 6: this attribute is only allowed at the session level"
+        }
+        SdpParserErrorKind::ExtmapAtSessionAndMediaLevel => {
+            "RFC 8285 section 8: extmap must not be used at the session and the media \
+             level at the same time"
+        }
+        SdpParserErrorKind::SimulcastReceiveForSendonly
+        | SdpParserErrorKind::SimulcastSendForRecvonly => {
+            "RFC 8853 section 5.1: the simulcast directions must match the direction of \
+             the media description"
+        }
+        SdpParserErrorKind::SimulcastRidUndeclared => {
+            "RFC 8853 section 5.1: every rid of simulcast must be declared with a=rid"
+        }
+        SdpParserErrorKind::RidPtUndeclared => {
+            "RFC 8851 section 4: the pt restriction of a=rid may only list formats of the \
+             m= line"
+        }
+        SdpParserErrorKind::RtcpMuxOnlyWithoutRtcpMux => {
+            "RFC 8858 section 4: a=rtcp-mux-only must be accompanied by a=rtcp-mux"
+        }
+        SdpParserErrorKind::FragmentInvalidLine => {
+            "RFC 8840 section 9: an sdpfrag may only contain ICE attributes, mids and \
+             candidates"
+        }
+        SdpParserErrorKind::FragmentMissingMid => {
+            "RFC 8840 section 9: every media description of an sdpfrag needs an a=mid"
+        }
+        _ => return None,
+    })
+}
+
+// The syntax of the line, for errors which only the line tells more about
+fn line_hint(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "v" => "RFC 8866 section 5.1: the protocol version must be v=0",
+        "o" => {
+            "RFC 8866 section 5.2: o=<username> <sess-id> <sess-version> <nettype> \
+             <addrtype> <unicast-address>"
+        }
+        "s" => "RFC 8866 section 5.3: s=<session name>, use s=- for none",
+        "c" => "RFC 8866 section 5.7: c=<nettype> <addrtype> <connection-address>",
+        "b" => "RFC 8866 section 5.8: b=<bwtype>:<bandwidth>",
+        "t" => "RFC 8866 section 5.9: t=<start-time> <stop-time>",
+        "m" => "RFC 8866 section 5.14: m=<media> <port>[/<number of ports>] <proto> <fmt> ...",
+        "e" | "i" | "k" | "p" | "r" | "u" | "z" => {
+            "RFC 8866 section 5: this line type is valid SDP but not supported"
+        }
+        "a=candidate" => {
+            "RFC 8839 section 5.1: candidate:<foundation> <component-id> <transport> \
+             <priority> <address> <port> typ <cand-type>"
+        }
+        "a=extmap" => {
+            "RFC 8285 section 8: extmap:<id>[/<direction>] <uri>, id must be 1-14 or \
+             1-255 with extmap-allow-mixed"
+        }
+        "a=fingerprint" => "RFC 8122 section 5: fingerprint:<hash-func> <fingerprint>",
+        "a=fmtp" => "RFC 8866 section 6.15: fmtp:<format> <format specific parameters>",
+        "a=rid" => "RFC 8851 section 10: rid:<rid-id> <send|recv> [<restrictions>]",
+        "a=rtpmap" => {
+            "RFC 8866 section 6.6: rtpmap:<payload type> <encoding name>/<clock rate>\
+             [/<encoding parameters>]"
+        }
+        "a=setup" => "RFC 4145 section 4: setup:<active|passive|actpass|holdconn>",
+        "a=simulcast" => "RFC 8853 section 5.1: simulcast:<send|recv> <rid list> ...",
+        key if key.starts_with("a=") => "RFC 8866 section 5.13: a=<attribute-name>[:<value>]",
+        _ => return None,
+    })
+}
+
+fn kind_hint(kind: SdpParserErrorKind) -> Option<&'static str> {
+    let key = match kind {
+        SdpParserErrorKind::SdpEmpty
+        | SdpParserErrorKind::SdpTooShort
+        | SdpParserErrorKind::LineMissingEquals
+        | SdpParserErrorKind::LineMissingType
+        | SdpParserErrorKind::LineTypeTooLong
+        | SdpParserErrorKind::LineTypeEmpty
+        | SdpParserErrorKind::LineMissingValue
+        | SdpParserErrorKind::LineEmptyValue
+        | SdpParserErrorKind::LineTypeUnknown => {
+            return Some("RFC 8866 section 5: every line has the form <type>=<value>")
+        }
+        SdpParserErrorKind::LineTypeUnsupported => "e",
+        SdpParserErrorKind::VersionUnsupported => "v",
+        SdpParserErrorKind::OriginMissingUsername
+        | SdpParserErrorKind::OriginMissingSessionId
+        | SdpParserErrorKind::OriginMissingSessionVersion
+        | SdpParserErrorKind::OriginMissingNetworkType
+        | SdpParserErrorKind::OriginMissingAddressType
+        | SdpParserErrorKind::OriginMissingAddress
+        | SdpParserErrorKind::OriginAddressTypeMismatch => "o",
+        SdpParserErrorKind::NetworkTypeUnsupported => {
+            return Some("RFC 8866 section 5.7: IN is the only supported network type")
+        }
+        SdpParserErrorKind::ConnectionTokenCount => "c",
+        SdpParserErrorKind::ConnectionNotMulticast
+        | SdpParserErrorKind::ConnectionMissingTtl
+        | SdpParserErrorKind::ConnectionTtlNotAllowed
+        | SdpParserErrorKind::ConnectionAddressCountOutOfRange => {
+            return Some(
+                "RFC 8866 section 5.7: only multicast addresses have a number of addresses, \
+                 IP4 multicast addresses need a TTL and IP6 ones must not have one",
+            )
+        }
+        SdpParserErrorKind::BandwidthTokenCount => "b",
+        SdpParserErrorKind::TimingTokenCount => "t",
+        SdpParserErrorKind::MediaTooFewTokens | SdpParserErrorKind::MediaMissingPort => "m",
+        SdpParserErrorKind::MediaUnknownType => {
+            return Some(
+                "RFC 8866 section 5.14: the supported media types are audio, video, text, \
+                 application and image",
+            )
+        }
+        SdpParserErrorKind::MediaUnknownProtocol => {
+            return Some("RFC 8866 section 5.14: the transport protocol of m= is not supported")
+        }
+        SdpParserErrorKind::MediaPortOutOfRange => {
+            return Some("RFC 8866 section 5.14: the port of m= must be <= 65535")
+        }
+        SdpParserErrorKind::MediaFormatOutOfRange => {
+            return Some(
+                "RFC 3551 section 6: RTP formats are a static payload type or a dynamic one \
+                 in the range 35-63 or 96-127",
+            )
+        }
+        SdpParserErrorKind::AttributeUnknown
+        | SdpParserErrorKind::AttributeUnexpectedValue
+        | SdpParserErrorKind::AttributeMissingValue => "a=",
+        SdpParserErrorKind::CandidateTooFewTokens
+        | SdpParserErrorKind::CandidateMissingTyp
+        | SdpParserErrorKind::CandidateExtensionWithoutValue => "a=candidate",
+        SdpParserErrorKind::CandidateUnknownTransport => {
+            return Some("RFC 8839 section 5.1: the candidate transport must be UDP or TCP")
+        }
+        SdpParserErrorKind::CandidatePortOutOfRange
+        | SdpParserErrorKind::CandidateRportOutOfRange => {
+            return Some("RFC 8839 section 5.1: candidate ports must be <= 65535")
+        }
+        SdpParserErrorKind::CandidatePriorityOutOfRange => {
+            return Some("RFC 8445 section 5.1.2: the priority must be in the range 1 to 2^31-1")
+        }
+        SdpParserErrorKind::CandidateUnknownType => {
+            return Some("RFC 8839 section 5.1: typ must be host, srflx, prflx or relay")
+        }
+        SdpParserErrorKind::CandidateUnknownTcpType => {
+            return Some("RFC 6544 section 4.5: tcptype must be active, passive or so")
+        }
+        SdpParserErrorKind::ExtmapTooFewTokens
+        | SdpParserErrorKind::ExtmapUnknownDirection
+        | SdpParserErrorKind::ExtmapInvalidExtensionAttributes => "a=extmap",
+        SdpParserErrorKind::FingerprintTokenCount
+        | SdpParserErrorKind::FingerprintUnsupportedHashAlgorithm
+        | SdpParserErrorKind::FingerprintInvalidByte
+        | SdpParserErrorKind::FingerprintWrongLength => "a=fingerprint",
+        SdpParserErrorKind::FmtpMissingParameters
+        | SdpParserErrorKind::FmtpInvalidParameter
+        | SdpParserErrorKind::FmtpInvalidBoolean
+        | SdpParserErrorKind::FmtpProfileLevelIdOutOfRange
+        | SdpParserErrorKind::FmtpPacketizationModeOutOfRange
+        | SdpParserErrorKind::FmtpRtxMissingApt
+        | SdpParserErrorKind::FmtpRedOutOfRange
+        | SdpParserErrorKind::FmtpSt2110MissingParameter => "a=fmtp",
+        SdpParserErrorKind::RidTooFewTokens | SdpParserErrorKind::RidInvalidParameter => "a=rid",
+        SdpParserErrorKind::RtpmapMissingPayloadType
+        | SdpParserErrorKind::RtpmapPayloadTypeOutOfRange
+        | SdpParserErrorKind::RtpmapMissingCodecName => "a=rtpmap",
+        SdpParserErrorKind::SetupUnknownValue => "a=setup",
+        SdpParserErrorKind::SimulcastMissingDirection
+        | SdpParserErrorKind::SimulcastDuplicateDirection
+        | SdpParserErrorKind::SimulcastMissingList
+        | SdpParserErrorKind::SimulcastUnknownDescriptor => "a=simulcast",
+        _ => return sequence_hint(kind),
+    };
+    line_hint(key)
+}
+
+/*
+ * The hint is chosen by the kind of the error. Only errors which were not
+ * given a more specific kind, e.g. a malformed integer, fall back to the
+ * syntax of their line.
+ */
+pub fn error_hint(error: &SdpParserError) -> Option<&'static str> {
+    let line = match *error {
+        SdpParserError::Line { ref line, .. } | SdpParserError::Unsupported { ref line, .. } => {
+            line
+        }
+        SdpParserError::Sequence { kind, .. } => return sequence_hint(kind),
+    };
+    match error.kind() {
+        SdpParserErrorKind::Generic
+        | SdpParserErrorKind::Unsupported
+        | SdpParserErrorKind::UnknownAddressType
+        | SdpParserErrorKind::AddressTypeMismatch
+        | SdpParserErrorKind::InvalidInteger
+        | SdpParserErrorKind::InvalidFloat
+        | SdpParserErrorKind::InvalidDomain
+        | SdpParserErrorKind::InvalidIpAddress => line_hint(&line_key(line)),
+        kind => kind_hint(kind),
+    }
+}
+
+#[cfg(test)]
+#[path = "./diagnostic_tests.rs"]
+mod tests;
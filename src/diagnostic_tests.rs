@@ -0,0 +1,168 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use parse_sdp;
+
+const SDP: &str = "v=0\r\n\
+                   o=- 1 1 IN IP4 0.0.0.0\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   c=IN IP4 0.0.0.0\r\n\
+                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                   a=fmtp:96 max-fs=1200;max-fr=foo\r\n\
+                   a=sendrecv\r\n";
+
+#[test]
+fn test_render_line_error() {
+    let error = parse_sdp(SDP, true).unwrap_err();
+    let diagnostic = SdpDiagnostic::new(&error, SDP);
+
+    assert_eq!(diagnostic.code(), "E004");
+    assert_eq!(diagnostic.severity(), "error");
+    assert_eq!(
+        diagnostic.to_string(),
+        "error[E004]: Integer parsing error: invalid digit found in string\n \
+         --> line 7, column 23\n  \
+         |\n\
+         6 | m=video 9 UDP/TLS/RTP/SAVPF 96\n\
+         7 | a=fmtp:96 max-fs=1200;max-fr=foo\n  \
+         |                       ^^^^^^^^^^\n\
+         8 | a=sendrecv\n  \
+         = hint: RFC 8866 section 6.15: fmtp:<format> <format specific parameters>\n"
+    );
+}
+
+#[test]
+fn test_render_colored() {
+    let error = parse_sdp(SDP, true).unwrap_err();
+    let diagnostic = SdpDiagnostic::new(&error, SDP).colored(true).context(0);
+    let rendered = diagnostic.to_string();
+
+    assert!(rendered.starts_with("\x1b[1;31merror[E004]\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;31m^^^^^^^^^^\x1b[0m"));
+    assert!(!rendered.contains("m=video"));
+}
+
+#[test]
+fn test_render_sequence_error() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\r\n";
+    let error = parse_sdp(sdp, true).unwrap_err();
+    let rendered = SdpDiagnostic::new(&error, sdp).context(0).to_string();
+
+    assert_eq!(
+        rendered,
+        "error[S613]: Extmap can't be define at session and media level\n \
+         --> line 6, column 1\n  \
+         |\n\
+         6 | a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\n  \
+         | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^\n\
+         ...\n\
+         9 | a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\n  \
+         | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^\n  \
+         = hint: RFC 8285 section 8: extmap must not be used at the session and the media \
+         level at the same time\n"
+    );
+}
+
+#[test]
+fn test_sequence_codes_and_hints() {
+    let sdp = "v=0\r\n\
+               s=-\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n";
+    let error = parse_sdp(sdp, true).unwrap_err();
+    let diagnostic = SdpDiagnostic::new(&error, sdp);
+    assert_eq!(diagnostic.code(), "S603");
+    assert_eq!(
+        diagnostic.hint(),
+        Some(
+            "RFC 8866 section 5: lines must appear in the order v, o, s, c, b, t, a \
+             followed by the media descriptions"
+        )
+    );
+
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               a=rtcp-mux-only\r\n";
+    let error = parse_sdp(sdp, true).unwrap_err();
+    let diagnostic = SdpDiagnostic::new(&error, sdp);
+    assert_eq!(diagnostic.code(), "S616");
+    assert_eq!(
+        diagnostic.hint(),
+        Some("RFC 8858 section 4: a=rtcp-mux-only must be accompanied by a=rtcp-mux")
+    );
+}
+
+#[test]
+fn test_render_with_mismatched_sdp() {
+    let error = parse_sdp(SDP, true).unwrap_err();
+    let rendered = SdpDiagnostic::new(&error, "v=0\r\n").to_string();
+    assert!(rendered.starts_with("error[E004]"));
+    assert!(rendered.contains("--> line 7, column 23"));
+}
+
+#[test]
+fn test_warning_code_and_hint() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=foo-bar:1\r\n";
+    let error = parse_sdp(sdp, true).unwrap_err();
+    let diagnostic = SdpDiagnostic::new(&error, sdp);
+
+    assert_eq!(diagnostic.severity(), "warning");
+    assert_eq!(diagnostic.code(), "W401");
+    assert_eq!(
+        diagnostic.hint(),
+        Some("RFC 8866 section 5.13: a=<attribute-name>[:<value>]")
+    );
+}
+
+#[test]
+fn test_hints_follow_the_error_kind() {
+    let hint = |sdp: &str| {
+        let error = parse_sdp(sdp, true).unwrap_err();
+        let diagnostic = SdpDiagnostic::new(&error, sdp);
+        (diagnostic.code(), diagnostic.hint())
+    };
+    let session = "v=0\r\n\
+                   o=- 1 1 IN IP4 0.0.0.0\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   c=IN IP4 0.0.0.0\r\n";
+
+    let (_, media_hint) = hint(&format!("{}m=foo 9 RTP/AVP 0\r\n", session));
+    assert!(media_hint.unwrap().contains("media types"));
+    assert!(!media_hint.unwrap().contains("65535"));
+
+    let (_, candidate_hint) = hint(&format!(
+        "{}m=audio 9 RTP/AVP 0\r\n\
+         a=candidate:1 1 FOO 2122252543 192.0.2.1 50000 typ host\r\n",
+        session
+    ));
+    assert!(candidate_hint.unwrap().contains("UDP or TCP"));
+
+    let (code, version_hint) = hint(&format!("v=1{}", &session[3..]));
+    assert_eq!(code, "E201");
+    assert_eq!(
+        version_hint,
+        Some("RFC 8866 section 5.1: the protocol version must be v=0")
+    );
+}
@@ -0,0 +1,135 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{SdpAttribute, SdpAttributeSetup, SdpAttributeType};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use media_type::SdpMedia;
+use SdpSession;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpDtlsRole {
+    Client,
+    Server,
+}
+
+impl SdpDtlsRole {
+    pub fn opposite(self) -> SdpDtlsRole {
+        match self {
+            SdpDtlsRole::Client => SdpDtlsRole::Server,
+            SdpDtlsRole::Server => SdpDtlsRole::Client,
+        }
+    }
+}
+
+/*
+ * RFC4145 4.1
+ * The media level a=setup overrides the session level one. If neither is
+ * present an offerer is assumed to be active and an answerer passive.
+ */
+pub fn get_setup(session: &SdpSession, media: &SdpMedia, is_offer: bool) -> SdpAttributeSetup {
+    let setup = media
+        .get_attribute(SdpAttributeType::Setup)
+        .or_else(|| session.get_attribute(SdpAttributeType::Setup));
+    match setup {
+        Some(SdpAttribute::Setup(setup)) => setup.clone(),
+        _ if is_offer => SdpAttributeSetup::Active,
+        _ => SdpAttributeSetup::Passive,
+    }
+}
+
+/*
+ * RFC5763 and RFC8842
+ * The setup value an answerer should use in response to the offered one. An
+ * actpass offer is answered with active, so that the handshake is started
+ * without waiting for the answer to arrive at the offerer.
+ */
+pub fn answer_setup(offered: &SdpAttributeSetup) -> SdpAttributeSetup {
+    match *offered {
+        SdpAttributeSetup::Active => SdpAttributeSetup::Passive,
+        SdpAttributeSetup::Actpass => SdpAttributeSetup::Active,
+        SdpAttributeSetup::Holdconn => SdpAttributeSetup::Holdconn,
+        SdpAttributeSetup::Passive => SdpAttributeSetup::Active,
+    }
+}
+
+/*
+ * Returns the DTLS role of the offerer, the answerer takes the opposite one.
+ * The active side is the DTLS client. None is returned if either side holds
+ * the connection.
+ */
+pub fn resolve_dtls_role(
+    offer: &SdpAttributeSetup,
+    answer: &SdpAttributeSetup,
+) -> Result<Option<SdpDtlsRole>, SdpParserInternalError> {
+    match (offer, answer) {
+        (_, SdpAttributeSetup::Actpass) => Err(SdpParserInternalError::Generic(
+            "An answer must not use a=setup:actpass".to_string(),
+        )
+        .with_kind(SdpParserErrorKind::DtlsSetupConflict)),
+        (SdpAttributeSetup::Holdconn, _) | (_, SdpAttributeSetup::Holdconn) => Ok(None),
+        (SdpAttributeSetup::Actpass, SdpAttributeSetup::Active)
+        | (SdpAttributeSetup::Passive, SdpAttributeSetup::Active) => Ok(Some(SdpDtlsRole::Server)),
+        (SdpAttributeSetup::Actpass, SdpAttributeSetup::Passive)
+        | (SdpAttributeSetup::Active, SdpAttributeSetup::Passive) => Ok(Some(SdpDtlsRole::Client)),
+        (offer, answer) => Err(SdpParserInternalError::Generic(format!(
+            "a=setup:{} can not be answered with a=setup:{}",
+            offer, answer
+        ))
+        .with_kind(SdpParserErrorKind::DtlsSetupConflict)),
+    }
+}
+
+pub fn validate_answer_setup(answer: &SdpSession) -> Result<(), SdpParserInternalError> {
+    let actpass = |attr: Option<&SdpAttribute>| match attr {
+        Some(SdpAttribute::Setup(setup)) => *setup == SdpAttributeSetup::Actpass,
+        _ => false,
+    };
+    if actpass(answer.get_attribute(SdpAttributeType::Setup))
+        || answer
+            .media
+            .iter()
+            .any(|media| actpass(media.get_attribute(SdpAttributeType::Setup)))
+    {
+        return Err(SdpParserInternalError::Generic(
+            "An answer must not use a=setup:actpass".to_string(),
+        )
+        .with_kind(SdpParserErrorKind::DtlsSetupConflict));
+    }
+    Ok(())
+}
+
+/*
+ * Resolves the DTLS role of the offerer for every m-section of the answer.
+ * Rejected m-sections have no role.
+ */
+pub fn resolve_dtls_roles(
+    offer: &SdpSession,
+    answer: &SdpSession,
+) -> Result<Vec<Option<SdpDtlsRole>>, SdpParserInternalError> {
+    validate_answer_setup(answer)?;
+    if offer.media.len() != answer.media.len() {
+        return Err(SdpParserInternalError::Generic(
+            "Offer and answer have a different number of media sections".to_string(),
+        ));
+    }
+    offer
+        .media
+        .iter()
+        .zip(answer.media.iter())
+        .map(|(offer_media, answer_media)| {
+            if offer_media.get_port() == 0 || answer_media.get_port() == 0 {
+                return Ok(None);
+            }
+            resolve_dtls_role(
+                &get_setup(offer, offer_media, true),
+                &get_setup(answer, answer_media, false),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./dtls_tests.rs"]
+mod tests;
@@ -0,0 +1,116 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use tests::parse_dummy_sdp_session;
+
+fn create_session(session_setup: Option<&str>, media_setups: &[Option<&str>]) -> SdpSession {
+    let mut sdp = String::new();
+    if let Some(setup) = session_setup {
+        sdp.push_str(&format!("a=setup:{}\r\n", setup));
+    }
+    for setup in media_setups {
+        sdp.push_str("m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n");
+        if let Some(setup) = setup {
+            sdp.push_str(&format!("a=setup:{}\r\n", setup));
+        }
+    }
+    parse_dummy_sdp_session(&sdp)
+}
+
+#[test]
+fn test_resolve_dtls_role() {
+    use attribute_type::SdpAttributeSetup::*;
+
+    assert_eq!(
+        resolve_dtls_role(&Actpass, &Active).unwrap(),
+        Some(SdpDtlsRole::Server)
+    );
+    assert_eq!(
+        resolve_dtls_role(&Actpass, &Passive).unwrap(),
+        Some(SdpDtlsRole::Client)
+    );
+    assert_eq!(
+        resolve_dtls_role(&Active, &Passive).unwrap(),
+        Some(SdpDtlsRole::Client)
+    );
+    assert_eq!(
+        resolve_dtls_role(&Passive, &Active).unwrap(),
+        Some(SdpDtlsRole::Server)
+    );
+    assert_eq!(resolve_dtls_role(&Holdconn, &Active).unwrap(), None);
+    for (offer, answer) in &[(Actpass, Actpass), (Active, Active), (Passive, Passive)] {
+        assert_eq!(
+            resolve_dtls_role(offer, answer).unwrap_err().kind(),
+            SdpParserErrorKind::DtlsSetupConflict
+        );
+    }
+
+    for offered in &[Active, Actpass, Holdconn, Passive] {
+        let answered = answer_setup(offered);
+        assert!(answered != Actpass);
+        assert!(resolve_dtls_role(offered, &answered).is_ok());
+    }
+    assert_eq!(SdpDtlsRole::Client.opposite(), SdpDtlsRole::Server);
+}
+
+#[test]
+fn test_get_setup_inheritance() {
+    let session = create_session(Some("passive"), &[None, Some("holdconn")]);
+    assert!(get_setup(&session, &session.media[0], true) == SdpAttributeSetup::Passive);
+    assert!(get_setup(&session, &session.media[1], true) == SdpAttributeSetup::Holdconn);
+
+    let session = create_session(None, &[None]);
+    assert!(get_setup(&session, &session.media[0], true) == SdpAttributeSetup::Active);
+    assert!(get_setup(&session, &session.media[0], false) == SdpAttributeSetup::Passive);
+}
+
+#[test]
+fn test_resolve_dtls_roles() {
+    let offer = create_session(Some("actpass"), &[None, None, Some("holdconn")]);
+    let answer = create_session(None, &[Some("active"), Some("passive"), None]);
+
+    let roles = resolve_dtls_roles(&offer, &answer).unwrap();
+    assert_eq!(
+        roles,
+        vec![Some(SdpDtlsRole::Server), Some(SdpDtlsRole::Client), None]
+    );
+}
+
+#[test]
+fn test_resolve_dtls_roles_answer_without_setup() {
+    let offer = create_session(None, &[Some("actpass"), None]);
+    let answer = create_session(None, &[None, None]);
+
+    let roles = resolve_dtls_roles(&offer, &answer).unwrap();
+    assert_eq!(
+        roles,
+        vec![Some(SdpDtlsRole::Client), Some(SdpDtlsRole::Client)]
+    );
+}
+
+#[test]
+fn test_resolve_dtls_roles_invalid_answer() {
+    let offer = create_session(None, &[Some("actpass")]);
+
+    let answer = create_session(Some("actpass"), &[None]);
+    assert_eq!(
+        validate_answer_setup(&answer).unwrap_err().kind(),
+        SdpParserErrorKind::DtlsSetupConflict
+    );
+    assert!(resolve_dtls_roles(&offer, &answer).is_err());
+
+    let answer = create_session(None, &[Some("actpass")]);
+    assert!(validate_answer_setup(&answer).is_err());
+
+    let answer = create_session(None, &[Some("passive"), Some("active")]);
+    assert!(validate_answer_setup(&answer).is_ok());
+    assert!(resolve_dtls_roles(&offer, &answer).is_err());
+
+    let offer = create_session(None, &[Some("actpass"), Some("active")]);
+    assert_eq!(
+        resolve_dtls_roles(&offer, &answer).unwrap_err().kind(),
+        SdpParserErrorKind::DtlsSetupConflict
+    );
+}
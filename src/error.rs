@@ -2,6 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+#[cfg(feature = "serialize")]
+use serde::de::{self as serde_de, Deserialize, Deserializer};
 #[cfg(feature = "serialize")]
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::error;
@@ -12,6 +14,367 @@ use address::AddressType;
 use std::num::ParseFloatError;
 use std::num::ParseIntError;
 
+/*
+ * A range of bytes, either within the whole SDP (SdpLine) or within a single
+ * line (errors). The end is exclusive.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl SdpSpan {
+    pub fn new(start: usize, end: usize) -> SdpSpan {
+        SdpSpan { start, end }
+    }
+
+    // Returns the span of token within parent, if token is a subslice of it
+    pub fn from_subslice(parent: &str, token: &str) -> Option<SdpSpan> {
+        let parent_start = parent.as_ptr() as usize;
+        let token_start = token.as_ptr() as usize;
+        if token_start < parent_start || token_start + token.len() > parent_start + parent.len() {
+            return None;
+        }
+        let start = token_start - parent_start;
+        Some(SdpSpan::new(start, start + token.len()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn shift(&self, offset: usize) -> SdpSpan {
+        SdpSpan::new(self.start + offset, self.end + offset)
+    }
+}
+
+/*
+ * Stable identifiers for every distinct parser failure. Unlike the messages
+ * these are not changed between releases and can be used to group errors.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpParserErrorKind {
+    // Errors which were not given a more specific kind
+    Generic,
+    Unsupported,
+    UnknownAddressType,
+    AddressTypeMismatch,
+    InvalidInteger,
+    InvalidFloat,
+    InvalidDomain,
+    InvalidIpAddress,
+    UnresolvedAddress,
+    MdnsNameNotLocal,
+    MdnsNameNotUuid,
+    // Whole SDP and line structure
+    SdpEmpty,
+    SdpTooShort,
+    LineMissingEquals,
+    LineMissingType,
+    LineTypeTooLong,
+    LineTypeEmpty,
+    LineMissingValue,
+    LineEmptyValue,
+    LineTypeUnsupported,
+    LineTypeUnknown,
+    // Session level lines
+    VersionUnsupported,
+    OriginMissingUsername,
+    OriginMissingSessionId,
+    OriginMissingSessionVersion,
+    OriginMissingNetworkType,
+    OriginMissingAddressType,
+    OriginMissingAddress,
+    OriginAddressTypeMismatch,
+    NetworkTypeUnsupported,
+    ConnectionTokenCount,
+    ConnectionNotMulticast,
+    ConnectionMissingTtl,
+    ConnectionTtlNotAllowed,
+    ConnectionAddressCountOutOfRange,
+    BandwidthTokenCount,
+    TimingTokenCount,
+    // Media lines
+    MediaUnknownType,
+    MediaUnknownProtocol,
+    MediaTooFewTokens,
+    MediaMissingPort,
+    MediaPortOutOfRange,
+    MediaFormatOutOfRange,
+    // Attributes
+    AttributeUnknown,
+    AttributeUnexpectedValue,
+    AttributeMissingValue,
+    AttributeNotAllowedAtSessionLevel,
+    AttributeNotAllowedAtMediaLevel,
+    DirectionUnknown,
+    CandidateTooFewTokens,
+    CandidateUnknownTransport,
+    CandidatePortOutOfRange,
+    CandidatePriorityOutOfRange,
+    CandidateMissingTyp,
+    CandidateUnknownType,
+    CandidateRportOutOfRange,
+    CandidateUnknownTcpType,
+    CandidateExtensionWithoutValue,
+    DtlsMessageTokenCount,
+    DtlsMessageUnknownRole,
+    ExtmapTooFewTokens,
+    ExtmapUnknownDirection,
+    ExtmapInvalidExtensionAttributes,
+    FingerprintTokenCount,
+    FingerprintUnsupportedHashAlgorithm,
+    FingerprintInvalidByte,
+    FingerprintWrongLength,
+    FmtpMissingParameters,
+    FmtpInvalidParameter,
+    FmtpInvalidBoolean,
+    FmtpProfileLevelIdOutOfRange,
+    FmtpPacketizationModeOutOfRange,
+    FmtpRtxMissingApt,
+    FmtpRedOutOfRange,
+    FmtpSt2110MissingParameter,
+    GroupMissingSemantics,
+    GroupUnknownSemantics,
+    IceOptionsMissingValue,
+    IcePacingOutOfRange,
+    ImageAttrMissingPayloadType,
+    ImageAttrMissingDirection,
+    ImageAttrDuplicateDirection,
+    ImageAttrMissingSet,
+    ImageAttrUnclosedSet,
+    ImageAttrMissingX,
+    ImageAttrMissingY,
+    ImageAttrUnclosedRange,
+    ImageAttrRangeFieldCount,
+    ImageAttrDiscreteListTooShort,
+    ImageAttrRatioRangeComponents,
+    ImageAttrRatioRangeOrder,
+    ImageAttrUnclosedSar,
+    ImageAttrSarNotAscending,
+    ImageAttrParNotOpened,
+    ImageAttrUnclosedPar,
+    ImageAttrTrailingTokens,
+    MsidMissingId,
+    MsidSemanticMissingToken,
+    RemoteCandidateMissingComponent,
+    RemoteCandidateMissingAddress,
+    RemoteCandidateMissingPort,
+    RemoteCandidatePortOutOfRange,
+    RidTooFewTokens,
+    RidInvalidParameter,
+    RtcpMissingPort,
+    RtcpMissingAddressType,
+    RtcpMissingAddress,
+    RtcpFbMissingType,
+    RtcpFbUnknownType,
+    RtcpFbMissingParameter,
+    RtcpFbUnknownParameter,
+    RtpmapMissingPayloadType,
+    RtpmapPayloadTypeOutOfRange,
+    RtpmapMissingCodecName,
+    SctpmapTokenCount,
+    SctpmapUnknownProtocol,
+    SctpPortOutOfRange,
+    SetupUnknownValue,
+    SimulcastMissingDirection,
+    SimulcastDuplicateDirection,
+    SimulcastMissingList,
+    SimulcastUnknownDescriptor,
+    SourceFilterTooFewTokens,
+    SourceFilterUnknownMode,
+    TsRefclkInvalidSource,
+    MediaClkInvalidSource,
+    T38InvalidValue,
+    SsrcMissingId,
+    SsrcGroupMissingSemantics,
+    SsrcGroupUnknownSemantics,
+    SsrcGroupEmpty,
+    T38AnswerNotAllowed,
+    // Sequence errors
+    TooFewLines,
+    MissingVersion,
+    MissingOrigin,
+    MissingSession,
+    SessionLevelInvalidLine,
+    MediaLineInSession,
+    MediaSectionMissingMediaLine,
+    MediaSectionInvalidLine,
+    MediaDuplicateConnection,
+    MediaPortCountMismatch,
+    MissingTiming,
+    MissingConnection,
+    ExtmapAtSessionAndMediaLevel,
+    SimulcastReceiveForSendonly,
+    SimulcastSendForRecvonly,
+    RtcpMuxOnlyWithoutRtcpMux,
+    RidPtUndeclared,
+    SimulcastRidUndeclared,
+    // Trickle ICE fragments
+    FragmentInvalidLine,
+    FragmentMissingMid,
+    FragmentUnknownMid,
+    FragmentUfragMismatch,
+    // Anonymizer configuration and state
+    AnonymizerStateConflict,
+    AnonymizerKeyMismatch,
+    AnonymizerNamespaceOutOfRange,
+    AnonymizerMissingKey,
+    // Offer/answer and interoperability helpers
+    ExtmapNoFreeId,
+    CandidateInitMediaIndexOutOfRange,
+    CandidateInitUnknownMid,
+    CandidateInitMissingMedia,
+    CandidateInitNotACandidate,
+    CandidateInitUfragMismatch,
+    WhipOfferBundleGroupCount,
+    WhipOfferMissingMid,
+    WhipOfferNotBundled,
+    WhipOfferWrongDirection,
+    WhipOfferMissingTransport,
+    WhipAnswerMissingCandidate,
+    WhipSessionNotBundled,
+    WhipIncompleteIceCredentials,
+    DtlsSetupConflict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpParserErrorCategory {
+    // Malformed or missing tokens
+    Syntax,
+    // Well formed tokens with an invalid value
+    Value,
+    // Addresses and address types
+    Address,
+    // Valid SDP which is not supported
+    Unsupported,
+    // The order or combination of lines
+    Sequence,
+}
+
+impl SdpParserErrorKind {
+    pub fn category(&self) -> SdpParserErrorCategory {
+        match *self {
+            SdpParserErrorKind::TooFewLines
+            | SdpParserErrorKind::MissingVersion
+            | SdpParserErrorKind::MissingOrigin
+            | SdpParserErrorKind::MissingSession
+            | SdpParserErrorKind::SessionLevelInvalidLine
+            | SdpParserErrorKind::MediaLineInSession
+            | SdpParserErrorKind::MediaSectionMissingMediaLine
+            | SdpParserErrorKind::MediaSectionInvalidLine
+            | SdpParserErrorKind::MediaDuplicateConnection
+            | SdpParserErrorKind::MediaPortCountMismatch
+            | SdpParserErrorKind::MissingTiming
+            | SdpParserErrorKind::MissingConnection
+            | SdpParserErrorKind::ExtmapAtSessionAndMediaLevel
+            | SdpParserErrorKind::SimulcastReceiveForSendonly
+            | SdpParserErrorKind::SimulcastSendForRecvonly
+            | SdpParserErrorKind::RtcpMuxOnlyWithoutRtcpMux
+            | SdpParserErrorKind::RidPtUndeclared
+            | SdpParserErrorKind::SimulcastRidUndeclared
+            | SdpParserErrorKind::FragmentInvalidLine
+            | SdpParserErrorKind::FragmentMissingMid
+            | SdpParserErrorKind::AttributeNotAllowedAtSessionLevel
+            | SdpParserErrorKind::AttributeNotAllowedAtMediaLevel => {
+                SdpParserErrorCategory::Sequence
+            }
+            SdpParserErrorKind::UnknownAddressType
+            | SdpParserErrorKind::AddressTypeMismatch
+            | SdpParserErrorKind::InvalidDomain
+            | SdpParserErrorKind::InvalidIpAddress
+            | SdpParserErrorKind::OriginAddressTypeMismatch
+            | SdpParserErrorKind::ConnectionNotMulticast
+            | SdpParserErrorKind::ConnectionAddressCountOutOfRange
+            | SdpParserErrorKind::UnresolvedAddress
+            | SdpParserErrorKind::MdnsNameNotLocal
+            | SdpParserErrorKind::MdnsNameNotUuid
+            | SdpParserErrorKind::NetworkTypeUnsupported => SdpParserErrorCategory::Address,
+            SdpParserErrorKind::Unsupported
+            | SdpParserErrorKind::LineTypeUnsupported
+            | SdpParserErrorKind::VersionUnsupported
+            | SdpParserErrorKind::MediaUnknownType
+            | SdpParserErrorKind::MediaUnknownProtocol
+            | SdpParserErrorKind::AttributeUnknown
+            | SdpParserErrorKind::CandidateExtensionWithoutValue
+            | SdpParserErrorKind::FingerprintUnsupportedHashAlgorithm
+            | SdpParserErrorKind::FmtpMissingParameters
+            | SdpParserErrorKind::GroupUnknownSemantics
+            | SdpParserErrorKind::RtcpFbUnknownType
+            | SdpParserErrorKind::RtcpFbUnknownParameter
+            | SdpParserErrorKind::SsrcGroupUnknownSemantics => SdpParserErrorCategory::Unsupported,
+            SdpParserErrorKind::InvalidInteger
+            | SdpParserErrorKind::InvalidFloat
+            | SdpParserErrorKind::MediaPortOutOfRange
+            | SdpParserErrorKind::MediaFormatOutOfRange
+            | SdpParserErrorKind::DirectionUnknown
+            | SdpParserErrorKind::CandidateUnknownTransport
+            | SdpParserErrorKind::CandidatePortOutOfRange
+            | SdpParserErrorKind::CandidatePriorityOutOfRange
+            | SdpParserErrorKind::CandidateUnknownType
+            | SdpParserErrorKind::CandidateRportOutOfRange
+            | SdpParserErrorKind::CandidateUnknownTcpType
+            | SdpParserErrorKind::DtlsMessageUnknownRole
+            | SdpParserErrorKind::ExtmapUnknownDirection
+            | SdpParserErrorKind::FingerprintInvalidByte
+            | SdpParserErrorKind::FingerprintWrongLength
+            | SdpParserErrorKind::FmtpInvalidBoolean
+            | SdpParserErrorKind::FmtpProfileLevelIdOutOfRange
+            | SdpParserErrorKind::FmtpPacketizationModeOutOfRange
+            | SdpParserErrorKind::FmtpRedOutOfRange
+            | SdpParserErrorKind::IcePacingOutOfRange
+            | SdpParserErrorKind::ImageAttrDuplicateDirection
+            | SdpParserErrorKind::ImageAttrRatioRangeOrder
+            | SdpParserErrorKind::ImageAttrSarNotAscending
+            | SdpParserErrorKind::RemoteCandidatePortOutOfRange
+            | SdpParserErrorKind::RtpmapPayloadTypeOutOfRange
+            | SdpParserErrorKind::SctpmapUnknownProtocol
+            | SdpParserErrorKind::SctpPortOutOfRange
+            | SdpParserErrorKind::SetupUnknownValue
+            | SdpParserErrorKind::SimulcastDuplicateDirection
+            | SdpParserErrorKind::SimulcastUnknownDescriptor
+            | SdpParserErrorKind::SourceFilterUnknownMode
+            | SdpParserErrorKind::T38InvalidValue
+            | SdpParserErrorKind::T38AnswerNotAllowed
+            | SdpParserErrorKind::AnonymizerStateConflict
+            | SdpParserErrorKind::AnonymizerKeyMismatch
+            | SdpParserErrorKind::AnonymizerNamespaceOutOfRange
+            | SdpParserErrorKind::AnonymizerMissingKey
+            | SdpParserErrorKind::FragmentUnknownMid
+            | SdpParserErrorKind::FragmentUfragMismatch
+            | SdpParserErrorKind::ExtmapNoFreeId
+            | SdpParserErrorKind::CandidateInitMediaIndexOutOfRange
+            | SdpParserErrorKind::CandidateInitUnknownMid
+            | SdpParserErrorKind::CandidateInitNotACandidate
+            | SdpParserErrorKind::CandidateInitUfragMismatch
+            | SdpParserErrorKind::WhipOfferBundleGroupCount
+            | SdpParserErrorKind::WhipOfferMissingMid
+            | SdpParserErrorKind::WhipOfferNotBundled
+            | SdpParserErrorKind::WhipOfferWrongDirection
+            | SdpParserErrorKind::WhipOfferMissingTransport
+            | SdpParserErrorKind::WhipAnswerMissingCandidate
+            | SdpParserErrorKind::WhipSessionNotBundled
+            | SdpParserErrorKind::WhipIncompleteIceCredentials
+            | SdpParserErrorKind::DtlsSetupConflict => SdpParserErrorCategory::Value,
+            _ => SdpParserErrorCategory::Syntax,
+        }
+    }
+}
+
+impl fmt::Display for SdpParserErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SdpParserInternalError {
     UnknownAddressType(String),
@@ -25,8 +388,110 @@ pub enum SdpParserInternalError {
     Float(ParseFloatError),
     Domain(url::ParseError),
     IpAddress(std::net::AddrParseError),
+    // The error was caused by the token at span, relative to the parsed string
+    Located {
+        error: Box<SdpParserInternalError>,
+        span: SdpSpan,
+    },
+    // The error is of a specific kind
+    Kind {
+        error: Box<SdpParserInternalError>,
+        kind: SdpParserErrorKind,
+    },
+    // An error restored from its serialized message, which is kept verbatim
+    Deserialized(String),
+}
+
+impl SdpParserInternalError {
+    /*
+     * Attaches the position of token within parent to the error. Errors
+     * which are already located are expected to be relative to the same
+     * parent and are left untouched, as their location is more precise.
+     */
+    pub fn located_in(self, parent: &str, token: &str) -> SdpParserInternalError {
+        if self.span().is_some() {
+            return self;
+        }
+        match SdpSpan::from_subslice(parent, token) {
+            Some(span) => SdpParserInternalError::Located {
+                error: Box::new(self),
+                span,
+            },
+            None => self,
+        }
+    }
+
+    pub fn with_kind(self, kind: SdpParserErrorKind) -> SdpParserInternalError {
+        SdpParserInternalError::Kind {
+            error: Box::new(self),
+            kind,
+        }
+    }
+
+    pub fn kind(&self) -> SdpParserErrorKind {
+        match *self {
+            SdpParserInternalError::UnknownAddressType(..) => {
+                SdpParserErrorKind::UnknownAddressType
+            }
+            SdpParserInternalError::AddressTypeMismatch { .. } => {
+                SdpParserErrorKind::AddressTypeMismatch
+            }
+            SdpParserInternalError::Generic(..) | SdpParserInternalError::Deserialized(..) => {
+                SdpParserErrorKind::Generic
+            }
+            SdpParserInternalError::Unsupported(..) => SdpParserErrorKind::Unsupported,
+            SdpParserInternalError::Integer(..) => SdpParserErrorKind::InvalidInteger,
+            SdpParserInternalError::Float(..) => SdpParserErrorKind::InvalidFloat,
+            SdpParserInternalError::Domain(..) => SdpParserErrorKind::InvalidDomain,
+            SdpParserInternalError::IpAddress(..) => SdpParserErrorKind::InvalidIpAddress,
+            SdpParserInternalError::Located { ref error, .. } => error.kind(),
+            SdpParserInternalError::Kind { kind, .. } => kind,
+        }
+    }
+
+    // Moves the location of the error by offset, e.g. from a value to its line
+    pub fn shift(self, offset: usize) -> SdpParserInternalError {
+        match self {
+            SdpParserInternalError::Located { error, span } => SdpParserInternalError::Located {
+                error,
+                span: span.shift(offset),
+            },
+            SdpParserInternalError::Kind { error, kind } => SdpParserInternalError::Kind {
+                error: Box::new(error.shift(offset)),
+                kind,
+            },
+            error => error,
+        }
+    }
+
+    // Makes the location relative to parent, for errors of parsing value
+    pub fn rebase(self, parent: &str, value: &str) -> SdpParserInternalError {
+        match SdpSpan::from_subslice(parent, value) {
+            Some(value_span) => self.shift(value_span.start),
+            None => self,
+        }
+    }
+
+    pub fn span(&self) -> Option<SdpSpan> {
+        match *self {
+            SdpParserInternalError::Located { span, .. } => Some(span),
+            SdpParserInternalError::Kind { ref error, .. } => error.span(),
+            _ => None,
+        }
+    }
+
+    // Returns the error without its location and kind
+    pub fn inner(&self) -> &SdpParserInternalError {
+        match *self {
+            SdpParserInternalError::Located { ref error, .. }
+            | SdpParserInternalError::Kind { ref error, .. } => error.inner(),
+            _ => self,
+        }
+    }
 }
 
+const INTERNAL_ERROR_MESSAGE_GENERIC: &str = "Parsing error: ";
+const INTERNAL_ERROR_MESSAGE_UNSUPPORTED: &str = "Unsupported parsing error: ";
 const INTERNAL_ERROR_MESSAGE_UNKNOWN_ADDRESS_TYPE: &str = "Unknown address type";
 const INTERNAL_ERROR_MESSAGE_ADDRESS_TYPE_MISMATCH: &str =
     "Address is of a different type(1) than declared(2)";
@@ -44,9 +509,11 @@ impl fmt::Display for SdpParserInternalError {
                 "{}: {}, {}",
                 INTERNAL_ERROR_MESSAGE_ADDRESS_TYPE_MISMATCH, found, expected
             ),
-            SdpParserInternalError::Generic(ref message) => write!(f, "Parsing error: {}", message),
+            SdpParserInternalError::Generic(ref message) => {
+                write!(f, "{}{}", INTERNAL_ERROR_MESSAGE_GENERIC, message)
+            }
             SdpParserInternalError::Unsupported(ref message) => {
-                write!(f, "Unsupported parsing error: {}", message)
+                write!(f, "{}{}", INTERNAL_ERROR_MESSAGE_UNSUPPORTED, message)
             }
             SdpParserInternalError::Integer(ref error) => {
                 write!(f, "Integer parsing error: {}", error)
@@ -58,6 +525,9 @@ impl fmt::Display for SdpParserInternalError {
             SdpParserInternalError::IpAddress(ref error) => {
                 write!(f, "IP address parsing error: {}", error)
             }
+            SdpParserInternalError::Located { ref error, .. }
+            | SdpParserInternalError::Kind { ref error, .. } => error.fmt(f),
+            SdpParserInternalError::Deserialized(ref message) => write!(f, "{}", message),
         }
     }
 }
@@ -69,6 +539,8 @@ impl Error for SdpParserInternalError {
             SdpParserInternalError::Float(ref error) => Some(error),
             SdpParserInternalError::Domain(ref error) => Some(error),
             SdpParserInternalError::IpAddress(ref error) => Some(error),
+            SdpParserInternalError::Located { ref error, .. }
+            | SdpParserInternalError::Kind { ref error, .. } => error.source(),
             // Can't tell much more about our internal errors
             _ => None,
         }
@@ -87,25 +559,61 @@ pub enum SdpParserError {
         line: String,
         line_number: usize,
     },
+    // line_number is the first of the offending line_numbers, or 0 if the
+    // error can't be attributed to any line
     Sequence {
         message: String,
         line_number: usize,
+        line_numbers: Vec<usize>,
+        kind: SdpParserErrorKind,
     },
 }
 
+impl SdpParserError {
+    /*
+     * The span of the offending token within the line. Errors without a more
+     * precise location point at the value of the line.
+     */
+    pub fn span(&self) -> Option<SdpSpan> {
+        match *self {
+            SdpParserError::Line {
+                ref error,
+                ref line,
+                ..
+            }
+            | SdpParserError::Unsupported {
+                ref error,
+                ref line,
+                ..
+            } => error.span().or_else(|| {
+                let value = line[line.find('=')? + 1..].trim();
+                SdpSpan::from_subslice(line, value)
+            }),
+            SdpParserError::Sequence { .. } => None,
+        }
+    }
+
+    pub fn kind(&self) -> SdpParserErrorKind {
+        match *self {
+            SdpParserError::Line { ref error, .. }
+            | SdpParserError::Unsupported { ref error, .. } => error.kind(),
+            SdpParserError::Sequence { kind, .. } => kind,
+        }
+    }
+
+    // The one based column of the offending token within the line
+    pub fn column(&self) -> Option<usize> {
+        self.span().map(|span| span.start + 1)
+    }
+}
+
 #[cfg(feature = "serialize")]
 impl Serialize for SdpParserError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct(
-            "error",
-            match *self {
-                SdpParserError::Sequence { .. } => 3,
-                _ => 4,
-            },
-        )?;
+        let mut state = serializer.serialize_struct("error", 6)?;
         match *self {
             SdpParserError::Line {
                 ref error,
@@ -114,7 +622,8 @@ impl Serialize for SdpParserError {
             } => {
                 state.serialize_field("type", "Line")?;
                 state.serialize_field("message", &format!("{}", error))?;
-                state.serialize_field("line", &line)?
+                state.serialize_field("line", &line)?;
+                state.serialize_field("span", &self.span())?
             }
             SdpParserError::Unsupported {
                 ref error,
@@ -123,13 +632,20 @@ impl Serialize for SdpParserError {
             } => {
                 state.serialize_field("type", "Unsupported")?;
                 state.serialize_field("message", &format!("{}", error))?;
-                state.serialize_field("line", &line)?
+                state.serialize_field("line", &line)?;
+                state.serialize_field("span", &self.span())?
             }
-            SdpParserError::Sequence { ref message, .. } => {
+            SdpParserError::Sequence {
+                ref message,
+                ref line_numbers,
+                ..
+            } => {
                 state.serialize_field("type", "Sequence")?;
                 state.serialize_field("message", &message)?;
+                state.serialize_field("line_numbers", &line_numbers)?;
             }
         };
+        state.serialize_field("kind", &self.kind())?;
         state.serialize_field(
             "line_number",
             &match *self {
@@ -142,6 +658,84 @@ impl Serialize for SdpParserError {
     }
 }
 
+/*
+ * The inverse of the serialization above. The internal error is restored
+ * from its message: Generic and Unsupported errors get their message back,
+ * everything else, e.g. integer parsing errors, is kept as the plain text.
+ * Kind and span are restored from their own fields.
+ */
+#[cfg(feature = "serialize")]
+#[derive(Deserialize)]
+struct SdpParserErrorFields {
+    #[serde(rename = "type")]
+    error_type: String,
+    message: String,
+    #[serde(default)]
+    line: String,
+    #[serde(default)]
+    span: Option<SdpSpan>,
+    #[serde(default)]
+    line_numbers: Vec<usize>,
+    kind: SdpParserErrorKind,
+    line_number: usize,
+}
+
+#[cfg(feature = "serialize")]
+fn restore_internal_error(fields: &SdpParserErrorFields) -> SdpParserInternalError {
+    let message = &fields.message;
+    let error = if let Some(generic) = message.strip_prefix(INTERNAL_ERROR_MESSAGE_GENERIC) {
+        SdpParserInternalError::Generic(generic.to_string())
+    } else if let Some(unsupported) = message.strip_prefix(INTERNAL_ERROR_MESSAGE_UNSUPPORTED) {
+        SdpParserInternalError::Unsupported(unsupported.to_string())
+    } else {
+        SdpParserInternalError::Deserialized(message.clone())
+    };
+    let error = if error.kind() == fields.kind {
+        error
+    } else {
+        error.with_kind(fields.kind)
+    };
+    match fields.span {
+        Some(span) => SdpParserInternalError::Located {
+            error: Box::new(error),
+            span,
+        },
+        None => error,
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> Deserialize<'de> for SdpParserError {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = SdpParserErrorFields::deserialize(deserializer)?;
+        match fields.error_type.as_str() {
+            "Line" => Ok(SdpParserError::Line {
+                error: restore_internal_error(&fields),
+                line: fields.line,
+                line_number: fields.line_number,
+            }),
+            "Unsupported" => Ok(SdpParserError::Unsupported {
+                error: restore_internal_error(&fields),
+                line: fields.line,
+                line_number: fields.line_number,
+            }),
+            "Sequence" => Ok(SdpParserError::Sequence {
+                message: fields.message,
+                line_number: fields.line_number,
+                line_numbers: fields.line_numbers,
+                kind: fields.kind,
+            }),
+            other => Err(serde_de::Error::unknown_variant(
+                other,
+                &["Line", "Unsupported", "Sequence"],
+            )),
+        }
+    }
+}
+
 impl fmt::Display for SdpParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -166,7 +760,24 @@ impl fmt::Display for SdpParserError {
             SdpParserError::Sequence {
                 ref message,
                 ref line_number,
-            } => write!(f, "Sequence error in line({}): {}", line_number, message),
+                ref line_numbers,
+                ..
+            } => {
+                if line_numbers.len() > 1 {
+                    write!(
+                        f,
+                        "Sequence error in lines({}): {}",
+                        line_numbers
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                        message
+                    )
+                } else {
+                    write!(f, "Sequence error in line({}): {}", line_number, message)
+                }
+            }
         }
     }
 }
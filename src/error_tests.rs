@@ -2,9 +2,12 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+#![allow(clippy::nonminimal_bool)]
+
 use super::*;
 use address::Address;
 use std::str::FromStr;
+
 #[test]
 fn test_sdp_parser_internal_error_unknown_address_type() {
     let error = SdpParserInternalError::UnknownAddressType("foo".to_string());
@@ -120,6 +123,8 @@ fn test_sdp_parser_error_sequence() {
     let sequence1 = SdpParserError::Sequence {
         message: "sequence message".to_string(),
         line_number: 42,
+        line_numbers: vec![42],
+        kind: SdpParserErrorKind::MissingTiming,
     };
     assert_eq!(
         format!("{}", sequence1),
@@ -127,3 +132,131 @@ fn test_sdp_parser_error_sequence() {
     );
     assert!(sequence1.source().is_none());
 }
+
+#[test]
+fn test_sdp_span() {
+    let line = "a=fmtp:96 max-fs=foo";
+    let span = SdpSpan::from_subslice(line, &line[17..]).unwrap();
+    assert_eq!(span, SdpSpan::new(17, 20));
+    assert_eq!(span.len(), 3);
+    assert_eq!(span.shift(2), SdpSpan::new(19, 22));
+    assert!(SdpSpan::from_subslice(line, "foo").is_none());
+}
+
+#[test]
+fn test_sdp_parser_internal_error_located() {
+    let value = "96 max-fs=foo";
+    let error =
+        SdpParserInternalError::Generic("test message".to_string()).located_in(value, &value[3..]);
+    assert_eq!(error.span(), Some(SdpSpan::new(3, 13)));
+    assert_eq!(format!("{}", error), "Parsing error: test message");
+    assert!(matches!(*error.inner(), SdpParserInternalError::Generic(_)));
+
+    // The first location is kept
+    let error = error.located_in(value, value);
+    assert_eq!(error.span(), Some(SdpSpan::new(3, 13)));
+
+    let line = "a=fmtp:96 max-fs=foo";
+    let error = error.rebase(line, &line[7..]);
+    assert_eq!(error.span(), Some(SdpSpan::new(10, 20)));
+
+    let line_error = SdpParserError::Line {
+        error,
+        line: line.to_string(),
+        line_number: 3,
+    };
+    assert_eq!(line_error.column(), Some(11));
+}
+
+#[test]
+fn test_sdp_parser_error_span_defaults_to_value() {
+    let error = SdpParserError::Unsupported {
+        error: SdpParserInternalError::Generic("unsupported value".to_string()),
+        line: "x= foo ".to_string(),
+        line_number: 1,
+    };
+    assert_eq!(error.span(), Some(SdpSpan::new(3, 6)));
+    assert_eq!(error.column(), Some(4));
+
+    let sequence = SdpParserError::Sequence {
+        message: "sequence message".to_string(),
+        line_number: 0,
+        line_numbers: Vec::new(),
+        kind: SdpParserErrorKind::TooFewLines,
+    };
+    assert!(sequence.span().is_none());
+}
+
+#[test]
+fn test_sdp_parser_error_sequence_multiple_lines() {
+    let sequence = SdpParserError::Sequence {
+        message: "sequence message".to_string(),
+        line_number: 4,
+        line_numbers: vec![4, 9],
+        kind: SdpParserErrorKind::ExtmapAtSessionAndMediaLevel,
+    };
+    assert_eq!(
+        format!("{}", sequence),
+        "Sequence error in lines(4, 9): sequence message"
+    );
+}
+
+#[test]
+fn test_sdp_parser_internal_error_kind() {
+    let error = SdpParserInternalError::Generic("test message".to_string());
+    assert_eq!(error.kind(), SdpParserErrorKind::Generic);
+    assert_eq!(
+        SdpParserInternalError::Unsupported("test".to_string()).kind(),
+        SdpParserErrorKind::Unsupported
+    );
+
+    let value = "96 max-fs=foo";
+    let error = error
+        .with_kind(SdpParserErrorKind::FmtpInvalidParameter)
+        .located_in(value, &value[3..]);
+    assert_eq!(error.kind(), SdpParserErrorKind::FmtpInvalidParameter);
+    assert_eq!(error.span(), Some(SdpSpan::new(3, 13)));
+    assert_eq!(format!("{}", error), "Parsing error: test message");
+    assert!(matches!(*error.inner(), SdpParserInternalError::Generic(_)));
+    assert_eq!(
+        error.shift(2).span(),
+        Some(SdpSpan::new(5, 15)),
+        "shifting must reach a location inside the kind"
+    );
+}
+
+#[test]
+fn test_sdp_parser_error_kind_category() {
+    assert_eq!(
+        SdpParserErrorKind::CandidateTooFewTokens.category(),
+        SdpParserErrorCategory::Syntax
+    );
+    assert_eq!(
+        SdpParserErrorKind::MediaPortOutOfRange.category(),
+        SdpParserErrorCategory::Value
+    );
+    assert_eq!(
+        SdpParserErrorKind::RidPtUndeclared.category(),
+        SdpParserErrorCategory::Sequence
+    );
+    assert_eq!(
+        SdpParserErrorKind::LineTypeUnsupported.category(),
+        SdpParserErrorCategory::Unsupported
+    );
+    assert_eq!(
+        SdpParserErrorKind::InvalidIpAddress.category(),
+        SdpParserErrorCategory::Address
+    );
+    assert_eq!(
+        SdpParserErrorKind::CandidateTooFewTokens.to_string(),
+        "CandidateTooFewTokens"
+    );
+
+    let sequence = SdpParserError::Sequence {
+        message: "sequence message".to_string(),
+        line_number: 4,
+        line_numbers: vec![4],
+        kind: SdpParserErrorKind::RidPtUndeclared,
+    };
+    assert_eq!(sequence.kind(), SdpParserErrorKind::RidPtUndeclared);
+}
@@ -0,0 +1,403 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{
+    SdpAttribute, SdpAttributeDirection, SdpAttributeExtmap, SdpAttributeGroupSemantic,
+    SdpAttributeType,
+};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use media_type::SdpMedia;
+use std::fmt;
+use SdpSession;
+
+/*
+ * RFC8285
+ * The one-byte header form carries IDs 1-14, ID 15 is reserved. The two-byte
+ * form carries IDs 1-255 and can only be mixed with the one-byte form if
+ * a=extmap-allow-mixed was negotiated.
+ */
+pub const ONE_BYTE_ID_RANGE: (u16, u16) = (1, 14);
+pub const TWO_BYTE_ID_RANGE: (u16, u16) = (1, 255);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpExtmapHeaderForm {
+    OneByte,
+    TwoByte,
+}
+
+impl SdpExtmapHeaderForm {
+    pub fn for_id(id: u16) -> Option<SdpExtmapHeaderForm> {
+        match id {
+            x if x >= ONE_BYTE_ID_RANGE.0 && x <= ONE_BYTE_ID_RANGE.1 => {
+                Some(SdpExtmapHeaderForm::OneByte)
+            }
+            x if x > ONE_BYTE_ID_RANGE.1 && x <= TWO_BYTE_ID_RANGE.1 => {
+                Some(SdpExtmapHeaderForm::TwoByte)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpKnownHeaderExtension {
+    AbsSendTime,
+    TransportWideCc,
+    Mid,
+    Rid,
+    RepairedRid,
+    AudioLevel,
+    VideoOrientation,
+    DependencyDescriptor,
+}
+
+impl SdpKnownHeaderExtension {
+    pub fn all() -> [SdpKnownHeaderExtension; 8] {
+        [
+            SdpKnownHeaderExtension::AbsSendTime,
+            SdpKnownHeaderExtension::TransportWideCc,
+            SdpKnownHeaderExtension::Mid,
+            SdpKnownHeaderExtension::Rid,
+            SdpKnownHeaderExtension::RepairedRid,
+            SdpKnownHeaderExtension::AudioLevel,
+            SdpKnownHeaderExtension::VideoOrientation,
+            SdpKnownHeaderExtension::DependencyDescriptor,
+        ]
+    }
+
+    pub fn uri(&self) -> &'static str {
+        match *self {
+            SdpKnownHeaderExtension::AbsSendTime => {
+                "http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time"
+            }
+            SdpKnownHeaderExtension::TransportWideCc => {
+                "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01"
+            }
+            SdpKnownHeaderExtension::Mid => "urn:ietf:params:rtp-hdrext:sdes:mid",
+            SdpKnownHeaderExtension::Rid => "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id",
+            SdpKnownHeaderExtension::RepairedRid => {
+                "urn:ietf:params:rtp-hdrext:sdes:repaired-rtp-stream-id"
+            }
+            SdpKnownHeaderExtension::AudioLevel => "urn:ietf:params:rtp-hdrext:ssrc-audio-level",
+            SdpKnownHeaderExtension::VideoOrientation => "urn:3gpp:video-orientation",
+            SdpKnownHeaderExtension::DependencyDescriptor => {
+                "https://aomediacodec.github.io/av1-rtp-spec/#dependency-descriptor-rtp-header-extension"
+            }
+        }
+    }
+
+    pub fn from_uri(uri: &str) -> Option<SdpKnownHeaderExtension> {
+        SdpKnownHeaderExtension::all()
+            .iter()
+            .find(|ext| ext.uri() == uri)
+            .cloned()
+    }
+}
+
+impl fmt::Display for SdpKnownHeaderExtension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.uri().fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpExtmapConflict {
+    // The ID is neither a valid one-byte nor two-byte ID
+    InvalidId { id: u16 },
+    // The same ID maps to different URIs within one RTP session
+    IdCollision { id: u16, uris: Vec<String> },
+    // The same URI is mapped to different IDs within one RTP session
+    UriCollision { uri: String, ids: Vec<u16> },
+    // A two-byte ID is used without a=extmap-allow-mixed
+    TwoByteIdWithoutAllowMixed { id: u16 },
+}
+
+impl fmt::Display for SdpExtmapConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpExtmapConflict::InvalidId { id } => write!(f, "extmap ID {} is out of range", id),
+            SdpExtmapConflict::IdCollision { id, ref uris } => write!(
+                f,
+                "extmap ID {} is used for different URIs: {}",
+                id,
+                uris.join(", ")
+            ),
+            SdpExtmapConflict::UriCollision { ref uri, ref ids } => write!(
+                f,
+                "extmap URI {} is mapped to different IDs: {}",
+                uri,
+                ids.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            SdpExtmapConflict::TwoByteIdWithoutAllowMixed { id } => write!(
+                f,
+                "extmap ID {} requires the two-byte header without extmap-allow-mixed",
+                id
+            ),
+        }
+    }
+}
+
+fn extmaps_of(attributes: &[SdpAttribute]) -> Vec<&SdpAttributeExtmap> {
+    attributes
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Extmap(extmap) => Some(extmap),
+            _ => None,
+        })
+        .collect()
+}
+
+fn allows_mixed(session: &SdpSession, media: &SdpMedia) -> bool {
+    session
+        .get_attribute(SdpAttributeType::ExtmapAllowMixed)
+        .is_some()
+        || media
+            .get_attribute(SdpAttributeType::ExtmapAllowMixed)
+            .is_some()
+}
+
+// Groups the m-sections by the RTP session they share. Every m-section which
+// is not part of a BUNDLE group forms an RTP session on its own.
+fn rtp_sessions(session: &SdpSession) -> Vec<Vec<&SdpMedia>> {
+    let bundles: Vec<&Vec<String>> = session
+        .attribute
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Group(group) => match group.semantics {
+                SdpAttributeGroupSemantic::Bundle => Some(&group.tags),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    let mut sessions: Vec<Vec<&SdpMedia>> = bundles.iter().map(|_| Vec::new()).collect();
+    for media in &session.media {
+        let bundle = match media.get_attribute(SdpAttributeType::Mid) {
+            Some(SdpAttribute::Mid(mid)) => bundles.iter().position(|tags| tags.contains(mid)),
+            _ => None,
+        };
+        match bundle {
+            Some(index) => sessions[index].push(media),
+            None => sessions.push(vec![media]),
+        }
+    }
+    sessions
+}
+
+pub fn validate_extmaps(session: &SdpSession) -> Vec<SdpExtmapConflict> {
+    let mut conflicts = Vec::new();
+    let session_extmaps = extmaps_of(&session.attribute);
+
+    for rtp_session in rtp_sessions(session) {
+        let mut mapping: Vec<(u16, &str)> = Vec::new();
+        for media in &rtp_session {
+            let allow_mixed = allows_mixed(session, media);
+            let mut extmaps = session_extmaps.clone();
+            extmaps.extend(extmaps_of(media.get_attributes()));
+            for extmap in extmaps {
+                let conflict = match SdpExtmapHeaderForm::for_id(extmap.id) {
+                    None => Some(SdpExtmapConflict::InvalidId { id: extmap.id }),
+                    Some(SdpExtmapHeaderForm::TwoByte) if !allow_mixed => {
+                        Some(SdpExtmapConflict::TwoByteIdWithoutAllowMixed { id: extmap.id })
+                    }
+                    _ => None,
+                };
+                if let Some(conflict) = conflict {
+                    if !conflicts.contains(&conflict) {
+                        conflicts.push(conflict);
+                    }
+                }
+                if !mapping.contains(&(extmap.id, extmap.url.as_str())) {
+                    mapping.push((extmap.id, extmap.url.as_str()));
+                }
+            }
+        }
+
+        for &(id, _) in &mapping {
+            let uris: Vec<String> = mapping
+                .iter()
+                .filter(|(i, _)| *i == id)
+                .map(|(_, uri)| uri.to_string())
+                .collect();
+            if uris.len() > 1 {
+                let conflict = SdpExtmapConflict::IdCollision { id, uris };
+                if !conflicts.contains(&conflict) {
+                    conflicts.push(conflict);
+                }
+            }
+        }
+        for &(_, uri) in &mapping {
+            let ids: Vec<u16> = mapping
+                .iter()
+                .filter(|(_, u)| *u == uri)
+                .map(|(id, _)| *id)
+                .collect();
+            if ids.len() > 1 {
+                let conflict = SdpExtmapConflict::UriCollision {
+                    uri: uri.to_string(),
+                    ids,
+                };
+                if !conflicts.contains(&conflict) {
+                    conflicts.push(conflict);
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+fn reverse_direction(direction: &Option<SdpAttributeDirection>) -> Option<SdpAttributeDirection> {
+    direction.as_ref().map(|d| match *d {
+        SdpAttributeDirection::Sendonly => SdpAttributeDirection::Recvonly,
+        SdpAttributeDirection::Recvonly => SdpAttributeDirection::Sendonly,
+        SdpAttributeDirection::Sendrecv => SdpAttributeDirection::Sendrecv,
+    })
+}
+
+/*
+ * Keeps track of the header extension IDs used by one RTP session (e.g. one
+ * BUNDLE group). IDs are allocated from the one-byte range first and only
+ * spill over into the two-byte range if extmap-allow-mixed is in use.
+ */
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpExtmapRegistry {
+    entries: Vec<(u16, String)>,
+    allow_mixed: bool,
+}
+
+impl SdpExtmapRegistry {
+    pub fn new(allow_mixed: bool) -> SdpExtmapRegistry {
+        SdpExtmapRegistry {
+            entries: Vec::new(),
+            allow_mixed,
+        }
+    }
+
+    pub fn with_known_extensions(
+        allow_mixed: bool,
+    ) -> Result<SdpExtmapRegistry, SdpParserInternalError> {
+        let mut registry = SdpExtmapRegistry::new(allow_mixed);
+        for ext in SdpKnownHeaderExtension::all().iter() {
+            registry.register(ext.uri())?;
+        }
+        Ok(registry)
+    }
+
+    pub fn allow_mixed(&self) -> bool {
+        self.allow_mixed
+    }
+
+    pub fn get_id(&self, uri: &str) -> Option<u16> {
+        self.entries
+            .iter()
+            .find(|(_, u)| u == uri)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn get_uri(&self, id: u16) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(i, _)| *i == id)
+            .map(|(_, uri)| uri.as_str())
+    }
+
+    fn free_id(&self) -> Option<u16> {
+        let is_free = |id: &u16| self.get_uri(*id).is_none();
+        let one_byte = ONE_BYTE_ID_RANGE.0..=ONE_BYTE_ID_RANGE.1;
+        match one_byte.clone().find(is_free) {
+            Some(id) => Some(id),
+            None if self.allow_mixed => {
+                // ID 15 is reserved in the one-byte form only
+                (ONE_BYTE_ID_RANGE.1 + 1..=TWO_BYTE_ID_RANGE.1).find(is_free)
+            }
+            None => None,
+        }
+    }
+
+    // Returns the ID of the URI, allocating a new one if needed
+    pub fn register(&mut self, uri: &str) -> Result<u16, SdpParserInternalError> {
+        if let Some(id) = self.get_id(uri) {
+            return Ok(id);
+        }
+        let id = self.free_id().ok_or_else(|| {
+            SdpParserInternalError::Generic(format!("No free extmap ID left for {}", uri))
+                .with_kind(SdpParserErrorKind::ExtmapNoFreeId)
+        })?;
+        self.entries.push((id, uri.to_string()));
+        Ok(id)
+    }
+
+    pub fn unregister(&mut self, uri: &str) {
+        self.entries.retain(|(_, u)| u != uri);
+    }
+
+    pub fn extmaps(&self) -> Vec<SdpAttributeExtmap> {
+        let mut entries = self.entries.clone();
+        entries.sort();
+        entries
+            .into_iter()
+            .map(|(id, url)| SdpAttributeExtmap {
+                id,
+                direction: None,
+                url,
+                extension_attributes: None,
+            })
+            .collect()
+    }
+
+    /*
+     * Builds the extmaps of an answer. The answer has to use the IDs of the
+     * offer, so the registry is remapped to them. Extensions which are not
+     * registered locally, and two-byte IDs without extmap-allow-mixed, are
+     * left out of the answer.
+     */
+    pub fn answer(
+        &mut self,
+        offered: &[SdpAttributeExtmap],
+    ) -> Result<Vec<SdpAttributeExtmap>, SdpParserInternalError> {
+        // Decide on the whole offer first, as remapping displaces other URIs
+        let supported: Vec<&SdpAttributeExtmap> = offered
+            .iter()
+            .filter(|extmap| self.get_id(&extmap.url).is_some())
+            .filter(|extmap| match SdpExtmapHeaderForm::for_id(extmap.id) {
+                Some(SdpExtmapHeaderForm::OneByte) => true,
+                Some(SdpExtmapHeaderForm::TwoByte) => self.allow_mixed,
+                None => false,
+            })
+            .collect();
+        let mut answer = Vec::new();
+        let mut displaced = Vec::new();
+        for extmap in &supported {
+            self.unregister(&extmap.url);
+            if let Some(other) = self.get_uri(extmap.id).map(ToString::to_string) {
+                self.unregister(&other);
+                displaced.push(other);
+            }
+            self.entries.push((extmap.id, extmap.url.clone()));
+            answer.push(SdpAttributeExtmap {
+                id: extmap.id,
+                direction: reverse_direction(&extmap.direction),
+                url: extmap.url.clone(),
+                extension_attributes: None,
+            });
+        }
+        for uri in displaced {
+            if !supported.iter().any(|e| e.url == uri) {
+                self.register(&uri)?;
+            }
+        }
+        Ok(answer)
+    }
+}
+
+#[cfg(test)]
+#[path = "./extmap_tests.rs"]
+mod tests;
@@ -0,0 +1,149 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use parse_sdp;
+
+fn extmap(id: u16, url: &str) -> SdpAttributeExtmap {
+    SdpAttributeExtmap {
+        id,
+        direction: None,
+        url: url.to_string(),
+        extension_attributes: None,
+    }
+}
+
+#[test]
+fn test_known_header_extension() {
+    for ext in SdpKnownHeaderExtension::all().iter() {
+        assert_eq!(SdpKnownHeaderExtension::from_uri(ext.uri()), Some(*ext));
+    }
+    assert_eq!(
+        SdpKnownHeaderExtension::from_uri("urn:ietf:params:rtp-hdrext:sdes:mid"),
+        Some(SdpKnownHeaderExtension::Mid)
+    );
+    assert!(SdpKnownHeaderExtension::from_uri("urn:foo").is_none());
+
+    assert_eq!(
+        SdpExtmapHeaderForm::for_id(14),
+        Some(SdpExtmapHeaderForm::OneByte)
+    );
+    assert_eq!(
+        SdpExtmapHeaderForm::for_id(15),
+        Some(SdpExtmapHeaderForm::TwoByte)
+    );
+    assert!(SdpExtmapHeaderForm::for_id(0).is_none());
+    assert!(SdpExtmapHeaderForm::for_id(256).is_none());
+}
+
+#[test]
+fn test_registry_allocation() {
+    let mut registry = SdpExtmapRegistry::new(false);
+    for i in 0..14 {
+        assert_eq!(registry.register(&format!("urn:ext:{}", i)).unwrap(), i + 1);
+    }
+    assert_eq!(registry.register("urn:ext:3").unwrap(), 4);
+    assert_eq!(
+        registry.register("urn:ext:overflow").unwrap_err().kind(),
+        SdpParserErrorKind::ExtmapNoFreeId
+    );
+
+    let mut registry = SdpExtmapRegistry::new(true);
+    for i in 0..14 {
+        registry.register(&format!("urn:ext:{}", i)).unwrap();
+    }
+    assert_eq!(registry.register("urn:ext:overflow").unwrap(), 15);
+    registry.unregister("urn:ext:0");
+    assert_eq!(registry.register("urn:ext:reuse").unwrap(), 1);
+    assert_eq!(registry.extmaps().len(), 15);
+}
+
+#[test]
+fn test_registry_answer() {
+    let mut registry = SdpExtmapRegistry::with_known_extensions(false).unwrap();
+    assert_eq!(
+        registry.get_id(SdpKnownHeaderExtension::AbsSendTime.uri()),
+        Some(1)
+    );
+    assert_eq!(registry.get_id(SdpKnownHeaderExtension::Mid.uri()), Some(3));
+
+    let mut offered_mid = extmap(1, SdpKnownHeaderExtension::Mid.uri());
+    offered_mid.direction = Some(SdpAttributeDirection::Sendonly);
+    let offered = vec![
+        offered_mid,
+        extmap(7, "urn:unsupported"),
+        extmap(20, SdpKnownHeaderExtension::Rid.uri()),
+    ];
+    let answer = registry.answer(&offered).unwrap();
+
+    assert_eq!(answer.len(), 1);
+    assert_eq!(answer[0].id, 1);
+    assert_eq!(
+        answer[0].to_string(),
+        format!("1/recvonly {}", SdpKnownHeaderExtension::Mid)
+    );
+    assert_eq!(registry.get_id(SdpKnownHeaderExtension::Mid.uri()), Some(1));
+    // abs-send-time was displaced from ID 1 and got a new ID
+    let abs_send_time = registry
+        .get_id(SdpKnownHeaderExtension::AbsSendTime.uri())
+        .unwrap();
+    assert_ne!(abs_send_time, 1);
+    assert_eq!(
+        registry.get_uri(abs_send_time),
+        Some(SdpKnownHeaderExtension::AbsSendTime.uri())
+    );
+
+    // Two supported extensions which swap their IDs are both kept
+    let mut registry = SdpExtmapRegistry::new(false);
+    assert_eq!(registry.register("urn:b").unwrap(), 1);
+    assert_eq!(registry.register("urn:a").unwrap(), 2);
+    let answer = registry
+        .answer(&[extmap(1, "urn:a"), extmap(2, "urn:b")])
+        .unwrap();
+    assert_eq!(
+        answer.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        vec!["1 urn:a", "2 urn:b"]
+    );
+    assert_eq!(registry.get_id("urn:a"), Some(1));
+    assert_eq!(registry.get_id("urn:b"), Some(2));
+}
+
+#[test]
+fn test_validate_extmaps() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=group:BUNDLE 0 1\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               a=mid:0\r\n\
+               a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               a=extmap:2 urn:ietf:params:rtp-hdrext:ssrc-audio-level\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=mid:1\r\n\
+               a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               a=extmap:2 urn:3gpp:video-orientation\r\n\
+               a=extmap:3 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=mid:2\r\n\
+               a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               a=extmap:20 urn:3gpp:video-orientation\r\n";
+    let session = parse_sdp(sdp, true).unwrap();
+    let conflicts = validate_extmaps(&session);
+
+    assert_eq!(conflicts.len(), 3);
+    assert!(conflicts.contains(&SdpExtmapConflict::TwoByteIdWithoutAllowMixed { id: 20 }));
+    assert!(conflicts.contains(&SdpExtmapConflict::IdCollision {
+        id: 2,
+        uris: vec![
+            "urn:ietf:params:rtp-hdrext:ssrc-audio-level".to_string(),
+            "urn:3gpp:video-orientation".to_string(),
+        ],
+    }));
+    assert!(conflicts.contains(&SdpExtmapConflict::UriCollision {
+        uri: "urn:ietf:params:rtp-hdrext:sdes:mid".to_string(),
+        ids: vec![1, 3],
+    }));
+}
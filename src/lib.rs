@@ -14,22 +14,38 @@ extern crate serde_derive;
 extern crate serde;
 use std::convert::TryFrom;
 use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 #[macro_use]
 pub mod attribute_type;
 pub mod address;
 pub mod anonymizer;
+pub mod candidate;
+pub mod diagnostic;
+pub mod dtls;
 pub mod error;
+pub mod extmap;
 pub mod media_type;
 pub mod network;
-
-use address::{AddressTyped, ExplicitlyTypedAddress};
-use anonymizer::{AnonymizingClone, StatefulSdpAnonymizer};
+pub mod plan;
+pub mod renegotiation;
+pub mod resolver;
+pub mod sdpfrag;
+pub mod simulcast;
+pub mod source_filter;
+pub mod t38;
+pub mod w3c;
+pub mod whip;
+
+use address::{AddressType, AddressTyped, ExplicitlyTypedAddress};
+use anonymizer::{
+    redacted_typed_address, AnonymizationAction, AnonymizingClone, StatefulSdpAnonymizer,
+};
 use attribute_type::{
     parse_attribute, SdpAttribute, SdpAttributeRid, SdpAttributeSimulcastVersion, SdpAttributeType,
     SdpSingleDirection,
 };
-use error::{SdpParserError, SdpParserInternalError};
+use error::{SdpParserError, SdpParserErrorKind, SdpParserInternalError, SdpSpan};
 use media_type::{
     parse_media, parse_media_vector, SdpFormatList, SdpMedia, SdpMediaLine, SdpMediaValue,
     SdpProtocolValue,
@@ -41,7 +57,7 @@ use network::{parse_address_type, parse_network_type};
  * bandwidth-fields =    *(%x62 "=" bwtype ":" bandwidth CRLF)
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpBandwidth {
     As(u32),
@@ -68,7 +84,7 @@ impl fmt::Display for SdpBandwidth {
  *                       connection-address CRLF]
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpConnection {
     pub address: ExplicitlyTypedAddress,
@@ -84,10 +100,167 @@ impl fmt::Display for SdpConnection {
     }
 }
 
+impl SdpConnection {
+    pub fn is_multicast(&self) -> bool {
+        match self.address {
+            ExplicitlyTypedAddress::Ip(ip) => ip.is_multicast(),
+            ExplicitlyTypedAddress::Fqdn { .. } => false,
+        }
+    }
+
+    pub fn address_count(&self) -> u32 {
+        self.amount.unwrap_or(1)
+    }
+
+    /*
+     * RFC 8866 section 5.7
+     * The parser accepts a TTL and number of addresses for any address.
+     * Only multicast addresses can have them, IP4 multicast addresses need
+     * a TTL, IP6 ones can't have one, and all the addresses of a range must
+     * be multicast addresses.
+     */
+    pub fn validate(&self) -> Result<(), SdpParserInternalError> {
+        if !self.is_multicast() {
+            if self.ttl.is_some() || self.amount.is_some() {
+                return Err(SdpParserInternalError::Generic(
+                    "only multicast connection addresses can have a TTL or number of addresses"
+                        .to_string(),
+                )
+                .with_kind(SdpParserErrorKind::ConnectionNotMulticast));
+            }
+            return Ok(());
+        }
+        match (self.address.address_type(), self.ttl) {
+            (AddressType::IpV4, None) => {
+                return Err(SdpParserInternalError::Generic(
+                    "IP4 multicast connection addresses must have a TTL".to_string(),
+                )
+                .with_kind(SdpParserErrorKind::ConnectionMissingTtl));
+            }
+            (AddressType::IpV6, Some(_)) => {
+                return Err(SdpParserInternalError::Generic(
+                    "IP6 multicast connection addresses can't have a TTL".to_string(),
+                )
+                .with_kind(SdpParserErrorKind::ConnectionTtlNotAllowed));
+            }
+            _ => (),
+        }
+        self.addresses().map(|_| ())
+    }
+
+    /*
+     * RFC 8866 section 5.7
+     * Expands a multicast range like 233.252.0.1/127/3 into the consecutive
+     * addresses 233.252.0.1, 233.252.0.2 and 233.252.0.3. Domain names need
+     * to be resolved first.
+     */
+    pub fn addresses(&self) -> Result<Vec<IpAddr>, SdpParserInternalError> {
+        let ip = match self.address {
+            ExplicitlyTypedAddress::Ip(ip) => ip,
+            ExplicitlyTypedAddress::Fqdn { ref domain, .. } => {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "connection address {} is not resolved",
+                    domain
+                ))
+                .with_kind(SdpParserErrorKind::UnresolvedAddress));
+            }
+        };
+        let count = self.address_count();
+        let out_of_range = || {
+            SdpParserInternalError::Generic(format!(
+                "connection address range {} must be in the multicast range",
+                self
+            ))
+            .with_kind(SdpParserErrorKind::ConnectionAddressCountOutOfRange)
+        };
+        if count == 0 {
+            return Err(out_of_range());
+        }
+        let addresses = (0..count)
+            .map(|i| match ip {
+                IpAddr::V4(ip) => u32::from(ip)
+                    .checked_add(i)
+                    .map(|ip| IpAddr::V4(Ipv4Addr::from(ip))),
+                IpAddr::V6(ip) => u128::from(ip)
+                    .checked_add(u128::from(i))
+                    .map(|ip| IpAddr::V6(Ipv6Addr::from(ip))),
+            })
+            .collect::<Option<Vec<IpAddr>>>()
+            .ok_or_else(out_of_range)?;
+        if count > 1 && addresses.iter().any(|ip| !ip.is_multicast()) {
+            return Err(out_of_range());
+        }
+        Ok(addresses)
+    }
+
+    /*
+     * RFC 8866 section 5.14
+     * The transport addresses of an m-section using this connection. With
+     * several addresses and ports they map one to one, e.g. with
+     * c=IN IP4 233.252.0.1/127/2 and m=video 49170/2 RTP/AVP 96 RTP uses
+     * 233.252.0.1 port 49170 and 233.252.0.2 port 49172. RTP ports are
+     * counted in pairs to leave room for RTCP.
+     */
+    pub fn transport_addresses(
+        &self,
+        media: &SdpMediaLine,
+    ) -> Result<Vec<SocketAddr>, SdpParserInternalError> {
+        let addresses = self.addresses()?;
+        let port_count = media.port_count.max(1);
+        let step: u32 = if media.proto.is_rtp() { 2 } else { 1 };
+        if addresses.len() > 1 && port_count > 1 && addresses.len() != port_count as usize {
+            return Err(SdpParserInternalError::Generic(format!(
+                "{} ports can't be mapped to {} connection addresses",
+                port_count,
+                addresses.len()
+            ))
+            .with_kind(SdpParserErrorKind::MediaPortCountMismatch));
+        }
+        let ports = (0..port_count)
+            .map(|i| {
+                u16::try_from(u64::from(media.port) + u64::from(step) * u64::from(i)).map_err(
+                    |_| {
+                        SdpParserInternalError::Generic(
+                            "media port range must be in range [0,65535]".to_string(),
+                        )
+                        .with_kind(SdpParserErrorKind::MediaPortOutOfRange)
+                    },
+                )
+            })
+            .collect::<Result<Vec<u16>, _>>()?;
+        Ok(match (addresses.len(), ports.len()) {
+            (1, _) => ports
+                .iter()
+                .map(|port| SocketAddr::new(addresses[0], *port))
+                .collect(),
+            (_, 1) => addresses
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, ports[0]))
+                .collect(),
+            _ => addresses
+                .iter()
+                .zip(ports)
+                .map(|(ip, port)| SocketAddr::new(*ip, port))
+                .collect(),
+        })
+    }
+}
+
 impl AnonymizingClone for SdpConnection {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
         let mut masked = self.clone();
-        masked.address = anon.mask_typed_address(&self.address);
+        masked.address = anon.policy().addresses.apply(
+            &self.address,
+            |address| anon.mask_typed_address(address),
+            redacted_typed_address(&self.address),
+        );
+        // The TTL and number of addresses only apply to multicast addresses
+        if anon.policy().addresses == AnonymizationAction::Redact
+            || (self.is_multicast() && !masked.is_multicast())
+        {
+            masked.ttl = None;
+            masked.amount = None;
+        }
         masked
     }
 }
@@ -98,7 +271,7 @@ impl AnonymizingClone for SdpConnection {
  *                       nettype SP addrtype SP unicast-address CRLF
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpOrigin {
     pub username: String,
@@ -123,8 +296,16 @@ impl fmt::Display for SdpOrigin {
 impl AnonymizingClone for SdpOrigin {
     fn masked_clone(&self, anon: &mut StatefulSdpAnonymizer) -> Self {
         let mut masked = self.clone();
-        masked.username = anon.mask_origin_user(&self.username);
-        masked.unicast_addr = anon.mask_typed_address(&masked.unicast_addr);
+        masked.username = anon.policy().origin_user.apply(
+            &self.username,
+            |user| anon.mask_origin_user(user),
+            "-".to_string(),
+        );
+        masked.unicast_addr = anon.policy().addresses.apply(
+            &self.unicast_addr,
+            |address| anon.mask_typed_address(address),
+            redacted_typed_address(&self.unicast_addr),
+        );
         masked
     }
 }
@@ -136,7 +317,7 @@ impl AnonymizingClone for SdpOrigin {
  *                       [zone-adjustments CRLF]
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpTiming {
     pub start: u64,
@@ -149,7 +330,7 @@ impl fmt::Display for SdpTiming {
     }
 }
 
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpType {
     // Note: Email, Information, Key, Phone, Repeat, Uri and Zone are left out
@@ -164,12 +345,14 @@ pub enum SdpType {
     Version(u64),
 }
 
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpLine {
     pub line_number: usize,
     pub sdp_type: SdpType,
     pub text: String,
+    // Byte offsets of the line within the parsed SDP
+    pub span: SdpSpan,
 }
 
 /*
@@ -190,7 +373,7 @@ pub struct SdpLine {
  *                       media-descriptions
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpSession {
     pub version: u64,
@@ -200,6 +383,9 @@ pub struct SdpSession {
     pub bandwidth: Vec<SdpBandwidth>,
     pub timing: Option<SdpTiming>,
     pub attribute: Vec<SdpAttribute>,
+    // Byte offsets of the parsed session level attributes, by their index
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub attribute_spans: Vec<Option<SdpSpan>>,
     pub media: Vec<SdpMedia>,
     pub warnings: Vec<SdpParserError>, // unsupported values:
                                        // information: Option<String>,
@@ -249,6 +435,7 @@ impl SdpSession {
             bandwidth: Vec::new(),
             timing: None,
             attribute: Vec::new(),
+            attribute_spans: Vec::new(),
             media: Vec::new(),
             warnings: Vec::new(),
         }
@@ -294,12 +481,19 @@ impl SdpSession {
             return Err(SdpParserInternalError::Generic(format!(
                 "{} not allowed at session level",
                 a
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::AttributeNotAllowedAtSessionLevel));
         };
         self.attribute.push(a);
+        self.attribute_spans.push(None);
         Ok(())
     }
 
+    // The span of the session level attribute at index within attribute
+    pub fn get_attribute_span(&self, index: usize) -> Option<SdpSpan> {
+        self.attribute_spans.get(index).cloned().flatten()
+    }
+
     pub fn extend_media(&mut self, v: Vec<SdpMedia>) {
         self.media.extend(v)
     }
@@ -314,8 +508,13 @@ impl SdpSession {
                         SdpParserError::Sequence {
                             message: format!("{}", e),
                             line_number: _line_number,
+                            line_numbers: vec![_line_number],
+                            kind: e.kind(),
                         }
-                    })?
+                    })?;
+                    // Realign the spans in case attribute was modified directly
+                    self.attribute_spans.resize(self.attribute.len() - 1, None);
+                    self.attribute_spans.push(Some(line.span));
                 }
                 SdpType::Bandwidth(b) => self.add_bandwidth(b),
                 SdpType::Timing(t) => self.set_timing(t),
@@ -325,12 +524,16 @@ impl SdpSession {
                     return Err(SdpParserError::Sequence {
                         message: "version, origin or session at wrong level".to_string(),
                         line_number: line.line_number,
+                        line_numbers: vec![line.line_number],
+                        kind: SdpParserErrorKind::SessionLevelInvalidLine,
                     });
                 }
                 SdpType::Media(_) => {
                     return Err(SdpParserError::Sequence {
                         message: "media line not allowed in session parser".to_string(),
                         line_number: line.line_number,
+                        line_numbers: vec![line.line_number],
+                        kind: SdpParserErrorKind::MediaLineInSession,
                     });
                 }
             }
@@ -384,13 +587,25 @@ impl AnonymizingClone for SdpSession {
             timing: self.timing.clone(),
             bandwidth: self.bandwidth.clone(),
             attribute: Vec::new(),
+            attribute_spans: Vec::new(),
             media: Vec::new(),
             warnings: Vec::new(),
         };
-        masked.origin = self.origin.masked_clone(anon);
+        masked.session = self.session.as_ref().map(|session| {
+            anon.policy().session_name.apply(
+                session,
+                |name| anon.mask_session_name(name),
+                "-".to_string(),
+            )
+        });
         masked.connection = masked.connection.map(|con| con.masked_clone(anon));
         for i in &self.attribute {
-            masked.attribute.push(i.masked_clone(anon));
+            if !i.redacted_by(anon.policy()) {
+                masked.attribute.push(i.masked_clone(anon));
+            }
+        }
+        for media in &self.media {
+            masked.media.push(media.masked_clone(anon));
         }
         masked
     }
@@ -410,7 +625,8 @@ fn parse_version(value: &str) -> Result<SdpType, SdpParserInternalError> {
         return Err(SdpParserInternalError::Generic(format!(
             "version type contains unsupported value {}",
             ver
-        )));
+        ))
+        .with_kind(SdpParserErrorKind::VersionUnsupported));
     };
     trace!("version: {}", ver);
     Ok(SdpType::Version(ver))
@@ -422,7 +638,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing username token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingUsername));
         }
         Some(x) => x,
     };
@@ -430,7 +647,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing session ID token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingSessionId));
         }
         Some(x) => x.parse::<u64>()?,
     };
@@ -438,7 +656,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing session version token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingSessionVersion));
         }
         Some(x) => x.parse::<u64>()?,
     };
@@ -446,7 +665,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing network type token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingNetworkType));
         }
         Some(x) => parse_network_type(x)?,
     };
@@ -454,7 +674,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing address type token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingAddressType));
         }
         Some(x) => parse_address_type(x)?,
     };
@@ -462,14 +683,16 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
         None => {
             return Err(SdpParserInternalError::Generic(
                 "Origin type is missing IP address token".to_string(),
-            ));
+            )
+            .with_kind(SdpParserErrorKind::OriginMissingAddress));
         }
         Some(x) => ExplicitlyTypedAddress::try_from((addrtype, x))?,
     };
     if addrtype != unicast_addr.address_type() {
         return Err(SdpParserInternalError::Generic(
             "Origin addrtype does not match address.".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::OriginAddressTypeMismatch));
     }
     let o = SdpOrigin {
         username: String::from(username),
@@ -486,22 +709,28 @@ fn parse_connection(value: &str) -> Result<SdpType, SdpParserInternalError> {
     if cv.len() != 3 {
         return Err(SdpParserInternalError::Generic(
             "connection attribute must have three tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::ConnectionTokenCount));
     }
     parse_network_type(cv[0])?;
     let addrtype = parse_address_type(cv[1])?;
-    let mut ttl = None;
-    let mut amount = None;
-    let mut addr_token = cv[2];
-    if addr_token.find('/') != None {
-        let addr_tokens: Vec<&str> = addr_token.split('/').collect();
-        if addr_tokens.len() >= 3 {
-            amount = Some(addr_tokens[2].parse::<u32>()?);
-        }
-        ttl = Some(addr_tokens[1].parse::<u8>()?);
-        addr_token = addr_tokens[0];
-    }
+    let mut tokens = cv[2].split('/');
+    let addr_token = tokens.next().unwrap_or_default();
+    let parameters: Vec<&str> = tokens.collect();
     let address = ExplicitlyTypedAddress::try_from((addrtype, addr_token))?;
+    // IP6 multicast addresses only have a number of addresses, see validate
+    let (ttl, amount) = match (addrtype, parameters.as_slice()) {
+        (_, []) => (None, None),
+        (AddressType::IpV6, [amount]) => (None, Some(amount.parse::<u32>()?)),
+        (_, [ttl]) => (Some(ttl.parse::<u8>()?), None),
+        (_, [ttl, amount]) => (Some(ttl.parse::<u8>()?), Some(amount.parse::<u32>()?)),
+        _ => {
+            return Err(SdpParserInternalError::Generic(
+                "connection address has too many parameters".to_string(),
+            )
+            .with_kind(SdpParserErrorKind::ConnectionTokenCount));
+        }
+    };
     let c = SdpConnection {
         address,
         ttl,
@@ -516,7 +745,8 @@ fn parse_bandwidth(value: &str) -> Result<SdpType, SdpParserInternalError> {
     if bv.len() != 2 {
         return Err(SdpParserInternalError::Generic(
             "bandwidth attribute must have two tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::BandwidthTokenCount));
     }
     let bandwidth = bv[1].parse::<u32>()?;
     let bw = match bv[0].to_uppercase().as_ref() {
@@ -534,7 +764,8 @@ fn parse_timing(value: &str) -> Result<SdpType, SdpParserInternalError> {
     if tv.len() != 2 {
         return Err(SdpParserInternalError::Generic(
             "timing attribute must have two tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::TimingTokenCount));
     }
     let start = tv[0].parse::<u64>()?;
     let stop = tv[1].parse::<u64>()?;
@@ -544,9 +775,10 @@ fn parse_timing(value: &str) -> Result<SdpType, SdpParserInternalError> {
 }
 
 fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserError> {
-    if line.find('=') == None {
+    if line.find('=').is_none() {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("missing = character in line".to_string()),
+            error: SdpParserInternalError::Generic("missing = character in line".to_string())
+                .with_kind(SdpParserErrorKind::LineMissingEquals),
             line: line.to_string(),
             line_number,
         });
@@ -555,7 +787,8 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
     let line_type = match splitted_line.next() {
         None => {
             return Err(SdpParserError::Line {
-                error: SdpParserInternalError::Generic("missing type".to_string()),
+                error: SdpParserInternalError::Generic("missing type".to_string())
+                    .with_kind(SdpParserErrorKind::LineMissingType),
                 line: line.to_string(),
                 line_number,
             });
@@ -564,14 +797,16 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
             let trimmed = t.trim();
             if trimmed.len() > 1 {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("type too long".to_string()),
+                    error: SdpParserInternalError::Generic("type too long".to_string())
+                        .with_kind(SdpParserErrorKind::LineTypeTooLong),
                     line: line.to_string(),
                     line_number,
                 });
             }
             if trimmed.is_empty() {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("type is empty".to_string()),
+                    error: SdpParserInternalError::Generic("type is empty".to_string())
+                        .with_kind(SdpParserErrorKind::LineTypeEmpty),
                     line: line.to_string(),
                     line_number,
                 });
@@ -582,7 +817,8 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
     let (line_value, untrimmed_line_value) = match splitted_line.next() {
         None => {
             return Err(SdpParserError::Line {
-                error: SdpParserInternalError::Generic("missing value".to_string()),
+                error: SdpParserInternalError::Generic("missing value".to_string())
+                    .with_kind(SdpParserErrorKind::LineMissingValue),
                 line: line.to_string(),
                 line_number,
             });
@@ -592,7 +828,8 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
             // For compatibility with sites that don't adhere to "s=-" for no session ID
             if trimmed.is_empty() && line_type.as_str() != "s" {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("value is empty".to_string()),
+                    error: SdpParserInternalError::Generic("value is empty".to_string())
+                        .with_kind(SdpParserErrorKind::LineEmptyValue),
                     line: line.to_string(),
                     line_number,
                 });
@@ -607,114 +844,209 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
         "e" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type email: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "i" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type information: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "k" => Err(SdpParserInternalError::Generic(format!(
             "unsupported insecure key exchange: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "m" => parse_media(line_value),
         "o" => parse_origin(line_value),
         "p" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type phone: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "r" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type repeat: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "s" => parse_session(untrimmed_line_value),
         "t" => parse_timing(line_value),
         "u" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type uri: {}",
             line_value
-        ))),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
         "v" => parse_version(line_value),
         "z" => Err(SdpParserInternalError::Generic(format!(
             "unsupported type zone: {}",
             line_value
-        ))),
-        _ => Err(SdpParserInternalError::Generic(
-            "unknown sdp type".to_string(),
-        )),
+        ))
+        .with_kind(SdpParserErrorKind::LineTypeUnsupported)),
+        _ => Err(
+            SdpParserInternalError::Generic("unknown sdp type".to_string())
+                .with_kind(SdpParserErrorKind::LineTypeUnknown),
+        ),
     }
     .map(|sdp_type| SdpLine {
         line_number,
         sdp_type,
         text: line.to_owned(),
+        span: SdpSpan::new(0, line.len()),
     })
-    .map_err(|e| match e {
-        SdpParserInternalError::UnknownAddressType(..)
-        | SdpParserInternalError::AddressTypeMismatch { .. }
-        | SdpParserInternalError::Generic(..)
-        | SdpParserInternalError::Integer(..)
-        | SdpParserInternalError::Float(..)
-        | SdpParserInternalError::Domain(..)
-        | SdpParserInternalError::IpAddress(..) => SdpParserError::Line {
-            error: e,
-            line: line.to_string(),
-            line_number,
-        },
-        SdpParserInternalError::Unsupported(..) => SdpParserError::Unsupported {
-            error: e,
-            line: line.to_string(),
-            line_number,
-        },
+    .map_err(|e| {
+        // Locations of the value parsers are relative to the value
+        let e = e.rebase(line, line_value);
+        match *e.inner() {
+            SdpParserInternalError::Unsupported(..) => SdpParserError::Unsupported {
+                error: e,
+                line: line.to_string(),
+                line_number,
+            },
+            _ => SdpParserError::Line {
+                error: e,
+                line: line.to_string(),
+                line_number,
+            },
+        }
     })
 }
 
-fn sanity_check_sdp_session(session: &SdpSession) -> Result<(), SdpParserError> {
-    let make_seq_error = |x: &str| SdpParserError::Sequence {
-        message: x.to_string(),
-        line_number: 0,
+/*
+ * Remembers which line each attribute and media line of a session came from,
+ * so that errors found after parsing can name the offending lines.
+ */
+#[derive(Default)]
+struct SdpLineIndex {
+    media: Vec<usize>,
+    attributes: Vec<(Option<usize>, SdpAttributeType, usize)>,
+}
+
+impl SdpLineIndex {
+    fn new(lines: &[SdpLine]) -> SdpLineIndex {
+        let mut index = SdpLineIndex::default();
+        for line in lines {
+            match line.sdp_type {
+                SdpType::Media(_) => index.media.push(line.line_number),
+                SdpType::Attribute(ref a) => {
+                    let media = index.media.len().checked_sub(1);
+                    index
+                        .attributes
+                        .push((media, SdpAttributeType::from(a), line.line_number))
+                }
+                _ => (),
+            }
+        }
+        index
+    }
+
+    // Line numbers of the attributes of a type, media is None for session level
+    fn attribute_lines(&self, media: Option<usize>, t: &SdpAttributeType) -> Vec<usize> {
+        self.attributes
+            .iter()
+            .filter(|(m, a, _)| *m == media && a == t)
+            .map(|(_, _, line_number)| *line_number)
+            .collect()
+    }
+
+    fn media_line(&self, media: usize) -> Vec<usize> {
+        self.media.get(media).cloned().into_iter().collect()
+    }
+}
+
+fn sanity_check_sdp_session(
+    session: &SdpSession,
+    index: &SdpLineIndex,
+) -> Result<(), SdpParserError> {
+    let make_seq_error = |kind: SdpParserErrorKind, x: &str, mut line_numbers: Vec<usize>| {
+        line_numbers.sort_unstable();
+        line_numbers.dedup();
+        SdpParserError::Sequence {
+            message: x.to_string(),
+            line_number: line_numbers.first().cloned().unwrap_or(0),
+            line_numbers,
+            kind,
+        }
     };
 
     if session.timing.is_none() {
-        return Err(make_seq_error("Missing timing type at session level"));
+        return Err(make_seq_error(
+            SdpParserErrorKind::MissingTiming,
+            "Missing timing type at session level",
+            Vec::new(),
+        ));
     }
     // Checks that all media have connections if there is no top level
     // This explicitly allows for zero connection lines if there are no media
     // sections for interoperability reasons.
-    let media_cons = &session.media.iter().all(|m| m.get_connection().is_some());
-    if !media_cons && session.get_connection().is_none() {
+    let media_without_cons: Vec<usize> = session
+        .media
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.get_connection().is_none())
+        .map(|(i, _)| i)
+        .collect();
+    if !media_without_cons.is_empty() && session.get_connection().is_none() {
         return Err(make_seq_error(
-            "Without connection type at session level all media sections must have connection types",
+            SdpParserErrorKind::MissingConnection,
+            "Without connection type at session level all media sections must have \
+             connection types",
+            media_without_cons
+                .iter()
+                .flat_map(|i| index.media_line(*i))
+                .collect(),
         ));
     }
 
     // Check that extmaps are not defined on session and media level
     if session.get_attribute(SdpAttributeType::Extmap).is_some() {
-        for msection in &session.media {
-            if msection.get_attribute(SdpAttributeType::Extmap).is_some() {
-                return Err(make_seq_error(
-                    "Extmap can't be define at session and media level",
-                ));
+        let media_with_extmap: Vec<usize> = session
+            .media
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.get_attribute(SdpAttributeType::Extmap).is_some())
+            .map(|(i, _)| i)
+            .collect();
+        if !media_with_extmap.is_empty() {
+            let mut extmap_lines = index.attribute_lines(None, &SdpAttributeType::Extmap);
+            for i in media_with_extmap {
+                extmap_lines.extend(index.attribute_lines(Some(i), &SdpAttributeType::Extmap));
             }
+            return Err(make_seq_error(
+                SdpParserErrorKind::ExtmapAtSessionAndMediaLevel,
+                "Extmap can't be define at session and media level",
+                extmap_lines,
+            ));
         }
     }
 
-    for msection in &session.media {
+    for (i, msection) in session.media.iter().enumerate() {
+        let lines_of = |types: &[SdpAttributeType]| -> Vec<usize> {
+            types
+                .iter()
+                .flat_map(|t| index.attribute_lines(Some(i), t))
+                .collect()
+        };
         if msection.get_attribute(SdpAttributeType::Sendonly).is_some() {
-            if let Some(&SdpAttribute::Simulcast(ref x)) =
+            if let Some(SdpAttribute::Simulcast(x)) =
                 msection.get_attribute(SdpAttributeType::Simulcast)
             {
                 if !x.receive.is_empty() {
                     return Err(make_seq_error(
+                        SdpParserErrorKind::SimulcastReceiveForSendonly,
                         "Simulcast can't define receive parameters for sendonly",
+                        lines_of(&[SdpAttributeType::Sendonly, SdpAttributeType::Simulcast]),
                     ));
                 }
             }
         }
         if msection.get_attribute(SdpAttributeType::Recvonly).is_some() {
-            if let Some(&SdpAttribute::Simulcast(ref x)) =
+            if let Some(SdpAttribute::Simulcast(x)) =
                 msection.get_attribute(SdpAttributeType::Simulcast)
             {
                 if !x.send.is_empty() {
                     return Err(make_seq_error(
+                        SdpParserErrorKind::SimulcastSendForRecvonly,
                         "Simulcast can't define send parameters for recvonly",
+                        lines_of(&[SdpAttributeType::Recvonly, SdpAttributeType::Simulcast]),
                     ));
                 }
             }
@@ -726,7 +1058,9 @@ fn sanity_check_sdp_session(session: &SdpSession) -> Result<(), SdpParserError>
             && msection.get_attribute(SdpAttributeType::RtcpMux).is_none()
         {
             return Err(make_seq_error(
+                SdpParserErrorKind::RtcpMuxOnlyWithoutRtcpMux,
                 "rtcp-mux-only media sections must also contain the rtcp-mux attribute",
+                lines_of(&[SdpAttributeType::RtcpMuxOnly]),
             ));
         }
 
@@ -753,26 +1087,36 @@ fn sanity_check_sdp_session(session: &SdpSession) -> Result<(), SdpParserError>
             })
             .collect();
 
-        for rid_format in rids.iter().flat_map(|rid| &rid.formats) {
-            match *msection.get_formats() {
-                SdpFormatList::Integers(ref int_fmt) => {
-                    if !int_fmt.contains(&(u32::from(*rid_format))) {
-                        return Err(make_seq_error(
-                            "Rid pts must be declared in the media section",
-                        ));
-                    }
-                }
-                SdpFormatList::Strings(ref str_fmt) => {
-                    if !str_fmt.contains(&rid_format.to_string()) {
-                        return Err(make_seq_error(
-                            "Rid pts must be declared in the media section",
-                        ));
-                    }
-                }
-            }
+        let rid_lines = lines_of(&[SdpAttributeType::Rid]);
+        let undeclared_rids: Vec<usize> = rids
+            .iter()
+            .enumerate()
+            .filter(|(_, rid)| {
+                rid.formats
+                    .iter()
+                    .any(|rid_format| match *msection.get_formats() {
+                        SdpFormatList::Integers(ref int_fmt) => {
+                            !int_fmt.contains(&(u32::from(*rid_format)))
+                        }
+                        SdpFormatList::Strings(ref str_fmt) => {
+                            !str_fmt.contains(&rid_format.to_string())
+                        }
+                    })
+            })
+            .map(|(position, _)| position)
+            .collect();
+        if !undeclared_rids.is_empty() {
+            return Err(make_seq_error(
+                SdpParserErrorKind::RidPtUndeclared,
+                "Rid pts must be declared in the media section",
+                undeclared_rids
+                    .iter()
+                    .filter_map(|position| rid_lines.get(*position).cloned())
+                    .collect(),
+            ));
         }
 
-        if let Some(&SdpAttribute::Simulcast(ref simulcast)) =
+        if let Some(SdpAttribute::Simulcast(simulcast)) =
             msection.get_attribute(SdpAttributeType::Simulcast)
         {
             let check_defined_rids =
@@ -782,7 +1126,9 @@ fn sanity_check_sdp_session(session: &SdpSession) -> Result<(), SdpParserError>
                     for simulcast_rid in simulcast_version_list.iter().flat_map(|x| &x.ids) {
                         if !rid_ids.contains(&simulcast_rid.id.as_str()) {
                             return Err(make_seq_error(
+                                SdpParserErrorKind::SimulcastRidUndeclared,
                                 "Simulcast RIDs must be defined in any rid attribute",
+                                lines_of(&[SdpAttributeType::Simulcast]),
                             ));
                         }
                     }
@@ -802,33 +1148,45 @@ fn parse_sdp_vector(lines: &mut Vec<SdpLine>) -> Result<SdpSession, SdpParserErr
         return Err(SdpParserError::Sequence {
             message: "SDP neeeds at least 4 lines".to_string(),
             line_number: 0,
+            line_numbers: Vec::new(),
+            kind: SdpParserErrorKind::TooFewLines,
         });
     }
+    let index = SdpLineIndex::new(lines);
 
-    let version = match lines.remove(0).sdp_type {
+    let line = lines.remove(0);
+    let version = match line.sdp_type {
         SdpType::Version(v) => v,
         _ => {
             return Err(SdpParserError::Sequence {
                 message: "first line needs to be version number".to_string(),
-                line_number: 0,
+                line_number: line.line_number,
+                line_numbers: vec![line.line_number],
+                kind: SdpParserErrorKind::MissingVersion,
             });
         }
     };
-    let origin = match lines.remove(0).sdp_type {
+    let line = lines.remove(0);
+    let origin = match line.sdp_type {
         SdpType::Origin(v) => v,
         _ => {
             return Err(SdpParserError::Sequence {
                 message: "second line needs to be origin".to_string(),
-                line_number: 1,
+                line_number: line.line_number,
+                line_numbers: vec![line.line_number],
+                kind: SdpParserErrorKind::MissingOrigin,
             });
         }
     };
-    let session = match lines.remove(0).sdp_type {
+    let line = lines.remove(0);
+    let session = match line.sdp_type {
         SdpType::Session(v) => v,
         _ => {
             return Err(SdpParserError::Sequence {
                 message: "third line needs to be session".to_string(),
-                line_number: 2,
+                line_number: line.line_number,
+                line_numbers: vec![line.line_number],
+                kind: SdpParserErrorKind::MissingSession,
             });
         }
     };
@@ -847,14 +1205,15 @@ fn parse_sdp_vector(lines: &mut Vec<SdpLine>) -> Result<SdpSession, SdpParserErr
         None => sdp_session.parse_session_vector(lines)?,
     };
 
-    sanity_check_sdp_session(&sdp_session)?;
+    sanity_check_sdp_session(&sdp_session, &index)?;
     Ok(sdp_session)
 }
 
 pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpParserError> {
     if sdp.is_empty() {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("empty SDP".to_string()),
+            error: SdpParserInternalError::Generic("empty SDP".to_string())
+                .with_kind(SdpParserErrorKind::SdpEmpty),
             line: sdp.to_string(),
             line_number: 0,
         });
@@ -862,7 +1221,8 @@ pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpPars
     // see test_parse_sdp_minimal_sdp_successfully
     if sdp.len() < 51 {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("string too short to be valid SDP".to_string()),
+            error: SdpParserInternalError::Generic("string too short to be valid SDP".to_string())
+                .with_kind(SdpParserErrorKind::SdpTooShort),
             line: sdp.to_string(),
             line_number: 0,
         });
@@ -877,7 +1237,10 @@ pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpPars
             continue;
         }
         match parse_sdp_line(line, line_number) {
-            Ok(n) => {
+            Ok(mut n) => {
+                if let Some(span) = SdpSpan::from_subslice(sdp, line) {
+                    n.span = span;
+                }
                 sdp_lines.push(n);
             }
             Err(e) => {
@@ -906,9 +1269,13 @@ pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpPars
                     SdpParserError::Sequence {
                         message,
                         line_number,
+                        line_numbers,
+                        kind,
                     } => errors.push(SdpParserError::Sequence {
                         message,
                         line_number,
+                        line_numbers,
+                        kind,
                     }),
                 }
             }
@@ -5,7 +5,7 @@
 extern crate url;
 use super::*;
 use address::{Address, AddressType};
-use anonymizer::ToBytesVec;
+use anonymizer::{AnonymizationAction, AnonymizationPolicy, ToBytesVec};
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
 
@@ -40,6 +40,20 @@ pub fn create_dummy_media_section() -> SdpMedia {
     SdpMedia::new(media_line)
 }
 
+// Parses the given session attributes and m-sections behind a minimal session
+pub fn parse_dummy_sdp_session(body: &str) -> SdpSession {
+    let sdp = format!(
+        "v=0\r\n\
+         o=- 1 1 IN IP4 0.0.0.0\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         {}",
+        body
+    );
+    parse_sdp(&sdp, true).unwrap()
+}
+
 #[test]
 fn test_session_works() -> Result<(), SdpParserInternalError> {
     parse_session("topic")?;
@@ -126,6 +140,146 @@ fn connection_works() -> Result<(), SdpParserInternalError> {
     Ok(())
 }
 
+fn connection_error_kind(value: &str) -> Option<SdpParserErrorKind> {
+    match parse_connection(value) {
+        Ok(SdpType::Connection(c)) => c.validate().err().map(|e| e.kind()),
+        Ok(_) => unreachable!(),
+        Err(e) => Some(e.kind()),
+    }
+}
+
+#[test]
+fn connection_multicast() -> Result<(), SdpParserInternalError> {
+    if let SdpType::Connection(c) = parse_connection("IN IP4 233.252.0.1/127")? {
+        assert!(c.is_multicast());
+        assert_eq!(c.ttl, Some(127));
+        assert_eq!(c.amount, None);
+        assert_eq!(
+            c.addresses()?,
+            vec!["233.252.0.1".parse::<IpAddr>().unwrap()]
+        );
+    } else {
+        unreachable!();
+    }
+    if let SdpType::Connection(c) = parse_connection("IN IP6 ff15::101/3")? {
+        assert_eq!(c.ttl, None);
+        assert_eq!(c.address_count(), 3);
+        assert_eq!(c.to_string(), "IN IP6 ff15::101/3");
+        assert_eq!(
+            c.addresses()?,
+            ["ff15::101", "ff15::102", "ff15::103"]
+                .iter()
+                .map(|ip| ip.parse::<IpAddr>().unwrap())
+                .collect::<Vec<IpAddr>>()
+        );
+    } else {
+        unreachable!();
+    }
+    if let SdpType::Connection(c) = parse_connection("IN IP4 198.51.100.1")? {
+        assert!(!c.is_multicast());
+    } else {
+        unreachable!();
+    }
+
+    assert_eq!(
+        connection_error_kind("IN IP4 127.0.0.1/10/10"),
+        Some(SdpParserErrorKind::ConnectionNotMulticast)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP4 example.com/127"),
+        Some(SdpParserErrorKind::ConnectionNotMulticast)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP4 233.252.0.1"),
+        Some(SdpParserErrorKind::ConnectionMissingTtl)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP6 ff15::101/1/1"),
+        Some(SdpParserErrorKind::ConnectionTtlNotAllowed)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP4 233.252.0.1/1/1/1"),
+        Some(SdpParserErrorKind::ConnectionTokenCount)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP4 233.252.0.1/1/0"),
+        Some(SdpParserErrorKind::ConnectionAddressCountOutOfRange)
+    );
+    assert_eq!(
+        connection_error_kind("IN IP4 239.255.255.255/1/2"),
+        Some(SdpParserErrorKind::ConnectionAddressCountOutOfRange)
+    );
+    assert!(parse_connection("IN IP4 233.252.0.1/256").is_err());
+    assert_eq!(connection_error_kind("IN IP4 233.252.0.1/127/2"), None);
+    assert_eq!(connection_error_kind("IN IP6 ff15::101/3"), None);
+    Ok(())
+}
+
+#[test]
+fn connection_transport_addresses() -> Result<(), SdpParserInternalError> {
+    let transport_addresses = |c: &str, m: &str| -> Result<Vec<String>, SdpParserInternalError> {
+        match (parse_connection(c)?, parse_media(m)?) {
+            (SdpType::Connection(c), SdpType::Media(m)) => Ok(c
+                .transport_addresses(&m)?
+                .iter()
+                .map(ToString::to_string)
+                .collect()),
+            _ => unreachable!(),
+        }
+    };
+    assert_eq!(
+        transport_addresses("IN IP4 233.252.0.1/127/2", "video 49170/2 RTP/AVP 96")?,
+        vec!["233.252.0.1:49170", "233.252.0.2:49172"]
+    );
+    assert_eq!(
+        transport_addresses("IN IP4 233.252.0.1/127/3", "video 49170 RTP/AVP 96")?,
+        vec![
+            "233.252.0.1:49170",
+            "233.252.0.2:49170",
+            "233.252.0.3:49170"
+        ]
+    );
+    assert_eq!(
+        transport_addresses("IN IP6 ff15::101", "application 5000/2 DTLS/SCTP 5000")?,
+        vec!["[ff15::101]:5000", "[ff15::101]:5001"]
+    );
+    assert_eq!(
+        transport_addresses("IN IP4 233.252.0.1/127/2", "video 49170/3 RTP/AVP 96")
+            .err()
+            .map(|e| e.kind()),
+        Some(SdpParserErrorKind::MediaPortCountMismatch)
+    );
+    assert!(transport_addresses("IN IP4 198.51.100.1", "video 65534/2 RTP/AVP 96").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sanity_check_port_count() {
+    let sdp = |media: &str| {
+        format!(
+            "v=0\r\n\
+             o=- 0 0 IN IP4 0.0.0.0\r\n\
+             s=-\r\n\
+             t=0 0\r\n\
+             c=IN IP4 233.252.0.1/127/2\r\n\
+             m={}\r\n",
+            media
+        )
+    };
+    assert!(parse_sdp(&sdp("video 49170/2 RTP/AVP 96"), true).is_ok());
+    assert!(parse_sdp(&sdp("video 49170 RTP/AVP 96"), true).is_ok());
+    // A mismatch is only reported when the transport addresses are needed
+    let session = parse_sdp(&sdp("video 49170/3 RTP/AVP 96"), true).unwrap();
+    let connection = session.get_connection().as_ref().unwrap();
+    match parse_media("video 49170/3 RTP/AVP 96") {
+        Ok(SdpType::Media(media)) => assert_eq!(
+            connection.transport_addresses(&media).unwrap_err().kind(),
+            SdpParserErrorKind::MediaPortCountMismatch
+        ),
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn connection_lots_of_whitespace() -> Result<(), SdpParserInternalError> {
     parse_connection("IN   IP4   127.0.0.1")?;
@@ -273,12 +427,12 @@ fn test_sanity_check_sdp_session_timing() -> Result<(), SdpParserError> {
     let mut sdp_session = create_dummy_sdp_session();
     sdp_session.extend_media(vec![create_dummy_media_section()]);
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_err());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_err());
 
     let t = SdpTiming { start: 0, stop: 0 };
     sdp_session.set_timing(t);
 
-    sanity_check_sdp_session(&sdp_session)?;
+    sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default())?;
     Ok(())
 }
 
@@ -288,11 +442,11 @@ fn test_sanity_check_sdp_session_media() -> Result<(), SdpParserError> {
     let t = SdpTiming { start: 0, stop: 0 };
     sdp_session.set_timing(t);
 
-    sanity_check_sdp_session(&sdp_session)?;
+    sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default())?;
 
     sdp_session.extend_media(vec![create_dummy_media_section()]);
 
-    sanity_check_sdp_session(&sdp_session)?;
+    sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default())?;
     Ok(())
 }
 
@@ -308,12 +462,12 @@ fn test_sanity_check_sdp_connection() -> Result<(), SdpParserInternalError> {
     let t = SdpTiming { start: 0, stop: 0 };
     sdp_session.set_timing(t);
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_ok());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_ok());
 
     // the dummy media section doesn't contain a connection
     sdp_session.extend_media(vec![create_dummy_media_section()]);
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_err());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_err());
 
     let connection = parse_connection("IN IP6 ::1")?;
     if let SdpType::Connection(c) = connection {
@@ -322,7 +476,7 @@ fn test_sanity_check_sdp_connection() -> Result<(), SdpParserInternalError> {
         unreachable!();
     }
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_ok());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_ok());
 
     let mut second_media = create_dummy_media_section();
     let mconnection = parse_connection("IN IP4 0.0.0.0")?;
@@ -334,7 +488,7 @@ fn test_sanity_check_sdp_connection() -> Result<(), SdpParserInternalError> {
     sdp_session.extend_media(vec![second_media]);
     assert!(sdp_session.media.len() == 2);
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_ok());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_ok());
     Ok(())
 }
 
@@ -356,7 +510,7 @@ fn test_sanity_check_sdp_session_extmap() -> Result<(), SdpParserInternalError>
         .get_attribute(SdpAttributeType::Extmap)
         .is_some());
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_ok());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_ok());
 
     let mut second_media = create_dummy_media_section();
     let mattribute =
@@ -373,11 +527,11 @@ fn test_sanity_check_sdp_session_extmap() -> Result<(), SdpParserInternalError>
     sdp_session.extend_media(vec![second_media]);
     assert!(sdp_session.media.len() == 2);
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_err());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_err());
 
     sdp_session.attribute = Vec::new();
 
-    assert!(sanity_check_sdp_session(&sdp_session).is_ok());
+    assert!(sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default()).is_ok());
     Ok(())
 }
 
@@ -388,7 +542,7 @@ fn test_sanity_check_sdp_session_simulcast() -> Result<(), SdpParserError> {
     sdp_session.set_timing(t);
     sdp_session.extend_media(vec![create_dummy_media_section()]);
 
-    sanity_check_sdp_session(&sdp_session)?;
+    sanity_check_sdp_session(&sdp_session, &SdpLineIndex::default())?;
     Ok(())
 }
 
@@ -614,6 +768,147 @@ fn test_mask_sdp() {
     }
 }
 
+#[test]
+fn test_mask_sdp_media_sections() {
+    let sdp = parse_sdp(
+        "v=0\r\n\
+         o=- 1 1 IN IP4 192.0.2.1\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         c=IN IP4 192.0.2.2\r\n\
+         a=ice-ufrag:audiouser\r\n\
+         a=candidate:1 1 UDP 2122252543 192.0.2.2 50000 typ host\r\n\
+         a=sendrecv\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 120\r\n\
+         c=IN IP4 192.0.2.3\r\n\
+         a=ice-ufrag:videouser\r\n\
+         a=sendonly\r\n",
+        true,
+    )
+    .unwrap();
+    let mut anon = StatefulSdpAnonymizer::new();
+    let masked = sdp.masked_clone(&mut anon);
+
+    assert_eq!(masked.media.len(), 2);
+    assert_eq!(
+        masked.media[0].get_connection().as_ref().unwrap().address,
+        ExplicitlyTypedAddress::Ip(IpAddr::V4(Ipv4Addr::from(2)))
+    );
+    assert_eq!(
+        masked.media[1].get_connection().as_ref().unwrap().address,
+        ExplicitlyTypedAddress::Ip(IpAddr::V4(Ipv4Addr::from(3)))
+    );
+    let text = masked.to_string();
+    assert!(text.contains("a=candidate:1 1 UDP 2122252543 0.0.0.2 1 typ host\r\n"));
+    assert!(text.contains("a=ice-ufrag:ice-user-00000001\r\n"));
+    assert!(text.contains("a=ice-ufrag:ice-user-00000002\r\n"));
+    assert!(text.contains("a=sendonly\r\n"));
+    for original in &["192.0.2.2", "192.0.2.3", "audiouser", "videouser"] {
+        assert!(!text.contains(original), "{} in {}", original, text);
+    }
+}
+
+const POLICY_SDP: &str = "v=0\r\n\
+                          o=- 1 1 IN IP4 192.0.2.1\r\n\
+                          s=Team call\r\n\
+                          t=0 0\r\n\
+                          a=group:BUNDLE audio video\r\n\
+                          a=msid-semantic:WMS stream\r\n\
+                          a=identity:YXNzZXJ0aW9u\r\n\
+                          m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                          c=IN IP4 192.0.2.2\r\n\
+                          a=mid:audio\r\n\
+                          a=msid:stream track\r\n\
+                          a=ice-ufrag:user\r\n\
+                          a=ice-pwd:passwordpasswordpassword\r\n\
+                          a=candidate:1 1 UDP 2122252543 192.0.2.2 50000 typ host\r\n\
+                          a=ssrc:1 cname:alice\r\n\
+                          a=ssrc:1 msid:stream track\r\n\
+                          a=sendrecv\r\n\
+                          m=video 9 UDP/TLS/RTP/SAVPF 120\r\n\
+                          c=IN IP4 192.0.2.2\r\n\
+                          a=mid:video\r\n\
+                          a=label:camera\r\n\
+                          a=rid:hi send\r\n\
+                          a=rid:lo send\r\n\
+                          a=simulcast:send hi;~lo\r\n\
+                          a=sendonly\r\n";
+
+#[test]
+fn test_anonymization_policy_keep_ports() {
+    let sdp = parse_sdp(POLICY_SDP, true).unwrap();
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy {
+        ports: AnonymizationAction::Keep,
+        ..AnonymizationPolicy::default()
+    });
+    let masked = sdp.masked_clone(&mut anon).to_string();
+    assert!(masked.contains("a=candidate:1 1 UDP 2122252543 0.0.0.2 50000 typ host\r\n"));
+    assert!(masked.contains("c=IN IP4 0.0.0.2\r\n"));
+    assert!(masked.contains("s=Team call\r\n"));
+    assert!(masked.contains("a=mid:audio\r\n"));
+    assert!(masked.contains("a=ssrc:1 cname:cname-00000001\r\n"));
+    assert!(masked.contains("a=ssrc:1 msid:stream track\r\n"));
+}
+
+#[test]
+fn test_anonymization_policy_mask_all() {
+    let sdp = parse_sdp(POLICY_SDP, true).unwrap();
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::mask_all());
+    let masked = sdp.masked_clone(&mut anon);
+    let text = masked.to_string();
+    for expected in &[
+        "s=session-00000001\r\n",
+        "a=group:BUNDLE mid-00000001 mid-00000002\r\n",
+        "a=msid-semantic:WMS msid-00000001\r\n",
+        "a=identity:identity-00000001\r\n",
+        "a=mid:mid-00000001\r\n",
+        "a=msid:msid-00000001 msid-00000002\r\n",
+        "a=ssrc:1 msid:msid-00000001 msid-00000002\r\n",
+        "a=label:label-00000001\r\n",
+        "a=rid:rid-00000001 send\r\n",
+        "a=simulcast:send rid-00000001;~rid-00000002\r\n",
+    ] {
+        assert!(text.contains(expected), "{} missing in {}", expected, text);
+    }
+    for original in &["Team call", "stream", "alice", "camera", "YXNzZXJ0aW9u"] {
+        assert!(!text.contains(original), "{} in {}", original, text);
+    }
+    assert!(parse_sdp(&text, true).is_ok());
+}
+
+#[test]
+fn test_anonymization_policy_redact_all() {
+    let sdp = parse_sdp(POLICY_SDP, true).unwrap();
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::redact_all());
+    let text = sdp.masked_clone(&mut anon).to_string();
+    assert!(text.starts_with("v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\n"));
+    assert!(text.contains("c=IN IP4 0.0.0.0\r\n"));
+    for removed in &[
+        "a=group",
+        "a=msid",
+        "a=identity",
+        "a=mid",
+        "a=ice-",
+        "a=candidate",
+        "a=ssrc",
+        "a=label",
+        "a=rid",
+        "a=simulcast",
+    ] {
+        assert!(!text.contains(removed), "{} in {}", removed, text);
+    }
+    assert!(text.contains("a=sendonly\r\n"));
+    assert!(parse_sdp(&text, true).is_ok());
+
+    let mut anon = StatefulSdpAnonymizer::new();
+    anon.set_policy(AnonymizationPolicy::keep_all());
+    assert_eq!(sdp.masked_clone(&mut anon).to_string(), sdp.to_string());
+}
+
 #[test]
 fn test_parse_session_vector() -> Result<(), SdpParserError> {
     let mut sdp_session = create_dummy_sdp_session();
@@ -772,3 +1067,174 @@ fn test_session_add_media_invalid_attribute_fails() -> Result<(), SdpParserInter
         .is_err());
     Ok(())
 }
+
+#[test]
+fn test_parse_sdp_line_spans() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n";
+    let error = parse_sdp(
+        &format!(
+            "{}m=video 9 UDP/TLS/RTP/SAVPF 96\r\na=fmtp:96 max-fs=1200;max-fr=foo\r\n",
+            sdp
+        ),
+        true,
+    )
+    .unwrap_err();
+    assert_eq!(error.span(), Some(SdpSpan::new(22, 32)));
+    assert_eq!(error.column(), Some(23));
+
+    let error = parse_sdp(
+        &format!(
+            "{}m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+             a=candidate:1 1 UDP 2122252543 192.168.1.1 99999 typ host\r\n",
+            sdp
+        ),
+        true,
+    )
+    .unwrap_err();
+    assert_eq!(error.column(), Some(44));
+
+    // Errors without a precise location point at the value
+    let error = parse_sdp(&format!("{}a=group:FOO 0\r\n", sdp), true).unwrap_err();
+    assert!(matches!(error, SdpParserError::Unsupported { .. }));
+    assert_eq!(error.column(), Some(3));
+
+    let lines = "v=0\r\nx=1";
+    let line = parse_sdp_line(&lines[5..], 1).unwrap_err();
+    assert_eq!(line.column(), Some(3));
+}
+
+#[test]
+fn test_parse_sdp_model_spans() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=ice-lite\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               a=rtpmap:111 opus/48000/2\r\n\
+               a=sendrecv\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=mid:1\r\n";
+    let mut session = parse_sdp(sdp, true).unwrap();
+    let text = |span: Option<SdpSpan>| span.map(|s| &sdp[s.start..s.end]);
+
+    assert_eq!(text(session.get_attribute_span(0)), Some("a=ice-lite"));
+    assert_eq!(text(session.get_attribute_span(1)), None);
+    assert_eq!(
+        text(session.media[0].get_span()),
+        Some("m=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=rtpmap:111 opus/48000/2\r\na=sendrecv")
+    );
+    assert_eq!(
+        text(session.media[0].get_attribute_span(1)),
+        Some("a=sendrecv")
+    );
+    assert_eq!(
+        text(session.media[1].get_span()),
+        Some("m=video 9 UDP/TLS/RTP/SAVPF 96\r\na=mid:1")
+    );
+
+    // Spans follow their attributes when other attributes are removed
+    session.media[0].remove_attribute(SdpAttributeType::Rtpmap);
+    assert_eq!(
+        text(session.media[0].get_attribute_span(0)),
+        Some("a=sendrecv")
+    );
+    session.media[0]
+        .add_attribute(SdpAttribute::RtcpMux)
+        .unwrap();
+    assert_eq!(session.media[0].get_attribute_span(1), None);
+}
+
+#[test]
+fn test_parse_sdp_sequence_error_lines() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+               a=extmap:3 urn:3gpp:video-orientation\r\n";
+    match parse_sdp(sdp, true) {
+        Err(SdpParserError::Sequence {
+            line_number,
+            line_numbers,
+            ..
+        }) => {
+            assert_eq!(line_number, 5);
+            assert_eq!(line_numbers, vec![5, 8, 9]);
+        }
+        _ => unreachable!(),
+    }
+
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=rid:foo send pt=97\r\n";
+    let error = parse_sdp(sdp, true).unwrap_err();
+    assert_eq!(
+        format!("{}", error),
+        "Sequence error in line(6): Without connection type at session level all media sections must have connection types"
+    );
+
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+               a=rid:foo send pt=96\r\n\
+               a=rid:bar send pt=97\r\n";
+    match parse_sdp(sdp, true) {
+        Err(SdpParserError::Sequence { line_numbers, .. }) => {
+            assert_eq!(line_numbers, vec![7])
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_parse_sdp_error_kinds() {
+    let header = "v=0\r\n\
+                  o=- 1 1 IN IP4 0.0.0.0\r\n\
+                  s=-\r\n\
+                  t=0 0\r\n\
+                  c=IN IP4 0.0.0.0\r\n";
+    let kind_of = |body: &str| {
+        parse_sdp(&format!("{}{}", header, body), true)
+            .unwrap_err()
+            .kind()
+    };
+
+    assert_eq!(
+        kind_of("m=audio 70000 UDP/TLS/RTP/SAVPF 111\r\n"),
+        SdpParserErrorKind::MediaPortOutOfRange
+    );
+    assert_eq!(
+        kind_of("m=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=candidate:0 1 UDP 2122252543\r\n"),
+        SdpParserErrorKind::CandidateTooFewTokens
+    );
+    assert_eq!(
+        kind_of("m=video 9 UDP/TLS/RTP/SAVPF 96\r\na=rid:foo send pt=97\r\n"),
+        SdpParserErrorKind::RidPtUndeclared
+    );
+    assert_eq!(
+        kind_of("m=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=mid\r\n"),
+        SdpParserErrorKind::AttributeMissingValue
+    );
+    assert_eq!(
+        parse_sdp("v=0\r\n", true).unwrap_err().kind(),
+        SdpParserErrorKind::SdpTooShort
+    );
+}
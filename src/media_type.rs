@@ -6,7 +6,7 @@ use anonymizer::{AnonymizingClone, StatefulSdpAnonymizer};
 use attribute_type::{
     maybe_print_param, SdpAttribute, SdpAttributeRtpmap, SdpAttributeSctpmap, SdpAttributeType,
 };
-use error::{SdpParserError, SdpParserInternalError};
+use error::{SdpParserError, SdpParserErrorKind, SdpParserInternalError, SdpSpan};
 use std::fmt;
 use {SdpBandwidth, SdpConnection, SdpLine, SdpType};
 
@@ -16,7 +16,7 @@ use {SdpBandwidth, SdpConnection, SdpLine, SdpType};
  *                       SP proto 1*(SP fmt) CRLF
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpMediaLine {
     pub media: SdpMediaValue,
@@ -41,11 +41,13 @@ impl fmt::Display for SdpMediaLine {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpMediaValue {
     Audio,
     Video,
     Application,
+    Image,
+    Text,
 }
 
 impl fmt::Display for SdpMediaValue {
@@ -54,13 +56,15 @@ impl fmt::Display for SdpMediaValue {
             SdpMediaValue::Audio => "audio",
             SdpMediaValue::Video => "video",
             SdpMediaValue::Application => "application",
+            SdpMediaValue::Image => "image",
+            SdpMediaValue::Text => "text",
         }
         .fmt(f)
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum SdpProtocolValue {
     RtpAvp,          /* RTP/AVP [RFC4566] */
     RtpAvpf,         /* RTP/AVPF [RFC4585] */
@@ -73,6 +77,8 @@ pub enum SdpProtocolValue {
     DtlsSctp,        /* DTLS/SCTP [draft-ietf-mmusic-sctp-sdp-07] */
     UdpDtlsSctp,     /* UDP/DTLS/SCTP [draft-ietf-mmusic-sctp-sdp-26] */
     TcpDtlsSctp,     /* TCP/DTLS/SCTP [draft-ietf-mmusic-sctp-sdp-26] */
+    Udptl,           /* udptl [ITU-T T.38] */
+    UdpTlsUdptl,     /* UDP/TLS/UDPTL [RFC7345] */
 }
 
 impl fmt::Display for SdpProtocolValue {
@@ -89,13 +95,28 @@ impl fmt::Display for SdpProtocolValue {
             SdpProtocolValue::DtlsSctp => "DTLS/SCTP",
             SdpProtocolValue::UdpDtlsSctp => "UDP/DTLS/SCTP",
             SdpProtocolValue::TcpDtlsSctp => "TCP/DTLS/SCTP",
+            SdpProtocolValue::Udptl => "udptl",
+            SdpProtocolValue::UdpTlsUdptl => "UDP/TLS/UDPTL",
         }
         .fmt(f)
     }
 }
 
+impl SdpProtocolValue {
+    pub fn is_rtp(&self) -> bool {
+        !matches!(
+            *self,
+            SdpProtocolValue::DtlsSctp
+                | SdpProtocolValue::UdpDtlsSctp
+                | SdpProtocolValue::TcpDtlsSctp
+                | SdpProtocolValue::Udptl
+                | SdpProtocolValue::UdpTlsUdptl
+        )
+    }
+}
+
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpFormatList {
     Integers(Vec<u32>),
@@ -122,13 +143,19 @@ impl fmt::Display for SdpFormatList {
  *                       attribute-fields )
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpMedia {
     media: SdpMediaLine,
     connection: Option<SdpConnection>,
     bandwidth: Vec<SdpBandwidth>,
     attribute: Vec<SdpAttribute>,
+    // Byte offsets within the parsed SDP of the whole m-section and of each
+    // of its attributes. Attributes which were not parsed have no span.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    span: Option<SdpSpan>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    attribute_spans: Vec<Option<SdpSpan>>,
     // unsupported values:
     // information: Option<String>,
     // key: Option<String>,
@@ -154,9 +181,20 @@ impl SdpMedia {
             connection: None,
             bandwidth: Vec::new(),
             attribute: Vec::new(),
+            span: None,
+            attribute_spans: Vec::new(),
         }
     }
 
+    pub fn get_span(&self) -> Option<SdpSpan> {
+        self.span
+    }
+
+    // The span of the attribute at index within get_attributes()
+    pub fn get_attribute_span(&self, index: usize) -> Option<SdpSpan> {
+        self.attribute_spans.get(index).cloned().flatten()
+    }
+
     pub fn get_type(&self) -> &SdpMediaValue {
         &self.media.media
     }
@@ -193,14 +231,21 @@ impl SdpMedia {
         &self.attribute
     }
 
-    pub fn add_attribute(&mut self, attr: SdpAttribute) -> Result<(), SdpParserInternalError> {
+    fn check_attribute(attr: &SdpAttribute) -> Result<(), SdpParserInternalError> {
         if !attr.allowed_at_media_level() {
             return Err(SdpParserInternalError::Generic(format!(
                 "{} not allowed at media level",
                 attr
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::AttributeNotAllowedAtMediaLevel));
         }
+        Ok(())
+    }
+
+    pub fn add_attribute(&mut self, attr: SdpAttribute) -> Result<(), SdpParserInternalError> {
+        SdpMedia::check_attribute(&attr)?;
         self.attribute.push(attr);
+        self.attribute_spans.push(None);
         Ok(())
     }
 
@@ -211,7 +256,46 @@ impl SdpMedia {
     }
 
     pub fn remove_attribute(&mut self, t: SdpAttributeType) {
-        self.attribute.retain(|a| SdpAttributeType::from(a) != t);
+        self.retain_attributes(|a| SdpAttributeType::from(a) != t);
+    }
+
+    pub fn retain_attributes<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&SdpAttribute) -> bool,
+    {
+        // Retain visits the attributes in order, so the spans stay aligned
+        let mut spans = self
+            .attribute_spans
+            .drain(..)
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut kept_spans = Vec::new();
+        self.attribute.retain(|a| {
+            let span = spans.next().flatten();
+            let kept = keep(a);
+            if kept {
+                kept_spans.push(span);
+            }
+            kept
+        });
+        self.attribute_spans = kept_spans;
+    }
+
+    /*
+     * Replaces every attribute in place with the one returned by map. The
+     * replacements are checked like added attributes, an attribute is left
+     * unchanged if its replacement is not allowed at the media level.
+     */
+    pub fn map_attributes<F>(&mut self, mut map: F) -> Result<(), SdpParserInternalError>
+    where
+        F: FnMut(&SdpAttribute) -> SdpAttribute,
+    {
+        for attribute in &mut self.attribute {
+            let mapped = map(attribute);
+            SdpMedia::check_attribute(&mapped)?;
+            *attribute = mapped;
+        }
+        Ok(())
     }
 
     pub fn set_attribute(&mut self, attr: SdpAttribute) -> Result<(), SdpParserInternalError> {
@@ -225,7 +309,7 @@ impl SdpMedia {
             SdpFormatList::Strings(_) => self.media.formats = SdpFormatList::Strings(Vec::new()),
         }
 
-        self.attribute.retain({
+        self.retain_attributes({
             |x| {
                 !matches!(
                     *x,
@@ -301,11 +385,17 @@ impl AnonymizingClone for SdpMedia {
         let mut masked = SdpMedia {
             media: self.media.clone(),
             bandwidth: self.bandwidth.clone(),
-            connection: self.connection.clone(),
+            connection: self.connection.as_ref().map(|c| c.masked_clone(anon)),
             attribute: Vec::new(),
+            // The masked values do not have the lengths of the parsed ones
+            span: None,
+            attribute_spans: Vec::new(),
         };
         for i in &self.attribute {
-            masked.attribute.push(i.masked_clone(anon));
+            if !i.redacted_by(anon.policy()) {
+                masked.attribute.push(i.masked_clone(anon));
+                masked.attribute_spans.push(None);
+            }
         }
         masked
     }
@@ -316,11 +406,14 @@ fn parse_media_token(value: &str) -> Result<SdpMediaValue, SdpParserInternalErro
         "audio" => SdpMediaValue::Audio,
         "video" => SdpMediaValue::Video,
         "application" => SdpMediaValue::Application,
+        "image" => SdpMediaValue::Image,
+        "text" => SdpMediaValue::Text,
         _ => {
             return Err(SdpParserInternalError::Unsupported(format!(
                 "unsupported media value: {}",
                 value
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::MediaUnknownType));
         }
     })
 }
@@ -338,11 +431,14 @@ fn parse_protocol_token(value: &str) -> Result<SdpProtocolValue, SdpParserIntern
         "DTLS/SCTP" => SdpProtocolValue::DtlsSctp,
         "UDP/DTLS/SCTP" => SdpProtocolValue::UdpDtlsSctp,
         "TCP/DTLS/SCTP" => SdpProtocolValue::TcpDtlsSctp,
+        "UDPTL" => SdpProtocolValue::Udptl,
+        "UDP/TLS/UDPTL" => SdpProtocolValue::UdpTlsUdptl,
         _ => {
             return Err(SdpParserInternalError::Unsupported(format!(
                 "unsupported protocol value: {}",
                 value
-            )));
+            ))
+            .with_kind(SdpParserErrorKind::MediaUnknownProtocol));
         }
     })
 }
@@ -352,22 +448,25 @@ pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
     if mv.len() < 4 {
         return Err(SdpParserInternalError::Generic(
             "media attribute must have at least four tokens".to_string(),
-        ));
+        )
+        .with_kind(SdpParserErrorKind::MediaTooFewTokens));
     }
     let media = parse_media_token(mv[0])?;
     let mut ptokens = mv[1].split('/');
     let port = match ptokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
-                "missing port token".to_string(),
-            ));
+            return Err(
+                SdpParserInternalError::Generic("missing port token".to_string())
+                    .with_kind(SdpParserErrorKind::MediaMissingPort),
+            );
         }
         Some(p) => p.parse::<u32>()?,
     };
     if port > 65535 {
-        return Err(SdpParserInternalError::Generic(
-            "media port token is too big".to_string(),
-        ));
+        return Err(
+            SdpParserInternalError::Generic("media port token is too big".to_string())
+                .with_kind(SdpParserErrorKind::MediaPortOutOfRange),
+        );
     }
     let port_count = match ptokens.next() {
         None => 0,
@@ -376,7 +475,7 @@ pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let proto = parse_protocol_token(mv[2])?;
     let fmt_slice: &[&str] = &mv[3..];
     let formats = match media {
-        SdpMediaValue::Audio | SdpMediaValue::Video => {
+        SdpMediaValue::Audio | SdpMediaValue::Video | SdpMediaValue::Text => {
             let mut fmt_vec: Vec<u32> = vec![];
             for num in fmt_slice {
                 let fmt_num = num.parse::<u32>()?;
@@ -387,13 +486,14 @@ pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
                     13 |  // Comfort Noise
                     35 ..= 63 | 96 ..= 127 => (),  // dynamic range
                     _ => return Err(SdpParserInternalError::Generic(
-                          "format number in media line is out of range".to_string()))
+                          "format number in media line is out of range".to_string())
+                          .with_kind(SdpParserErrorKind::MediaFormatOutOfRange))
                 };
                 fmt_vec.push(fmt_num);
             }
             SdpFormatList::Integers(fmt_vec)
         }
-        SdpMediaValue::Application => {
+        SdpMediaValue::Application | SdpMediaValue::Image => {
             let mut fmt_vec: Vec<String> = vec![];
             // TODO enforce length == 1 and content 'webrtc-datachannel' only?
             for token in fmt_slice {
@@ -423,19 +523,25 @@ pub fn parse_media_vector(lines: &mut Vec<SdpLine>) -> Result<Vec<SdpMedia>, Sdp
             return Err(SdpParserError::Sequence {
                 message: "first line in media section needs to be a media line".to_string(),
                 line_number: media_line.line_number,
+                line_numbers: vec![media_line.line_number],
+                kind: SdpParserErrorKind::MediaSectionMissingMediaLine,
             });
         }
     };
+    sdp_media.span = Some(media_line.span);
 
     while !lines.is_empty() {
         let line = lines.remove(0);
         let _line_number = line.line_number;
+        let span = line.span;
         match line.sdp_type {
             SdpType::Connection(c) => {
                 if sdp_media.connection.is_some() {
                     return Err(SdpParserError::Sequence {
                         message: "connection type already exists at this media level".to_string(),
                         line_number: _line_number,
+                        line_numbers: vec![_line_number],
+                        kind: SdpParserErrorKind::MediaDuplicateConnection,
                     });
                 }
 
@@ -443,6 +549,7 @@ pub fn parse_media_vector(lines: &mut Vec<SdpLine>) -> Result<Vec<SdpMedia>, Sdp
             }
             SdpType::Bandwidth(b) => sdp_media.add_bandwidth(b),
             SdpType::Attribute(a) => {
+                let attributes = sdp_media.attribute.len();
                 match a {
                     SdpAttribute::DtlsMessage(_) => {
                         // Ignore this attribute on media level
@@ -461,20 +568,32 @@ pub fn parse_media_vector(lines: &mut Vec<SdpLine>) -> Result<Vec<SdpMedia>, Sdp
                 .map_err(|e: SdpParserInternalError| SdpParserError::Sequence {
                     message: format!("{}", e),
                     line_number: _line_number,
-                })?
+                    line_numbers: vec![_line_number],
+                    kind: e.kind(),
+                })?;
+                if sdp_media.attribute.len() > attributes {
+                    sdp_media.attribute_spans[attributes] = Some(span);
+                }
             }
             SdpType::Media(v) => {
                 media_sections.push(sdp_media);
                 sdp_media = SdpMedia::new(v);
+                sdp_media.span = Some(span);
             }
 
             SdpType::Origin(_) | SdpType::Session(_) | SdpType::Timing(_) | SdpType::Version(_) => {
                 return Err(SdpParserError::Sequence {
                     message: "invalid type in media section".to_string(),
                     line_number: line.line_number,
+                    line_numbers: vec![line.line_number],
+                    kind: SdpParserErrorKind::MediaSectionInvalidLine,
                 });
             }
         };
+        // The m-section extends to the end of its last line
+        sdp_media.span = sdp_media
+            .span
+            .map(|media_span| SdpSpan::new(media_span.start, span.end));
     }
 
     media_sections.push(sdp_media);
@@ -8,6 +8,7 @@ use attribute_type::{
     SdpAttributeFmtp, SdpAttributeFmtpParameters, SdpAttributePayloadType, SdpAttributeRtcpFb,
     SdpAttributeRtcpFbType,
 };
+use error::SdpSpan;
 use std::convert::TryFrom;
 
 pub fn create_dummy_media_section() -> SdpMedia {
@@ -62,9 +63,11 @@ pub fn add_dummy_attributes(media: &mut SdpMedia) {
                 ptime: 0,
                 minptime: 0,
                 maxptime: 0,
+                cps: 0,
                 encodings: Vec::new(),
                 dtmf_tones: "".to_string(),
                 rtx: None,
+                st2110_video: None,
                 unknown_tokens: Vec::new()
             }
         },))
@@ -122,6 +125,26 @@ fn test_add_codec() -> Result<(), SdpParserInternalError> {
     Ok(())
 }
 
+#[test]
+fn test_text_codecs() -> Result<(), SdpParserInternalError> {
+    let mut msection = SdpMedia::new(check_parse("text 9 UDP/TLS/RTP/SAVPF 100 98"));
+    assert_eq!(*msection.get_type(), SdpMediaValue::Text);
+    msection.remove_codecs();
+    msection.add_codec(SdpAttributeRtpmap::new(98, "t140".to_string(), 1000))?;
+    msection.add_codec(SdpAttributeRtpmap::new(100, "red".to_string(), 1000))?;
+    match *msection.get_formats() {
+        SdpFormatList::Integers(ref formats) => assert_eq!(*formats, vec![98, 100]),
+        _ => unreachable!(),
+    }
+    assert_eq!(
+        msection
+            .get_attributes_of_type(SdpAttributeType::Rtpmap)
+            .len(),
+        2
+    );
+    Ok(())
+}
+
 #[test]
 fn test_remove_codecs() -> Result<(), SdpParserInternalError> {
     let mut msection = create_dummy_media_section();
@@ -149,6 +172,29 @@ fn test_remove_codecs() -> Result<(), SdpParserInternalError> {
     Ok(())
 }
 
+#[test]
+fn test_map_attributes() -> Result<(), SdpParserInternalError> {
+    let mut msection = create_dummy_media_section();
+    msection.add_attribute(SdpAttribute::Mid("foo".to_string()))?;
+    msection.add_attribute(SdpAttribute::Sendrecv)?;
+
+    msection.map_attributes(|attribute| match attribute {
+        SdpAttribute::Mid(_) => SdpAttribute::Mid("bar".to_string()),
+        _ => attribute.clone(),
+    })?;
+    assert_eq!(msection.get_attributes()[0].to_string(), "mid:bar");
+
+    let error = msection
+        .map_attributes(|_| SdpAttribute::IceLite)
+        .unwrap_err();
+    assert_eq!(
+        error.kind(),
+        SdpParserErrorKind::AttributeNotAllowedAtMediaLevel
+    );
+    assert_eq!(msection.get_attributes()[1].to_string(), "sendrecv");
+    Ok(())
+}
+
 #[test]
 fn test_add_datachannel() -> Result<(), SdpParserInternalError> {
     let mut msection = create_dummy_media_section();
@@ -219,6 +265,8 @@ fn test_parse_media_token() -> Result<(), SdpParserInternalError> {
     assert_eq!(video, SdpMediaValue::Video);
     let app = parse_media_token("aPplIcatIOn")?;
     assert_eq!(app, SdpMediaValue::Application);
+    let text = parse_media_token("text")?;
+    assert_eq!(text, SdpMediaValue::Text);
 
     assert!(parse_media_token("").is_err());
     assert!(parse_media_token("foobar").is_err());
@@ -277,6 +325,7 @@ fn test_media_works() {
     check_parse_and_serialize("audio 9 UDP/TLS/RTP/SAVPF 109 9 0 8");
     check_parse_and_serialize("audio 0 UDP/TLS/RTP/SAVPF 8");
     check_parse_and_serialize("audio 9/2 UDP/TLS/RTP/SAVPF 8");
+    check_parse_and_serialize("text 11000 RTP/AVP 100 98");
 }
 
 #[test]
@@ -316,6 +365,7 @@ fn test_media_vector_first_line_failure() {
         line_number: 0,
         sdp_type: SdpType::Session("hello".to_string()),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(line);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -335,6 +385,7 @@ fn test_media_vector_multiple_connections() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(media);
     let c = SdpConnection {
@@ -346,12 +397,14 @@ fn test_media_vector_multiple_connections() {
         line_number: 1,
         sdp_type: SdpType::Connection(c.clone()),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(c1);
     let c2 = SdpLine {
         line_number: 2,
         sdp_type: SdpType::Connection(c),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(c2);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -371,6 +424,7 @@ fn test_media_vector_invalid_types() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(media);
     use SdpTiming;
@@ -379,6 +433,7 @@ fn test_media_vector_invalid_types() {
         line_number: 1,
         sdp_type: SdpType::Timing(t),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(tline);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -398,6 +453,7 @@ fn test_media_vector_invalid_media_level_attribute() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(media);
     let a = SdpAttribute::IceLite;
@@ -405,6 +461,7 @@ fn test_media_vector_invalid_media_level_attribute() {
         line_number: 1,
         sdp_type: SdpType::Attribute(a),
         text: "".to_owned(),
+        span: SdpSpan::default(),
     };
     sdp_lines.push(aline);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
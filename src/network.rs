@@ -3,7 +3,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use address::{Address, AddressType};
-use error::SdpParserInternalError;
+use error::{SdpParserErrorKind, SdpParserInternalError};
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -16,16 +16,19 @@ pub fn ip_address_to_string(addr: IpAddr) -> String {
 
 pub fn parse_network_type(value: &str) -> Result<(), SdpParserInternalError> {
     if value.to_uppercase() != "IN" {
-        return Err(SdpParserInternalError::Generic(
-            "nettype must be IN".to_string(),
-        ));
+        return Err(
+            SdpParserInternalError::Generic("nettype must be IN".to_string())
+                .with_kind(SdpParserErrorKind::NetworkTypeUnsupported),
+        );
     };
     Ok(())
 }
 
 pub fn parse_address_type(value: &str) -> Result<AddressType, SdpParserInternalError> {
-    AddressType::from_str(value.to_uppercase().as_str())
-        .map_err(|_| SdpParserInternalError::Generic("address type must be IP4 or IP6".to_string()))
+    AddressType::from_str(value.to_uppercase().as_str()).map_err(|_| {
+        SdpParserInternalError::Generic("address type must be IP4 or IP6".to_string())
+            .with_kind(SdpParserErrorKind::UnknownAddressType)
+    })
 }
 
 pub fn parse_unicast_address(value: &str) -> Result<Address, SdpParserInternalError> {
@@ -0,0 +1,390 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{
+    SdpAttribute, SdpAttributeGroupSemantic, SdpAttributeMsid, SdpAttributeMsidSemantic,
+    SdpAttributeSsrc, SdpAttributeType,
+};
+use error::SdpParserInternalError;
+use media_type::{SdpMedia, SdpMediaValue};
+use SdpSession;
+
+/*
+ * Plan B describes all tracks of one media type inside a single m-section and
+ * tells them apart through the msid carried by each a=ssrc line. Unified Plan
+ * (RFC 8829) uses one m-section with its own a=mid and a=msid per track.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpPlan {
+    UnifiedPlan,
+    PlanB,
+}
+
+// Returns the distinct "stream track" values of the msid ssrc attributes
+// in the order of their first appearance.
+fn ssrc_msids(media: &SdpMedia) -> Vec<String> {
+    let mut msids: Vec<String> = Vec::new();
+    for attr in media.get_attributes() {
+        if let SdpAttribute::Ssrc(ssrc) = attr {
+            if let Some(msid) = ssrc_msid(ssrc) {
+                if !msids.contains(&msid) {
+                    msids.push(msid);
+                }
+            }
+        }
+    }
+    msids
+}
+
+fn ssrc_msid(ssrc: &SdpAttributeSsrc) -> Option<String> {
+    match (&ssrc.attribute, &ssrc.value) {
+        (Some(attribute), Some(value)) if attribute.to_lowercase() == "msid" => {
+            Some(value.trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+fn msid_from_value(value: &str) -> SdpAttributeMsid {
+    let mut tokens = value.split_whitespace();
+    SdpAttributeMsid {
+        id: tokens.next().unwrap_or("-").to_string(),
+        appdata: tokens.next().map(ToString::to_string),
+    }
+}
+
+fn get_mid(media: &SdpMedia) -> Option<String> {
+    match media.get_attribute(SdpAttributeType::Mid) {
+        Some(SdpAttribute::Mid(mid)) => Some(mid.clone()),
+        _ => None,
+    }
+}
+
+fn get_msid(media: &SdpMedia) -> Option<&SdpAttributeMsid> {
+    match media.get_attribute(SdpAttributeType::Msid) {
+        Some(SdpAttribute::Msid(msid)) => Some(msid),
+        _ => None,
+    }
+}
+
+fn is_rtp_media(media: &SdpMedia) -> bool {
+    matches!(
+        *media.get_type(),
+        SdpMediaValue::Audio | SdpMediaValue::Video | SdpMediaValue::Text
+    )
+}
+
+// Hands out numeric mids which are not used by any m-section yet.
+struct MidGenerator {
+    used: Vec<String>,
+    next: u32,
+}
+
+impl MidGenerator {
+    fn new(session: &SdpSession) -> Self {
+        MidGenerator {
+            used: session.media.iter().filter_map(get_mid).collect(),
+            next: 0,
+        }
+    }
+
+    fn generate(&mut self) -> String {
+        loop {
+            let candidate = self.next.to_string();
+            self.next += 1;
+            if !self.used.contains(&candidate) {
+                self.used.push(candidate.clone());
+                return candidate;
+            }
+        }
+    }
+}
+
+pub fn detect_plan(session: &SdpSession) -> SdpPlan {
+    if session
+        .media
+        .iter()
+        .any(|media| ssrc_msids(media).len() > 1)
+    {
+        SdpPlan::PlanB
+    } else {
+        SdpPlan::UnifiedPlan
+    }
+}
+
+// Replaces the tags of all BUNDLE groups according to the given list of
+// (old mid, new mids) pairs. An empty list of new mids removes the tag.
+fn update_bundle_groups(session: &mut SdpSession, replacements: &[(String, Vec<String>)]) {
+    for attr in session.attribute.iter_mut() {
+        if let SdpAttribute::Group(ref mut group) = *attr {
+            if let SdpAttributeGroupSemantic::Bundle = group.semantics {
+                group.tags = group
+                    .tags
+                    .iter()
+                    .flat_map(
+                        |tag| match replacements.iter().find(|(old, _)| old == tag) {
+                            Some((_, new)) => new.clone(),
+                            None => vec![tag.clone()],
+                        },
+                    )
+                    .collect();
+            }
+        }
+    }
+}
+
+fn set_msid_semantic(session: &mut SdpSession, msids: Vec<String>) {
+    let semantic = match session.get_attribute(SdpAttributeType::MsidSemantic) {
+        Some(SdpAttribute::MsidSemantic(existing)) => existing.semantic.clone(),
+        _ => "WMS".to_string(),
+    };
+    session
+        .attribute
+        .retain(|a| SdpAttributeType::from(a) != SdpAttributeType::MsidSemantic);
+    session
+        .attribute
+        .push(SdpAttribute::MsidSemantic(SdpAttributeMsidSemantic {
+            semantic,
+            msids,
+        }));
+}
+
+fn split_plan_b_media(
+    media: &SdpMedia,
+    mids: &mut MidGenerator,
+) -> Result<Vec<SdpMedia>, SdpParserInternalError> {
+    let msids = ssrc_msids(media);
+    if msids.is_empty() {
+        return Ok(vec![media.clone()]);
+    }
+
+    let mut track_of_ssrc: Vec<(u32, String)> = media
+        .get_attributes()
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Ssrc(ssrc) => ssrc_msid(ssrc).map(|msid| (ssrc.id, msid)),
+            _ => None,
+        })
+        .collect();
+    // SSRCs without an msid of their own (e.g. some RTX or FEC streams) are
+    // assigned to the track of the ssrc-group they belong to.
+    for attr in media.get_attributes() {
+        if let SdpAttribute::SsrcGroup(_, group) = attr {
+            let track = group.iter().find_map(|s| {
+                track_of_ssrc
+                    .iter()
+                    .find(|(id, _)| *id == s.id)
+                    .map(|(_, msid)| msid.clone())
+            });
+            if let Some(track) = track {
+                for s in group {
+                    if !track_of_ssrc.iter().any(|(id, _)| *id == s.id) {
+                        track_of_ssrc.push((s.id, track.clone()));
+                    }
+                }
+            }
+        }
+    }
+    // Anything left over stays with the first track
+    let msid_of_ssrc = |id: u32| -> &String {
+        track_of_ssrc
+            .iter()
+            .find(|(ssrc_id, _)| *ssrc_id == id)
+            .map(|(_, msid)| msid)
+            .unwrap_or(&msids[0])
+    };
+
+    let mut template = media.clone();
+    for t in &[
+        SdpAttributeType::Ssrc,
+        SdpAttributeType::SsrcGroup,
+        SdpAttributeType::Msid,
+        SdpAttributeType::Mid,
+    ] {
+        template.remove_attribute(t.clone());
+    }
+
+    let mut sections = Vec::new();
+    for (index, msid) in msids.iter().enumerate() {
+        let mut section = template.clone();
+        let mid = match (index, get_mid(media)) {
+            (0, Some(mid)) => mid,
+            _ => mids.generate(),
+        };
+        section.add_attribute(SdpAttribute::Mid(mid))?;
+        section.add_attribute(SdpAttribute::Msid(msid_from_value(msid)))?;
+        for attr in media.get_attributes() {
+            let belongs = match attr {
+                SdpAttribute::Ssrc(ssrc) => msid_of_ssrc(ssrc.id) == msid,
+                SdpAttribute::SsrcGroup(_, group) => group
+                    .first()
+                    .map(|s| msid_of_ssrc(s.id) == msid)
+                    .unwrap_or(false),
+                _ => false,
+            };
+            if belongs {
+                section.add_attribute(attr.clone())?;
+            }
+        }
+        sections.push(section);
+    }
+    Ok(sections)
+}
+
+pub fn plan_b_to_unified_plan(session: &SdpSession) -> Result<SdpSession, SdpParserInternalError> {
+    let mut converted = session.clone();
+    let mut mids = MidGenerator::new(session);
+    let mut replacements = Vec::new();
+    converted.media = Vec::new();
+
+    for media in &session.media {
+        if !is_rtp_media(media) || media.get_port() == 0 {
+            converted.media.push(media.clone());
+            continue;
+        }
+        let sections = split_plan_b_media(media, &mut mids)?;
+        if let Some(old_mid) = get_mid(media) {
+            replacements.push((old_mid, sections.iter().filter_map(get_mid).collect()));
+        }
+        converted.media.extend(sections);
+    }
+
+    update_bundle_groups(&mut converted, &replacements);
+    if session
+        .get_attribute(SdpAttributeType::MsidSemantic)
+        .is_some()
+    {
+        // In Unified Plan every m-section announces its stream via a=msid
+        set_msid_semantic(&mut converted, Vec::new());
+    }
+    Ok(converted)
+}
+
+fn merge_direction(target: &mut SdpMedia, other: &SdpMedia) -> Result<(), SdpParserInternalError> {
+    let direction = |media: &SdpMedia| -> (bool, bool) {
+        if media.get_attribute(SdpAttributeType::Sendrecv).is_some() {
+            (true, true)
+        } else if media.get_attribute(SdpAttributeType::Sendonly).is_some() {
+            (true, false)
+        } else if media.get_attribute(SdpAttributeType::Recvonly).is_some() {
+            (false, true)
+        } else if media.get_attribute(SdpAttributeType::Inactive).is_some() {
+            (false, false)
+        } else {
+            // sendrecv is the default direction
+            (true, true)
+        }
+    };
+    let (target_send, target_recv) = direction(target);
+    let (other_send, other_recv) = direction(other);
+    let merged = match (target_send || other_send, target_recv || other_recv) {
+        (true, true) => SdpAttribute::Sendrecv,
+        (true, false) => SdpAttribute::Sendonly,
+        (false, true) => SdpAttribute::Recvonly,
+        (false, false) => SdpAttribute::Inactive,
+    };
+    for t in &[
+        SdpAttributeType::Sendrecv,
+        SdpAttributeType::Sendonly,
+        SdpAttributeType::Recvonly,
+        SdpAttributeType::Inactive,
+    ] {
+        target.remove_attribute(t.clone());
+    }
+    target.add_attribute(merged)
+}
+
+// Moves the a=msid of a Unified Plan m-section onto its a=ssrc lines.
+fn ssrc_attributes_with_msid(media: &SdpMedia) -> Vec<SdpAttribute> {
+    let msid = get_msid(media).map(ToString::to_string);
+    let mut attributes: Vec<SdpAttribute> = Vec::new();
+    let mut ids: Vec<u32> = Vec::new();
+    for attr in media.get_attributes() {
+        match attr {
+            SdpAttribute::Ssrc(ssrc) => {
+                if ssrc_msid(ssrc).is_some() && msid.is_some() {
+                    // Replaced by the msid taken from a=msid below
+                    continue;
+                }
+                if !ids.contains(&ssrc.id) {
+                    ids.push(ssrc.id);
+                }
+                attributes.push(attr.clone());
+            }
+            SdpAttribute::SsrcGroup(..) => attributes.push(attr.clone()),
+            _ => (),
+        }
+    }
+    if let Some(msid) = msid {
+        for id in ids {
+            let mut ssrc = SdpAttributeSsrc::new(id);
+            ssrc.attribute = Some("msid".to_string());
+            ssrc.value = Some(msid.clone());
+            attributes.push(SdpAttribute::Ssrc(ssrc));
+        }
+    }
+    attributes
+}
+
+pub fn unified_plan_to_plan_b(session: &SdpSession) -> Result<SdpSession, SdpParserInternalError> {
+    let mut converted = session.clone();
+    let mut replacements: Vec<(String, Vec<String>)> = Vec::new();
+    let mut streams: Vec<String> = Vec::new();
+    // Index into converted.media of the merge target per media type
+    let mut targets: Vec<(SdpMediaValue, usize)> = Vec::new();
+    converted.media = Vec::new();
+
+    for media in &session.media {
+        if let Some(msid) = get_msid(media) {
+            if !streams.contains(&msid.id) {
+                streams.push(msid.id.clone());
+            }
+        }
+        if !is_rtp_media(media) || media.get_port() == 0 {
+            converted.media.push(media.clone());
+            continue;
+        }
+
+        let ssrc_attributes = ssrc_attributes_with_msid(media);
+        match targets.iter().find(|(kind, _)| kind == media.get_type()) {
+            Some(&(_, index)) => {
+                let target = &mut converted.media[index];
+                merge_direction(target, media)?;
+                for attr in ssrc_attributes {
+                    target.add_attribute(attr)?;
+                }
+                if let Some(mid) = get_mid(media) {
+                    replacements.push((mid, Vec::new()));
+                }
+            }
+            None => {
+                let mut target = media.clone();
+                target.remove_attribute(SdpAttributeType::Ssrc);
+                target.remove_attribute(SdpAttributeType::SsrcGroup);
+                target.remove_attribute(SdpAttributeType::Msid);
+                for attr in ssrc_attributes {
+                    target.add_attribute(attr)?;
+                }
+                targets.push((media.get_type().clone(), converted.media.len()));
+                converted.media.push(target);
+            }
+        }
+    }
+
+    update_bundle_groups(&mut converted, &replacements);
+    if !streams.is_empty()
+        || session
+            .get_attribute(SdpAttributeType::MsidSemantic)
+            .is_some()
+    {
+        // Plan B announces all its streams at the session level
+        set_msid_semantic(&mut converted, streams);
+    }
+    Ok(converted)
+}
+
+#[cfg(test)]
+#[path = "./plan_tests.rs"]
+mod tests;
@@ -0,0 +1,144 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use parse_sdp;
+
+const PLAN_B_SDP: &str = "v=0\r\n\
+                          o=- 1 1 IN IP4 0.0.0.0\r\n\
+                          s=-\r\n\
+                          t=0 0\r\n\
+                          c=IN IP4 0.0.0.0\r\n\
+                          a=group:BUNDLE audio video\r\n\
+                          a=msid-semantic:WMS s1 s2\r\n\
+                          m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                          a=mid:audio\r\n\
+                          a=sendrecv\r\n\
+                          a=rtpmap:111 opus/48000/2\r\n\
+                          a=ssrc:1 cname:foo\r\n\
+                          a=ssrc:1 msid:s1 a1\r\n\
+                          m=video 9 UDP/TLS/RTP/SAVPF 96 97\r\n\
+                          a=mid:video\r\n\
+                          a=sendrecv\r\n\
+                          a=rtpmap:96 VP8/90000\r\n\
+                          a=rtpmap:97 rtx/90000\r\n\
+                          a=ssrc-group:FID 10 11\r\n\
+                          a=ssrc:10 cname:foo\r\n\
+                          a=ssrc:10 msid:s1 v1\r\n\
+                          a=ssrc:11 cname:foo\r\n\
+                          a=ssrc-group:FID 20 21\r\n\
+                          a=ssrc:20 cname:foo\r\n\
+                          a=ssrc:20 msid:s2 v2\r\n\
+                          a=ssrc:21 cname:foo\r\n";
+
+fn bundle_tags(session: &SdpSession) -> Vec<String> {
+    match session.get_attribute(SdpAttributeType::Group) {
+        Some(SdpAttribute::Group(group)) => group.tags.clone(),
+        _ => unreachable!(),
+    }
+}
+
+fn ssrc_ids(media: &SdpMedia) -> Vec<u32> {
+    let mut ids: Vec<u32> = Vec::new();
+    for attr in media.get_attributes() {
+        if let SdpAttribute::Ssrc(ssrc) = attr {
+            if !ids.contains(&ssrc.id) {
+                ids.push(ssrc.id);
+            }
+        }
+    }
+    ids
+}
+
+#[test]
+fn test_detect_plan() {
+    let plan_b = parse_sdp(PLAN_B_SDP, true).unwrap();
+    assert_eq!(detect_plan(&plan_b), SdpPlan::PlanB);
+
+    let unified = plan_b_to_unified_plan(&plan_b).unwrap();
+    assert_eq!(detect_plan(&unified), SdpPlan::UnifiedPlan);
+}
+
+#[test]
+fn test_plan_b_to_unified_plan() {
+    let plan_b = parse_sdp(PLAN_B_SDP, true).unwrap();
+    let unified = plan_b_to_unified_plan(&plan_b).unwrap();
+
+    assert_eq!(unified.media.len(), 3);
+    let mids: Vec<String> = unified.media.iter().filter_map(get_mid).collect();
+    assert_eq!(mids, vec!["audio", "video", "0"]);
+    assert_eq!(bundle_tags(&unified), mids);
+
+    assert_eq!(ssrc_ids(&unified.media[1]), vec![10, 11]);
+    assert_eq!(ssrc_ids(&unified.media[2]), vec![20, 21]);
+    assert_eq!(get_msid(&unified.media[2]).unwrap().to_string(), "s2 v2");
+    assert_eq!(
+        unified.media[2]
+            .get_attributes_of_type(SdpAttributeType::SsrcGroup)
+            .len(),
+        1
+    );
+    assert!(unified.media[2]
+        .get_attribute(SdpAttributeType::Rtpmap)
+        .is_some());
+
+    match unified.get_attribute(SdpAttributeType::MsidSemantic) {
+        Some(SdpAttribute::MsidSemantic(semantic)) => assert!(semantic.msids.is_empty()),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_unified_plan_to_plan_b() {
+    let plan_b = parse_sdp(PLAN_B_SDP, true).unwrap();
+    let unified = plan_b_to_unified_plan(&plan_b).unwrap();
+    let converted = unified_plan_to_plan_b(&unified).unwrap();
+
+    assert_eq!(detect_plan(&converted), SdpPlan::PlanB);
+    assert_eq!(converted.media.len(), 2);
+    assert_eq!(bundle_tags(&converted), vec!["audio", "video"]);
+    assert_eq!(ssrc_ids(&converted.media[1]), vec![10, 11, 20, 21]);
+    assert!(converted.media[1]
+        .get_attribute(SdpAttributeType::Msid)
+        .is_none());
+    assert_eq!(
+        ssrc_msids(&converted.media[1]),
+        vec!["s1 v1".to_string(), "s2 v2".to_string()]
+    );
+
+    match converted.get_attribute(SdpAttributeType::MsidSemantic) {
+        Some(SdpAttribute::MsidSemantic(semantic)) => {
+            assert_eq!(semantic.msids, vec!["s1", "s2"])
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_unified_plan_to_plan_b_merges_direction() {
+    let sdp = "v=0\r\n\
+               o=- 1 1 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               a=mid:0\r\n\
+               a=recvonly\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+               a=mid:1\r\n\
+               a=sendonly\r\n\
+               a=msid:s1 a1\r\n\
+               a=ssrc:5 cname:foo\r\n";
+    let unified = parse_sdp(sdp, true).unwrap();
+    let converted = unified_plan_to_plan_b(&unified).unwrap();
+
+    assert_eq!(converted.media.len(), 1);
+    assert!(converted.media[0]
+        .get_attribute(SdpAttributeType::Sendrecv)
+        .is_some());
+    assert!(converted.media[0]
+        .get_attribute(SdpAttributeType::Recvonly)
+        .is_none());
+    assert_eq!(ssrc_msids(&converted.media[0]), vec!["s1 a1".to_string()]);
+}
@@ -0,0 +1,176 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{
+    SdpAttribute, SdpAttributeFingerprint, SdpAttributeGroupSemantic, SdpAttributeType,
+};
+use media_type::SdpMedia;
+use SdpSession;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpMediaChange {
+    // The m-section did not exist in the previous description
+    Added { index: usize },
+    // The m-section was rejected or dropped
+    Removed { index: usize },
+    // A rejected m-section was reused, or its mid or media type changed
+    Recycled { index: usize },
+    // The effective ICE ufrag or pwd of the m-section changed
+    IceRestart { index: usize },
+    // The effective fingerprints of the m-section changed
+    FingerprintChanged { index: usize },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpSessionChanges {
+    // The session level ICE ufrag or pwd changed
+    pub ice_restart: bool,
+    // The session level fingerprints changed
+    pub fingerprint_changed: bool,
+    // The tags of the BUNDLE groups changed
+    pub bundle_changed: bool,
+    pub media: Vec<SdpMediaChange>,
+}
+
+impl SdpSessionChanges {
+    pub fn is_empty(&self) -> bool {
+        !self.ice_restart
+            && !self.fingerprint_changed
+            && !self.bundle_changed
+            && self.media.is_empty()
+    }
+
+    pub fn ice_restarted(&self, index: usize) -> bool {
+        self.media.contains(&SdpMediaChange::IceRestart { index })
+    }
+}
+
+fn string_attribute(attr: Option<&SdpAttribute>) -> Option<&String> {
+    match attr {
+        Some(SdpAttribute::IceUfrag(value)) | Some(SdpAttribute::IcePwd(value)) => Some(value),
+        Some(SdpAttribute::Mid(value)) => Some(value),
+        _ => None,
+    }
+}
+
+fn fingerprints(attributes: &[SdpAttribute]) -> Vec<&SdpAttributeFingerprint> {
+    attributes
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Fingerprint(fingerprint) => Some(fingerprint),
+            _ => None,
+        })
+        .collect()
+}
+
+fn ice_credentials<'a>(
+    session: &'a SdpSession,
+    media: &'a SdpMedia,
+) -> (Option<&'a String>, Option<&'a String>) {
+    let effective = |t: SdpAttributeType| {
+        string_attribute(media.get_attribute(t.clone()))
+            .or_else(|| string_attribute(session.get_attribute(t)))
+    };
+    (
+        effective(SdpAttributeType::IceUfrag),
+        effective(SdpAttributeType::IcePwd),
+    )
+}
+
+fn media_fingerprints<'a>(
+    session: &'a SdpSession,
+    media: &'a SdpMedia,
+) -> Vec<&'a SdpAttributeFingerprint> {
+    let media_fingerprints = fingerprints(media.get_attributes());
+    if media_fingerprints.is_empty() {
+        fingerprints(&session.attribute)
+    } else {
+        media_fingerprints
+    }
+}
+
+fn bundle_groups(session: &SdpSession) -> Vec<&Vec<String>> {
+    session
+        .attribute
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Group(group) => match group.semantics {
+                SdpAttributeGroupSemantic::Bundle => Some(&group.tags),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+// A bundle-only m-section uses port 0 without being rejected
+fn is_rejected(media: &SdpMedia) -> bool {
+    media.get_port() == 0 && media.get_attribute(SdpAttributeType::BundleOnly).is_none()
+}
+
+fn is_recycled(old: &SdpMedia, new: &SdpMedia) -> bool {
+    let old_mid = string_attribute(old.get_attribute(SdpAttributeType::Mid));
+    let new_mid = string_attribute(new.get_attribute(SdpAttributeType::Mid));
+    !is_rejected(new)
+        && (is_rejected(old)
+            || old.get_type() != new.get_type()
+            || (old_mid.is_some() && new_mid.is_some() && old_mid != new_mid))
+}
+
+/*
+ * Compares a previous and a new description of the same session, e.g. the
+ * current remote description and a renegotiation offer. The credentials and
+ * fingerprints of an m-section are the effective ones, i.e. inherited from
+ * the session level if the m-section does not have its own.
+ */
+pub fn compare_sessions(old: &SdpSession, new: &SdpSession) -> SdpSessionChanges {
+    let mut changes = SdpSessionChanges {
+        ice_restart: string_attribute(old.get_attribute(SdpAttributeType::IceUfrag))
+            != string_attribute(new.get_attribute(SdpAttributeType::IceUfrag))
+            || string_attribute(old.get_attribute(SdpAttributeType::IcePwd))
+                != string_attribute(new.get_attribute(SdpAttributeType::IcePwd)),
+        fingerprint_changed: fingerprints(&old.attribute) != fingerprints(&new.attribute),
+        bundle_changed: bundle_groups(old) != bundle_groups(new),
+        media: Vec::new(),
+    };
+
+    for index in 0..old.media.len().max(new.media.len()) {
+        let (old_media, new_media) = match (old.media.get(index), new.media.get(index)) {
+            (Some(old_media), Some(new_media)) => (old_media, new_media),
+            (None, Some(_)) => {
+                changes.media.push(SdpMediaChange::Added { index });
+                continue;
+            }
+            _ => {
+                changes.media.push(SdpMediaChange::Removed { index });
+                continue;
+            }
+        };
+        if is_recycled(old_media, new_media) {
+            changes.media.push(SdpMediaChange::Recycled { index });
+            continue;
+        }
+        if is_rejected(new_media) {
+            if !is_rejected(old_media) {
+                changes.media.push(SdpMediaChange::Removed { index });
+            }
+            continue;
+        }
+        if ice_credentials(old, old_media) != ice_credentials(new, new_media) {
+            changes.media.push(SdpMediaChange::IceRestart { index });
+        }
+        if media_fingerprints(old, old_media) != media_fingerprints(new, new_media) {
+            changes
+                .media
+                .push(SdpMediaChange::FingerprintChanged { index });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+#[path = "./renegotiation_tests.rs"]
+mod tests;
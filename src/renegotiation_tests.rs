@@ -0,0 +1,124 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use tests::parse_dummy_sdp_session;
+
+const FINGERPRINT_A: &str = "sha-256 \
+    CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC:BF:2F:E3:91:CB:57:A9:9D:4A:A2:0B:40";
+const FINGERPRINT_B: &str = "sha-256 \
+    DD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC:BF:2F:E3:91:CB:57:A9:9D:4A:A2:0B:40";
+
+fn create_session(session_attributes: &str, media: &str) -> SdpSession {
+    parse_dummy_sdp_session(&format!("{}{}", session_attributes, media))
+}
+
+const MEDIA: &str = "m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                     a=mid:0\r\n\
+                     m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                     a=mid:1\r\n";
+
+#[test]
+fn test_compare_identical_sessions() {
+    let session_attributes = format!(
+        "a=ice-ufrag:foo\r\na=ice-pwd:bar\r\na=fingerprint:{}\r\na=group:BUNDLE 0 1\r\n",
+        FINGERPRINT_A
+    );
+    let old = create_session(&session_attributes, MEDIA);
+    let new = create_session(&session_attributes, MEDIA);
+    assert!(compare_sessions(&old, &new).is_empty());
+}
+
+#[test]
+fn test_compare_session_level_changes() {
+    let old = create_session(
+        &format!(
+            "a=ice-ufrag:foo\r\na=ice-pwd:bar\r\na=fingerprint:{}\r\na=group:BUNDLE 0 1\r\n",
+            FINGERPRINT_A
+        ),
+        MEDIA,
+    );
+    let new = create_session(
+        &format!(
+            "a=ice-ufrag:baz\r\na=ice-pwd:bar\r\na=fingerprint:{}\r\na=group:BUNDLE 0\r\n",
+            FINGERPRINT_B
+        ),
+        MEDIA,
+    );
+    let changes = compare_sessions(&old, &new);
+
+    assert!(changes.ice_restart);
+    assert!(changes.fingerprint_changed);
+    assert!(changes.bundle_changed);
+    // The m-sections inherit the session level values
+    assert!(changes.ice_restarted(0));
+    assert!(changes.ice_restarted(1));
+    assert!(changes
+        .media
+        .contains(&SdpMediaChange::FingerprintChanged { index: 1 }));
+}
+
+#[test]
+fn test_compare_media_level_changes() {
+    let old = create_session(
+        "a=ice-ufrag:foo\r\na=ice-pwd:bar\r\n",
+        "m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:0\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+         a=mid:1\r\n\
+         a=ice-pwd:other\r\n\
+         m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+         a=mid:2\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:3\r\n",
+    );
+    let new = create_session(
+        "a=ice-ufrag:foo\r\na=ice-pwd:bar\r\n",
+        "m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:0\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+         a=mid:1\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:4\r\n\
+         m=audio 0 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:3\r\n\
+         m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+         a=mid:5\r\n",
+    );
+    let changes = compare_sessions(&old, &new);
+
+    assert!(!changes.ice_restart);
+    assert!(!changes.bundle_changed);
+    assert_eq!(
+        changes.media,
+        vec![
+            SdpMediaChange::IceRestart { index: 1 },
+            SdpMediaChange::Recycled { index: 2 },
+            SdpMediaChange::Removed { index: 3 },
+            SdpMediaChange::Added { index: 4 },
+        ]
+    );
+}
+
+#[test]
+fn test_compare_bundle_only_media() {
+    let session_attributes = "a=ice-ufrag:foo\r\na=ice-pwd:bar\r\na=group:BUNDLE 0 1\r\n";
+    let bundle_only = "m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                       a=mid:0\r\n\
+                       m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+                       a=bundle-only\r\n\
+                       a=mid:1\r\n";
+    let old = create_session(session_attributes, MEDIA);
+    let new = create_session(session_attributes, bundle_only);
+    assert!(compare_sessions(&old, &new).is_empty());
+    assert!(compare_sessions(&new, &old).is_empty());
+    assert!(compare_sessions(&new, &new).is_empty());
+
+    let rejected = "m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                    a=mid:0\r\n\
+                    m=video 0 UDP/TLS/RTP/SAVPF 96\r\n\
+                    a=mid:1\r\n";
+    let changes = compare_sessions(&new, &create_session(session_attributes, rejected));
+    assert_eq!(changes.media, vec![SdpMediaChange::Removed { index: 1 }]);
+}
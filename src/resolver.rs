@@ -0,0 +1,267 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use address::{Address, AddressType, AddressTyped, ExplicitlyTypedAddress};
+use attribute_type::{
+    SdpAttribute, SdpAttributeCandidate, SdpAttributeRemoteCandidate, SdpAttributeRtcp,
+};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use {SdpConnection, SdpSession};
+
+/*
+ * Resolves the domain names of connection, origin, rtcp and candidate
+ * addresses, e.g. with DNS or mDNS. The crate picks the first address of
+ * the type declared next to the name, if there is one.
+ */
+pub trait SdpAddressResolver {
+    // Returns the addresses of name, an empty list if it is unknown
+    fn resolve(&self, name: &str) -> Result<Vec<IpAddr>, SdpParserInternalError>;
+}
+
+// A fixed table of names, like an /etc/hosts file
+#[derive(Clone, Debug, Default)]
+pub struct SdpStaticHostsResolver {
+    hosts: HashMap<String, Vec<IpAddr>>,
+}
+
+impl SdpStaticHostsResolver {
+    pub fn new() -> SdpStaticHostsResolver {
+        SdpStaticHostsResolver {
+            hosts: HashMap::new(),
+        }
+    }
+
+    // Names are case insensitive, a name can have several addresses
+    pub fn add_host(&mut self, name: &str, ip: IpAddr) {
+        self.hosts.entry(name.to_lowercase()).or_default().push(ip);
+    }
+}
+
+impl SdpAddressResolver for SdpStaticHostsResolver {
+    fn resolve(&self, name: &str) -> Result<Vec<IpAddr>, SdpParserInternalError> {
+        Ok(self
+            .hosts
+            .get(&name.to_lowercase())
+            .cloned()
+            .unwrap_or_default())
+    }
+}
+
+// The addresses the names of a session resolved to
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SdpResolvedAddresses {
+    addresses: HashMap<(String, Option<AddressType>), IpAddr>,
+    // The names which did not resolve, in the order they appear in
+    unresolved: Vec<(String, Option<AddressType>)>,
+}
+
+impl SdpResolvedAddresses {
+    pub fn get(&self, name: &str, address_type: Option<AddressType>) -> Option<IpAddr> {
+        self.addresses
+            .get(&(name.to_lowercase(), address_type))
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    pub fn unresolved(&self) -> &[(String, Option<AddressType>)] {
+        &self.unresolved
+    }
+
+    // An UnresolvedAddress error for every name which did not resolve
+    pub fn unresolved_errors(&self) -> Vec<SdpParserInternalError> {
+        self.unresolved
+            .iter()
+            .map(|(name, address_type)| {
+                let message = match address_type {
+                    Some(t) => format!("{} does not resolve to an {} address", name, t),
+                    None => format!("{} does not resolve to an address", name),
+                };
+                SdpParserInternalError::Generic(message)
+                    .with_kind(SdpParserErrorKind::UnresolvedAddress)
+            })
+            .collect()
+    }
+
+    pub fn resolve_address(&self, address: &Address) -> Address {
+        match address {
+            Address::Fqdn(name) => match self.get(name, None) {
+                Some(ip) => Address::Ip(ip),
+                None => address.clone(),
+            },
+            Address::Ip(_) => address.clone(),
+        }
+    }
+
+    pub fn resolve_typed_address(
+        &self,
+        address: &ExplicitlyTypedAddress,
+    ) -> ExplicitlyTypedAddress {
+        match address {
+            ExplicitlyTypedAddress::Fqdn {
+                address_type,
+                domain,
+            } => match self.get(domain, Some(*address_type)) {
+                Some(ip) => ExplicitlyTypedAddress::Ip(ip),
+                None => address.clone(),
+            },
+            ExplicitlyTypedAddress::Ip(_) => address.clone(),
+        }
+    }
+
+    // Replaces all the names in session which are in the table
+    pub fn apply(&self, session: &mut SdpSession) -> Result<(), SdpParserInternalError> {
+        session.origin.unicast_addr = self.resolve_typed_address(&session.origin.unicast_addr);
+        if let Some(ref mut connection) = session.connection {
+            connection.address = self.resolve_typed_address(&connection.address);
+        }
+        for media in &mut session.media {
+            if let Some(connection) = media.get_connection().clone() {
+                let address = self.resolve_typed_address(&connection.address);
+                media.set_connection(SdpConnection {
+                    address,
+                    ..connection
+                });
+            }
+            media.map_attributes(|attribute| match attribute {
+                SdpAttribute::Candidate(candidate) => {
+                    SdpAttribute::Candidate(SdpAttributeCandidate {
+                        address: self.resolve_address(&candidate.address),
+                        raddr: candidate.raddr.as_ref().map(|a| self.resolve_address(a)),
+                        ..candidate.clone()
+                    })
+                }
+                SdpAttribute::RemoteCandidate(candidate) => {
+                    SdpAttribute::RemoteCandidate(SdpAttributeRemoteCandidate {
+                        address: self.resolve_address(&candidate.address),
+                        ..candidate.clone()
+                    })
+                }
+                SdpAttribute::Rtcp(rtcp) => SdpAttribute::Rtcp(SdpAttributeRtcp {
+                    unicast_addr: rtcp
+                        .unicast_addr
+                        .as_ref()
+                        .map(|a| self.resolve_typed_address(a)),
+                    ..rtcp.clone()
+                }),
+                _ => attribute.clone(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn typed_name(address: &ExplicitlyTypedAddress) -> Option<(&String, Option<AddressType>)> {
+    match address {
+        ExplicitlyTypedAddress::Fqdn {
+            address_type,
+            domain,
+        } => Some((domain, Some(*address_type))),
+        ExplicitlyTypedAddress::Ip(_) => None,
+    }
+}
+
+fn untyped_name(address: &Address) -> Option<(&String, Option<AddressType>)> {
+    match address {
+        Address::Fqdn(name) => Some((name, None)),
+        Address::Ip(_) => None,
+    }
+}
+
+// The names in session and the address type declared for them
+fn session_names(session: &SdpSession) -> Vec<(&String, Option<AddressType>)> {
+    let mut names = Vec::new();
+    names.extend(typed_name(&session.origin.unicast_addr));
+    if let Some(ref connection) = session.connection {
+        names.extend(typed_name(&connection.address));
+    }
+    for media in &session.media {
+        if let Some(connection) = media.get_connection() {
+            names.extend(typed_name(&connection.address));
+        }
+        for attribute in media.get_attributes() {
+            match attribute {
+                SdpAttribute::Candidate(candidate) => {
+                    names.extend(untyped_name(&candidate.address));
+                    names.extend(candidate.raddr.as_ref().and_then(untyped_name));
+                }
+                SdpAttribute::RemoteCandidate(candidate) => {
+                    names.extend(untyped_name(&candidate.address));
+                }
+                SdpAttribute::Rtcp(rtcp) => {
+                    names.extend(rtcp.unicast_addr.as_ref().and_then(typed_name));
+                }
+                _ => (),
+            }
+        }
+    }
+    names
+}
+
+// Failures of the resolver are returned, unlike names it does not know
+fn resolve_name<R: SdpAddressResolver + ?Sized>(
+    resolver: &R,
+    name: &str,
+    address_type: Option<AddressType>,
+) -> Result<Option<IpAddr>, SdpParserInternalError> {
+    Ok(resolver
+        .resolve(name)?
+        .into_iter()
+        .find(|ip| match address_type {
+            Some(t) => ip.address_type() == t,
+            None => true,
+        }))
+}
+
+/*
+ * Resolves all the names in session into a side table, leaving the session
+ * untouched. Names with an IP4 or IP6 address type only resolve to an
+ * address of that type. Names which do not resolve, e.g. mDNS names of
+ * another network, are listed as unresolved and kept in the session. A
+ * failing lookup aborts the resolution with the error of the resolver.
+ */
+pub fn resolve_session_addresses<R: SdpAddressResolver + ?Sized>(
+    session: &SdpSession,
+    resolver: &R,
+) -> Result<SdpResolvedAddresses, SdpParserInternalError> {
+    let mut resolved = SdpResolvedAddresses::default();
+    for (name, address_type) in session_names(session) {
+        let key = (name.to_lowercase(), address_type);
+        if resolved.unresolved.contains(&key) {
+            continue;
+        }
+        if let Entry::Vacant(entry) = resolved.addresses.entry(key) {
+            match resolve_name(resolver, name, address_type)? {
+                Some(ip) => {
+                    entry.insert(ip);
+                }
+                None => resolved.unresolved.push(entry.into_key()),
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+// Replaces all the names in session with the addresses they resolve to
+pub fn resolve_session_addresses_in_place<R: SdpAddressResolver + ?Sized>(
+    session: &mut SdpSession,
+    resolver: &R,
+) -> Result<SdpResolvedAddresses, SdpParserInternalError> {
+    let resolved = resolve_session_addresses(session, resolver)?;
+    resolved.apply(session)?;
+    Ok(resolved)
+}
+
+#[cfg(test)]
+#[path = "./resolver_tests.rs"]
+mod tests;
@@ -0,0 +1,146 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use parse_sdp;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const SDP: &str = "v=0\r\n\
+                   o=- 0 0 IN IP4 origin.example.com\r\n\
+                   s=-\r\n\
+                   c=IN IP4 media.example.com\r\n\
+                   t=0 0\r\n\
+                   m=audio 5000 RTP/AVP 0\r\n\
+                   c=IN IP6 Media.Example.com\r\n\
+                   a=rtcp:5001 IN IP4 media.example.com\r\n\
+                   a=candidate:1 1 UDP 2122252543 1f4712db-ea17-4bcf-a596-105139dfd8bf.local 5000 typ host\r\n\
+                   a=candidate:2 1 UDP 1686052607 192.0.2.1 5002 typ srflx raddr 1f4712db-ea17-4bcf-a596-105139dfd8bf.local rport 5000\r\n";
+
+fn v4(d: u8) -> IpAddr {
+    IpAddr::V4(Ipv4Addr::new(192, 0, 2, d))
+}
+
+fn v6(d: u16) -> IpAddr {
+    IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, d))
+}
+
+fn hosts() -> SdpStaticHostsResolver {
+    let mut resolver = SdpStaticHostsResolver::new();
+    resolver.add_host("origin.example.com", v4(10));
+    resolver.add_host("media.example.com", v4(20));
+    resolver.add_host("MEDIA.example.com", v6(20));
+    resolver.add_host("1f4712db-ea17-4bcf-a596-105139dfd8bf.local", v4(30));
+    resolver
+}
+
+#[test]
+fn test_resolve_session_addresses() {
+    let session = parse_sdp(SDP, true).unwrap();
+    let resolved = resolve_session_addresses(&session, &hosts()).unwrap();
+    assert_eq!(resolved.len(), 4);
+    assert_eq!(
+        resolved.get("media.example.com", Some(AddressType::IpV4)),
+        Some(v4(20))
+    );
+    assert_eq!(
+        resolved.get("media.example.com", Some(AddressType::IpV6)),
+        Some(v6(20))
+    );
+    assert_eq!(
+        resolved.get("1f4712db-ea17-4bcf-a596-105139dfd8bf.local", None),
+        Some(v4(30))
+    );
+    // The session is left untouched
+    assert_eq!(
+        session.to_string(),
+        parse_sdp(SDP, true).unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_resolve_session_addresses_in_place() {
+    let mut session = parse_sdp(SDP, true).unwrap();
+    resolve_session_addresses_in_place(&mut session, &hosts()).unwrap();
+    let sdp = session.to_string();
+    assert!(sdp.contains("o=- 0 0 IN IP4 192.0.2.10\r\n"));
+    assert!(sdp.contains("c=IN IP4 192.0.2.20\r\n"));
+    assert!(sdp.contains("c=IN IP6 2001:db8::14\r\n"));
+    assert!(sdp.contains("a=rtcp:5001 IN IP4 192.0.2.20\r\n"));
+    assert!(sdp.contains("a=candidate:1 1 UDP 2122252543 192.0.2.30 5000 typ host\r\n"));
+    assert!(sdp.contains("raddr 192.0.2.30 rport 5000\r\n"));
+    assert!(parse_sdp(&sdp, true).is_ok());
+}
+
+#[test]
+fn test_resolve_honors_address_type() {
+    let session = parse_sdp(SDP, true).unwrap();
+    let mut resolver = SdpStaticHostsResolver::new();
+    resolver.add_host("origin.example.com", v4(10));
+    resolver.add_host("media.example.com", v4(20));
+    resolver.add_host("1f4712db-ea17-4bcf-a596-105139dfd8bf.local", v6(30));
+
+    let resolved = resolve_session_addresses(&session, &resolver).unwrap();
+    assert_eq!(
+        resolved.unresolved(),
+        &[("media.example.com".to_string(), Some(AddressType::IpV6))]
+    );
+    let errors = resolved.unresolved_errors();
+    assert_eq!(errors[0].kind(), SdpParserErrorKind::UnresolvedAddress);
+    assert_eq!(
+        errors[0].to_string(),
+        "Parsing error: media.example.com does not resolve to an IP6 address"
+    );
+}
+
+#[test]
+fn test_resolve_keeps_unresolved_names() {
+    let mut session = parse_sdp(SDP, true).unwrap();
+    let mut resolver = SdpStaticHostsResolver::new();
+    resolver.add_host("origin.example.com", v4(10));
+    resolver.add_host("media.example.com", v4(20));
+    resolver.add_host("media.example.com", v6(20));
+
+    let resolved = resolve_session_addresses_in_place(&mut session, &resolver).unwrap();
+    assert_eq!(resolved.len(), 3);
+    assert_eq!(
+        resolved.unresolved(),
+        &[(
+            "1f4712db-ea17-4bcf-a596-105139dfd8bf.local".to_string(),
+            None
+        )]
+    );
+    let sdp = session.to_string();
+    assert!(sdp.contains("c=IN IP4 192.0.2.20\r\n"));
+    assert!(sdp.contains("1f4712db-ea17-4bcf-a596-105139dfd8bf.local 5000 typ host\r\n"));
+
+    let resolved = resolve_session_addresses(&session, &SdpStaticHostsResolver::new()).unwrap();
+    assert!(resolved.is_empty());
+    assert_eq!(resolved.unresolved().len(), 1);
+}
+
+struct FailingResolver;
+
+impl SdpAddressResolver for FailingResolver {
+    fn resolve(&self, name: &str) -> Result<Vec<IpAddr>, SdpParserInternalError> {
+        Err(SdpParserInternalError::Generic(format!(
+            "lookup of {} timed out",
+            name
+        )))
+    }
+}
+
+#[test]
+fn test_resolver_failures_are_returned() {
+    let mut session = parse_sdp(SDP, true).unwrap();
+    let error = resolve_session_addresses(&session, &FailingResolver).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Parsing error: lookup of origin.example.com timed out"
+    );
+    assert!(resolve_session_addresses_in_place(&mut session, &FailingResolver).is_err());
+    assert_eq!(
+        session.to_string(),
+        parse_sdp(SDP, true).unwrap().to_string()
+    );
+}
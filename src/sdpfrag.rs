@@ -0,0 +1,291 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{SdpAttribute, SdpAttributeType};
+use error::{SdpParserError, SdpParserErrorKind, SdpParserInternalError};
+use media_type::SdpMedia;
+use std::fmt;
+use {parse_sdp_line, SdpSession, SdpType};
+
+/*
+ * RFC 8840
+ * An application/trickle-ice-sdpfrag body. It only carries ICE related
+ * attributes, at the session level and in m-sections identified by their
+ * a=mid, without the v, o, s and t lines of a complete description.
+ */
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpFragment {
+    pub attribute: Vec<SdpAttribute>,
+    pub media: Vec<SdpMedia>,
+    pub warnings: Vec<SdpParserError>,
+}
+
+impl fmt::Display for SdpFragment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{session_attributes}{media_sections}",
+            session_attributes = maybe_vector_to_string!("a={}\r\n", self.attribute, "\r\na="),
+            media_sections = self.media.iter().map(|s| s.to_string()).collect::<String>(),
+        )
+    }
+}
+
+fn get_mid(media: &SdpMedia) -> Option<&String> {
+    match media.get_attribute(SdpAttributeType::Mid) {
+        Some(SdpAttribute::Mid(mid)) => Some(mid),
+        _ => None,
+    }
+}
+
+/*
+ * RFC 8840 section 9
+ * The attributes a fragment may carry: the ICE attributes, the mid of each
+ * m-section and the BUNDLE group.
+ */
+fn is_fragment_attribute(attribute: &SdpAttribute) -> bool {
+    matches!(
+        SdpAttributeType::from(attribute),
+        SdpAttributeType::Candidate
+            | SdpAttributeType::EndOfCandidates
+            | SdpAttributeType::Group
+            | SdpAttributeType::IceLite
+            | SdpAttributeType::IceMismatch
+            | SdpAttributeType::IceOptions
+            | SdpAttributeType::IcePacing
+            | SdpAttributeType::IcePwd
+            | SdpAttributeType::IceUfrag
+            | SdpAttributeType::Mid
+            | SdpAttributeType::RemoteCandidate
+    )
+}
+
+fn get_ufrag(attribute: Option<&SdpAttribute>) -> Option<&String> {
+    match attribute {
+        Some(SdpAttribute::IceUfrag(ufrag)) => Some(ufrag),
+        _ => None,
+    }
+}
+
+impl SdpFragment {
+    pub fn new() -> SdpFragment {
+        SdpFragment {
+            attribute: Vec::new(),
+            media: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn get_attribute(&self, t: SdpAttributeType) -> Option<&SdpAttribute> {
+        self.attribute
+            .iter()
+            .find(|a| SdpAttributeType::from(*a) == t)
+    }
+
+    // The m-section of the fragment for the given mid
+    pub fn get_media(&self, mid: &str) -> Option<&SdpMedia> {
+        self.media
+            .iter()
+            .find(|media| get_mid(media).map(String::as_str) == Some(mid))
+    }
+}
+
+impl Default for SdpFragment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn parse_sdp_fragment(
+    fragment: &str,
+    fail_on_warning: bool,
+) -> Result<SdpFragment, SdpParserError> {
+    let mut errors: Vec<SdpParserError> = Vec::new();
+    let mut warnings: Vec<SdpParserError> = Vec::new();
+    let mut sdp_fragment = SdpFragment::new();
+    // The line number of the m= line of each m-section, for errors
+    let mut media_lines: Vec<usize> = Vec::new();
+
+    for (line_number, line) in fragment.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let sdp_line = match parse_sdp_line(line, line_number) {
+            Ok(sdp_line) => sdp_line,
+            Err(warning @ SdpParserError::Unsupported { .. }) => {
+                warnings.push(warning);
+                continue;
+            }
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        let result = match sdp_line.sdp_type {
+            SdpType::Media(media_line) => {
+                sdp_fragment.media.push(SdpMedia::new(media_line));
+                media_lines.push(line_number);
+                Ok(())
+            }
+            SdpType::Attribute(attribute) if !is_fragment_attribute(&attribute) => {
+                Err(SdpParserInternalError::Generic(format!(
+                    "{} is not an ICE attribute and not allowed in sdpfrag",
+                    attribute
+                ))
+                .with_kind(SdpParserErrorKind::FragmentInvalidLine))
+            }
+            SdpType::Attribute(attribute) => match sdp_fragment.media.last_mut() {
+                Some(media) => media.add_attribute(attribute),
+                None => {
+                    if attribute.allowed_at_session_level() {
+                        sdp_fragment.attribute.push(attribute);
+                        Ok(())
+                    } else {
+                        Err(SdpParserInternalError::Generic(format!(
+                            "{} not allowed at session level",
+                            attribute
+                        ))
+                        .with_kind(SdpParserErrorKind::AttributeNotAllowedAtSessionLevel))
+                    }
+                }
+            },
+            _ => Err(SdpParserInternalError::Generic(
+                "sdpfrag may only contain media and attribute lines".to_string(),
+            )
+            .with_kind(SdpParserErrorKind::FragmentInvalidLine)),
+        };
+        if let Err(e) = result {
+            errors.push(SdpParserError::Sequence {
+                message: format!("{}", e),
+                line_number,
+                line_numbers: vec![line_number],
+                kind: e.kind(),
+            });
+        }
+    }
+
+    if fail_on_warning && (!warnings.is_empty()) {
+        return Err(warnings.remove(0));
+    }
+
+    // We just return the last of the errors here
+    if let Some(e) = errors.pop() {
+        return Err(e);
+    };
+
+    // RFC 8840 section 9: m-sections are identified by their mid
+    if let Some(i) = sdp_fragment
+        .media
+        .iter()
+        .position(|media| get_mid(media).is_none())
+    {
+        return Err(SdpParserError::Sequence {
+            message: "media sections in sdpfrag must have a mid".to_string(),
+            line_number: media_lines[i],
+            line_numbers: vec![media_lines[i]],
+            kind: SdpParserErrorKind::FragmentMissingMid,
+        });
+    }
+
+    for warning in &warnings {
+        warn!("Warning: {}", &warning);
+    }
+    sdp_fragment.warnings = warnings;
+
+    Ok(sdp_fragment)
+}
+
+/*
+ * RFC 8840 section 4.4
+ * Adds the candidates and end-of-candidates indications of the fragment to
+ * the m-sections of session with the same mid. A session level
+ * end-of-candidates applies to all m-sections. Fragments of another ICE
+ * session, i.e. with a different ufrag, are rejected. Candidates already
+ * known are not added again.
+ */
+pub fn merge_sdp_fragment(
+    session: &mut SdpSession,
+    fragment: &SdpFragment,
+) -> Result<(), SdpParserInternalError> {
+    let fragment_ufrag = get_ufrag(fragment.get_attribute(SdpAttributeType::IceUfrag));
+    let session_ufrag = get_ufrag(session.get_attribute(SdpAttributeType::IceUfrag)).cloned();
+
+    if let (Some(current), Some(ufrag)) = (session_ufrag.as_ref(), fragment_ufrag) {
+        if current != ufrag {
+            return Err(SdpParserInternalError::Generic(format!(
+                "sdpfrag ufrag {} does not match the session ice-ufrag {}",
+                ufrag, current
+            ))
+            .with_kind(SdpParserErrorKind::FragmentUfragMismatch));
+        }
+    }
+
+    let mut indices = Vec::new();
+    for fragment_media in &fragment.media {
+        let mid = get_mid(fragment_media).ok_or_else(|| {
+            SdpParserInternalError::Generic("media sections in sdpfrag must have a mid".to_string())
+                .with_kind(SdpParserErrorKind::FragmentMissingMid)
+        })?;
+        let index = session
+            .media
+            .iter()
+            .position(|media| get_mid(media) == Some(mid))
+            .ok_or_else(|| {
+                SdpParserInternalError::Generic(format!("there is no m-section with mid {}", mid))
+                    .with_kind(SdpParserErrorKind::FragmentUnknownMid)
+            })?;
+        let media = &session.media[index];
+        let current =
+            get_ufrag(media.get_attribute(SdpAttributeType::IceUfrag)).or(session_ufrag.as_ref());
+        let ufrag =
+            get_ufrag(fragment_media.get_attribute(SdpAttributeType::IceUfrag)).or(fragment_ufrag);
+        if let (Some(current), Some(ufrag)) = (current, ufrag) {
+            if current != ufrag {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "sdpfrag ufrag {} does not match the ice-ufrag {} of mid {}",
+                    ufrag, current, mid
+                ))
+                .with_kind(SdpParserErrorKind::FragmentUfragMismatch));
+            }
+        }
+        indices.push(index);
+    }
+
+    for (fragment_media, index) in fragment.media.iter().zip(indices) {
+        let media = &mut session.media[index];
+        for attribute in fragment_media.get_attributes() {
+            let known = match *attribute {
+                SdpAttribute::Candidate(_) | SdpAttribute::EndOfCandidates => media
+                    .get_attributes()
+                    .iter()
+                    .any(|a| a.to_string() == attribute.to_string()),
+                _ => continue,
+            };
+            if !known {
+                media.add_attribute(attribute.clone())?;
+            }
+        }
+    }
+
+    if fragment
+        .get_attribute(SdpAttributeType::EndOfCandidates)
+        .is_some()
+    {
+        for media in &mut session.media {
+            if media
+                .get_attribute(SdpAttributeType::EndOfCandidates)
+                .is_none()
+            {
+                media.add_attribute(SdpAttribute::EndOfCandidates)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./sdpfrag_tests.rs"]
+mod tests;
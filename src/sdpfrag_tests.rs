@@ -0,0 +1,145 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use tests::parse_dummy_sdp_session;
+
+const FRAGMENT: &str = "a=ice-ufrag:foo\r\n\
+                        a=ice-pwd:bar\r\n\
+                        m=audio 9 RTP/AVP 0\r\n\
+                        a=mid:0\r\n\
+                        a=candidate:1 1 UDP 2122252543 192.0.2.1 50000 typ host\r\n\
+                        a=end-of-candidates\r\n\
+                        m=video 9 RTP/AVP 96\r\n\
+                        a=mid:1\r\n\
+                        a=candidate:2 1 UDP 2122252543 192.0.2.1 50002 typ host\r\n";
+
+fn create_session(ufrag: &str) -> SdpSession {
+    parse_dummy_sdp_session(&format!(
+        "a=ice-ufrag:{}\r\n\
+         a=ice-pwd:bar\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 0\r\n\
+         a=mid:0\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+         a=mid:1\r\n",
+        ufrag
+    ))
+}
+
+#[test]
+fn test_parse_sdp_fragment() {
+    let fragment = parse_sdp_fragment(FRAGMENT, true).unwrap();
+    assert_eq!(fragment.attribute.len(), 2);
+    assert_eq!(fragment.media.len(), 2);
+    let media = fragment.get_media("0").unwrap();
+    assert!(media
+        .get_attribute(SdpAttributeType::EndOfCandidates)
+        .is_some());
+    assert!(fragment.get_media("2").is_none());
+    assert_eq!(fragment.to_string(), FRAGMENT);
+
+    // A fragment may consist of a single line
+    let fragment = parse_sdp_fragment("a=end-of-candidates\r\n", true).unwrap();
+    assert!(fragment.media.is_empty());
+    assert_eq!(fragment.to_string(), "a=end-of-candidates\r\n");
+}
+
+#[test]
+fn test_parse_sdp_fragment_errors() {
+    let error = parse_sdp_fragment("v=0\r\na=ice-ufrag:foo\r\n", true).unwrap_err();
+    assert_eq!(error.kind(), SdpParserErrorKind::FragmentInvalidLine);
+
+    let error = parse_sdp_fragment("m=audio 9 RTP/AVP 0\r\na=ice-ufrag:foo\r\n", true).unwrap_err();
+    assert_eq!(error.kind(), SdpParserErrorKind::FragmentMissingMid);
+
+    let error = parse_sdp_fragment(
+        "a=candidate:1 1 UDP 2122252543 192.0.2.1 50000 typ host\r\n",
+        true,
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.kind(),
+        SdpParserErrorKind::AttributeNotAllowedAtSessionLevel
+    );
+
+    // Only ICE attributes are allowed, at the session and the media level
+    let error = parse_sdp_fragment("a=ice-ufrag:foo\r\na=sendrecv\r\n", true).unwrap_err();
+    assert_eq!(error.kind(), SdpParserErrorKind::FragmentInvalidLine);
+    let error = parse_sdp_fragment(
+        "m=audio 9 RTP/AVP 0\r\na=mid:0\r\na=rtpmap:0 PCMU/8000\r\n",
+        true,
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), SdpParserErrorKind::FragmentInvalidLine);
+
+    let fragment = "a=ice-ufrag:foo\r\na=unknown-attribute\r\n";
+    assert!(parse_sdp_fragment(fragment, true).is_err());
+    assert_eq!(
+        parse_sdp_fragment(fragment, false).unwrap().warnings.len(),
+        1
+    );
+}
+
+#[test]
+fn test_merge_sdp_fragment() {
+    let mut session = create_session("foo");
+    let fragment = parse_sdp_fragment(FRAGMENT, true).unwrap();
+    merge_sdp_fragment(&mut session, &fragment).unwrap();
+    // Merging the same fragment again does not duplicate candidates
+    merge_sdp_fragment(&mut session, &fragment).unwrap();
+
+    let count =
+        |index: usize, t: SdpAttributeType| session.media[index].get_attributes_of_type(t).len();
+    assert_eq!(count(0, SdpAttributeType::Candidate), 1);
+    assert_eq!(count(0, SdpAttributeType::EndOfCandidates), 1);
+    assert_eq!(count(1, SdpAttributeType::Candidate), 1);
+    assert_eq!(count(1, SdpAttributeType::EndOfCandidates), 0);
+    // The ICE credentials are not copied
+    assert_eq!(count(0, SdpAttributeType::IceUfrag), 0);
+
+    let fragment = parse_sdp_fragment("a=ice-ufrag:foo\r\na=end-of-candidates\r\n", true).unwrap();
+    merge_sdp_fragment(&mut session, &fragment).unwrap();
+    assert_eq!(
+        session.media[1]
+            .get_attributes_of_type(SdpAttributeType::EndOfCandidates)
+            .len(),
+        1
+    );
+
+    let mut session = create_session("restarted");
+    let fragment = parse_sdp_fragment(FRAGMENT, true).unwrap();
+    assert_eq!(
+        merge_sdp_fragment(&mut session, &fragment)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::FragmentUfragMismatch
+    );
+    assert!(session.media[0]
+        .get_attributes_of_type(SdpAttributeType::Candidate)
+        .is_empty());
+
+    let mut session = create_session("foo");
+    let fragment = parse_sdp_fragment("m=audio 9 RTP/AVP 0\r\na=mid:2\r\n", true).unwrap();
+    assert_eq!(
+        merge_sdp_fragment(&mut session, &fragment)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::FragmentUnknownMid
+    );
+}
+
+#[test]
+fn test_merge_sdp_fragment_after_restart() {
+    let mut session = create_session("restarted");
+    let fragment = parse_sdp_fragment("a=ice-ufrag:foo\r\na=end-of-candidates\r\n", true).unwrap();
+    assert_eq!(
+        merge_sdp_fragment(&mut session, &fragment)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::FragmentUfragMismatch
+    );
+    assert!(session.media[0]
+        .get_attribute(SdpAttributeType::EndOfCandidates)
+        .is_none());
+}
@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{
+    SdpAttribute, SdpAttributeRid, SdpAttributeRidParameters, SdpAttributeSimulcast,
+    SdpAttributeSimulcastId, SdpAttributeSimulcastVersion, SdpAttributeType, SdpSingleDirection,
+};
+use error::SdpParserInternalError;
+use media_type::SdpMedia;
+
+/*
+ * A single simulcast stream of an m-section as described by a=simulcast
+ * (RFC 8853) together with the restrictions of its a=rid (RFC 8851). The
+ * first alternative of a simulcast version is the layer's rid, the others
+ * are kept in alternatives.
+ */
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpSimulcastLayer {
+    pub rid: String,
+    pub direction: SdpSingleDirection,
+    pub paused: bool,
+    pub formats: Vec<u16>,
+    pub params: SdpAttributeRidParameters,
+    pub alternatives: Vec<String>,
+}
+
+/*
+ * What the answerer is able to handle. An empty list of formats accepts all
+ * payload types, a max_layers of None accepts any number of layers.
+ */
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SimulcastCapabilities {
+    pub formats: Vec<u16>,
+    pub limits: SdpAttributeRidParameters,
+    pub max_layers: Option<usize>,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpSimulcastAnswer {
+    pub simulcast: SdpAttributeSimulcast,
+    pub rids: Vec<SdpAttributeRid>,
+}
+
+impl SdpSimulcastAnswer {
+    pub fn layers(&self) -> Vec<SdpSimulcastLayer> {
+        simulcast_layers(&self.simulcast, &self.rids.iter().collect::<Vec<_>>())
+    }
+
+    // Replaces the a=simulcast and a=rid attributes of the answer m-section
+    pub fn apply(&self, media: &mut SdpMedia) -> Result<(), SdpParserInternalError> {
+        media.remove_attribute(SdpAttributeType::Rid);
+        for rid in &self.rids {
+            media.add_attribute(SdpAttribute::Rid(rid.clone()))?;
+        }
+        media.set_attribute(SdpAttribute::Simulcast(self.simulcast.clone()))
+    }
+}
+
+fn media_rids(media: &SdpMedia) -> Vec<&SdpAttributeRid> {
+    media
+        .get_attributes()
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Rid(rid) => Some(rid),
+            _ => None,
+        })
+        .collect()
+}
+
+fn simulcast_layers(
+    simulcast: &SdpAttributeSimulcast,
+    rids: &[&SdpAttributeRid],
+) -> Vec<SdpSimulcastLayer> {
+    let mut layers = Vec::new();
+    for (direction, versions) in &[
+        (SdpSingleDirection::Send, &simulcast.send),
+        (SdpSingleDirection::Recv, &simulcast.receive),
+    ] {
+        for version in versions.iter() {
+            let first = match version.ids.first() {
+                Some(first) => first,
+                None => continue,
+            };
+            let rid = rids
+                .iter()
+                .find(|rid| rid.id == first.id && rid.direction == *direction);
+            layers.push(SdpSimulcastLayer {
+                rid: first.id.clone(),
+                direction: direction.clone(),
+                paused: first.paused,
+                formats: rid.map(|r| r.formats.clone()).unwrap_or_default(),
+                params: rid.map(|r| r.params.clone()).unwrap_or_default(),
+                alternatives: version.ids[1..].iter().map(|id| id.id.clone()).collect(),
+            });
+        }
+    }
+    layers
+}
+
+pub fn get_simulcast_layers(media: &SdpMedia) -> Vec<SdpSimulcastLayer> {
+    match media.get_attribute(SdpAttributeType::Simulcast) {
+        Some(SdpAttribute::Simulcast(simulcast)) => simulcast_layers(simulcast, &media_rids(media)),
+        _ => Vec::new(),
+    }
+}
+
+fn intersect_formats(offered: &[u16], supported: &[u16]) -> Vec<u16> {
+    if supported.is_empty() {
+        return offered.to_vec();
+    }
+    offered
+        .iter()
+        .filter(|pt| supported.contains(pt))
+        .cloned()
+        .collect()
+}
+
+fn answer_versions(
+    offered: &[SdpAttributeSimulcastVersion],
+    offered_direction: SdpSingleDirection,
+    offer_rids: &[&SdpAttributeRid],
+    capabilities: &SimulcastCapabilities,
+) -> Vec<SdpAttributeSimulcastVersion> {
+    let supported = |id: &SdpAttributeSimulcastId| -> bool {
+        if id.paused {
+            return false;
+        }
+        match offer_rids
+            .iter()
+            .find(|rid| rid.id == id.id && rid.direction == offered_direction)
+        {
+            Some(rid) => {
+                rid.formats.is_empty()
+                    || !intersect_formats(&rid.formats, &capabilities.formats).is_empty()
+            }
+            None => false,
+        }
+    };
+    let versions = offered.iter().filter_map(|version| {
+        let ids: Vec<SdpAttributeSimulcastId> = version
+            .ids
+            .iter()
+            .filter(|id| supported(id))
+            .cloned()
+            .collect();
+        if ids.is_empty() {
+            None
+        } else {
+            Some(SdpAttributeSimulcastVersion { ids })
+        }
+    });
+    match capabilities.max_layers {
+        Some(max) => versions.take(max).collect(),
+        None => versions.collect(),
+    }
+}
+
+/*
+ * Answers the simulcast offer of an m-section according to RFC 8853: the
+ * directions are reversed, paused and unsupported rids are removed and the
+ * restrictions of the remaining rids are combined with the local limits.
+ * Returns None if the offer has no simulcast or nothing of it is usable.
+ */
+pub fn answer_simulcast(
+    offer: &SdpMedia,
+    capabilities: &SimulcastCapabilities,
+) -> Option<SdpSimulcastAnswer> {
+    let simulcast = match offer.get_attribute(SdpAttributeType::Simulcast) {
+        Some(SdpAttribute::Simulcast(simulcast)) => simulcast,
+        _ => return None,
+    };
+    let offer_rids = media_rids(offer);
+
+    let answer = SdpAttributeSimulcast {
+        send: answer_versions(
+            &simulcast.receive,
+            SdpSingleDirection::Recv,
+            &offer_rids,
+            capabilities,
+        ),
+        receive: answer_versions(
+            &simulcast.send,
+            SdpSingleDirection::Send,
+            &offer_rids,
+            capabilities,
+        ),
+    };
+    if answer.send.is_empty() && answer.receive.is_empty() {
+        return None;
+    }
+
+    let kept: Vec<(&SdpAttributeSimulcastId, SdpSingleDirection)> = answer
+        .send
+        .iter()
+        .flat_map(|v| v.ids.iter().map(|id| (id, SdpSingleDirection::Send)))
+        .chain(
+            answer
+                .receive
+                .iter()
+                .flat_map(|v| v.ids.iter().map(|id| (id, SdpSingleDirection::Recv))),
+        )
+        .collect();
+    let rids = kept
+        .iter()
+        .filter_map(|(id, direction)| {
+            offer_rids
+                .iter()
+                .find(|rid| rid.id == id.id && rid.direction == direction.reverse())
+                .map(|rid| SdpAttributeRid {
+                    id: rid.id.clone(),
+                    direction: direction.clone(),
+                    formats: intersect_formats(&rid.formats, &capabilities.formats),
+                    params: rid.params.intersect(&capabilities.limits),
+                    depends: rid
+                        .depends
+                        .iter()
+                        .filter(|d| kept.iter().any(|(k, _)| k.id == **d))
+                        .cloned()
+                        .collect(),
+                })
+        })
+        .collect();
+
+    Some(SdpSimulcastAnswer {
+        simulcast: answer,
+        rids,
+    })
+}
+
+#[cfg(test)]
+#[path = "./simulcast_tests.rs"]
+mod tests;
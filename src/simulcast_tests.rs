@@ -0,0 +1,100 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use tests::parse_dummy_sdp_session;
+use SdpSession;
+
+fn parse_offer() -> SdpSession {
+    parse_dummy_sdp_session(
+        "m=video 9 UDP/TLS/RTP/SAVPF 96 97\r\n\
+         a=sendonly\r\n\
+         a=rid:f send pt=96;max-width=1920;max-height=1080\r\n\
+         a=rid:h send pt=97;max-width=960\r\n\
+         a=rid:q send pt=96,97;max-fps=15\r\n\
+         a=rid:x send max-br=100000\r\n\
+         a=simulcast:send f;h,x;~q\r\n",
+    )
+}
+
+#[test]
+fn test_get_simulcast_layers() {
+    let offer = parse_offer();
+    let layers = get_simulcast_layers(&offer.media[0]);
+
+    assert_eq!(layers.len(), 3);
+    assert_eq!(layers[0].rid, "f");
+    assert_eq!(layers[0].direction, SdpSingleDirection::Send);
+    assert_eq!(layers[0].params.max_width, 1920);
+    assert_eq!(layers[1].rid, "h");
+    assert_eq!(layers[1].alternatives, vec!["x"]);
+    assert_eq!(layers[1].formats, vec![97]);
+    assert!(layers[2].paused);
+}
+
+#[test]
+fn test_answer_simulcast() {
+    let offer = parse_offer();
+    let capabilities = SimulcastCapabilities {
+        formats: vec![96],
+        limits: SdpAttributeRidParameters {
+            max_width: 1280,
+            max_fps: 30,
+            ..Default::default()
+        },
+        max_layers: None,
+    };
+    let answer = answer_simulcast(&offer.media[0], &capabilities).unwrap();
+
+    assert!(answer.simulcast.send.is_empty());
+    assert_eq!(answer.simulcast.to_string(), "recv f;x");
+    assert_eq!(answer.rids.len(), 2);
+    assert_eq!(
+        answer.rids[0].to_string(),
+        "f recv pt=96;max-width=1280;max-height=1080;max-fps=30"
+    );
+    assert_eq!(
+        answer.rids[1].to_string(),
+        "x recv max-width=1280;max-fps=30;max-br=100000"
+    );
+
+    let layers = answer.layers();
+    assert_eq!(layers.len(), 2);
+    assert_eq!(layers[1].rid, "x");
+    assert_eq!(layers[1].direction, SdpSingleDirection::Recv);
+    assert_eq!(layers[1].params.max_br, 100_000);
+}
+
+#[test]
+fn test_answer_simulcast_max_layers() {
+    let offer = parse_offer();
+    let capabilities = SimulcastCapabilities {
+        max_layers: Some(1),
+        ..Default::default()
+    };
+    let answer = answer_simulcast(&offer.media[0], &capabilities).unwrap();
+    assert_eq!(answer.simulcast.to_string(), "recv f");
+
+    let mut media = offer.media[0].clone();
+    media.remove_attribute(SdpAttributeType::Sendonly);
+    answer.apply(&mut media).unwrap();
+    assert_eq!(get_simulcast_layers(&media).len(), 1);
+    assert_eq!(media.get_attributes_of_type(SdpAttributeType::Rid).len(), 1);
+}
+
+#[test]
+fn test_answer_simulcast_rejected() {
+    let offer = parse_offer();
+    let capabilities = SimulcastCapabilities {
+        formats: vec![100],
+        ..Default::default()
+    };
+    let answer = answer_simulcast(&offer.media[0], &capabilities).unwrap();
+    // Only the rid without payload type restriction is left
+    assert_eq!(answer.simulcast.to_string(), "recv x");
+
+    let mut media = offer.media[0].clone();
+    media.remove_attribute(SdpAttributeType::Simulcast);
+    assert!(answer_simulcast(&media, &capabilities).is_none());
+}
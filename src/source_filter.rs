@@ -0,0 +1,109 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use address::{Address, AddressTyped, ExplicitlyTypedAddress};
+use attribute_type::{SdpAttribute, SdpAttributeSourceFilter, SdpSourceFilterMode};
+use media_type::SdpMedia;
+use std::net::IpAddr;
+use {SdpConnection, SdpSession};
+
+impl SdpAttributeSourceFilter {
+    // Whether the filter applies to packets sent to destination
+    pub fn applies_to(&self, destination: &ExplicitlyTypedAddress) -> bool {
+        if matches!(self.address_type, Some(t) if t != destination.address_type()) {
+            return false;
+        }
+        match self.destination {
+            Some(ref address) => *address == Address::from(destination.clone()),
+            None => true,
+        }
+    }
+
+    pub fn lists_source(&self, source: &Address) -> bool {
+        self.sources.contains(source)
+    }
+}
+
+fn get_source_filters(attributes: &[SdpAttribute]) -> Vec<&SdpAttributeSourceFilter> {
+    attributes
+        .iter()
+        .filter_map(|attribute| match attribute {
+            SdpAttribute::SourceFilter(filter) => Some(filter),
+            _ => None,
+        })
+        .collect()
+}
+
+// The destinations of a c= line, all the addresses of a multicast range
+fn connection_destinations(connection: &SdpConnection) -> Vec<ExplicitlyTypedAddress> {
+    match connection.addresses() {
+        Ok(addresses) => addresses
+            .into_iter()
+            .map(ExplicitlyTypedAddress::from)
+            .collect(),
+        Err(_) => vec![connection.address.clone()],
+    }
+}
+
+/*
+ * RFC 4570 section 3.2
+ * The source filters which apply to the m-section. Media level filters
+ * replace the session level ones, and of these only the filters for the
+ * destinations of the c= line in effect for the m-section are kept.
+ */
+pub fn media_source_filters(
+    session: &SdpSession,
+    media: &SdpMedia,
+) -> Vec<SdpAttributeSourceFilter> {
+    let mut filters = get_source_filters(media.get_attributes());
+    if filters.is_empty() {
+        filters = get_source_filters(&session.attribute);
+    }
+    let connection = media
+        .get_connection()
+        .as_ref()
+        .or(session.get_connection().as_ref());
+    let destinations = connection.map(connection_destinations);
+    filters
+        .into_iter()
+        .filter(|filter| match destinations {
+            Some(ref destinations) => destinations.iter().any(|d| filter.applies_to(d)),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+// The effective source filters of each m-section of session
+pub fn effective_source_filters(session: &SdpSession) -> Vec<Vec<SdpAttributeSourceFilter>> {
+    session
+        .media
+        .iter()
+        .map(|media| media_source_filters(session, media))
+        .collect()
+}
+
+/*
+ * Whether packets from source to destination pass the filters. A source
+ * must be listed by one of the incl filters for the destination, if there
+ * are any, and must not be listed by any of its excl filters.
+ */
+pub fn source_allowed(
+    filters: &[SdpAttributeSourceFilter],
+    destination: &IpAddr,
+    source: &IpAddr,
+) -> bool {
+    let destination = ExplicitlyTypedAddress::from(*destination);
+    let source = Address::Ip(*source);
+    let (included, excluded): (Vec<&SdpAttributeSourceFilter>, Vec<_>) = filters
+        .iter()
+        .filter(|filter| filter.applies_to(&destination))
+        .partition(|filter| filter.mode == SdpSourceFilterMode::Include);
+    (included.is_empty() || included.iter().any(|filter| filter.lists_source(&source)))
+        && !excluded.iter().any(|filter| filter.lists_source(&source))
+}
+
+#[cfg(test)]
+#[path = "./source_filter_tests.rs"]
+mod tests;
@@ -0,0 +1,98 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use parse_sdp;
+
+const SSM_SDP: &str = "v=0\r\n\
+                       o=- 1 1 IN IP4 192.0.2.1\r\n\
+                       s=IPTV\r\n\
+                       t=0 0\r\n\
+                       a=source-filter: incl IN IP4 232.3.4.5 192.0.2.10\r\n\
+                       m=video 5000 RTP/AVP 96\r\n\
+                       c=IN IP4 232.3.4.5/64\r\n\
+                       a=rtpmap:96 H264/90000\r\n\
+                       m=audio 5002 RTP/AVP 96\r\n\
+                       c=IN IP4 232.3.4.6/64/2\r\n\
+                       a=source-filter: excl IN * * 192.0.2.66\r\n\
+                       a=source-filter: incl IN IP4 232.3.4.7 192.0.2.11 192.0.2.12\r\n\
+                       a=rtpmap:96 opus/48000/2\r\n\
+                       m=audio 5004 RTP/AVP 96\r\n\
+                       c=IN IP4 232.3.4.8/64\r\n\
+                       a=rtpmap:96 opus/48000/2\r\n";
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_effective_source_filters() {
+    let session = parse_sdp(SSM_SDP, true).unwrap();
+    let filters = effective_source_filters(&session);
+    assert_eq!(filters.len(), 3);
+
+    // The session level filter applies to the first m-section
+    assert_eq!(filters[0].len(), 1);
+    assert_eq!(
+        filters[0][0].to_string(),
+        "incl IN IP4 232.3.4.5 192.0.2.10"
+    );
+    assert!(source_allowed(
+        &filters[0],
+        &ip("232.3.4.5"),
+        &ip("192.0.2.10")
+    ));
+    assert!(!source_allowed(
+        &filters[0],
+        &ip("232.3.4.5"),
+        &ip("192.0.2.11")
+    ));
+
+    // Media level filters replace it, 232.3.4.7 is the second address
+    assert_eq!(filters[1].len(), 2);
+    assert!(source_allowed(
+        &filters[1],
+        &ip("232.3.4.6"),
+        &ip("192.0.2.99")
+    ));
+    assert!(!source_allowed(
+        &filters[1],
+        &ip("232.3.4.6"),
+        &ip("192.0.2.66")
+    ));
+    assert!(source_allowed(
+        &filters[1],
+        &ip("232.3.4.7"),
+        &ip("192.0.2.12")
+    ));
+    assert!(!source_allowed(
+        &filters[1],
+        &ip("232.3.4.7"),
+        &ip("192.0.2.99")
+    ));
+
+    // The session level filter is for another destination
+    assert!(filters[2].is_empty());
+    assert!(source_allowed(
+        &filters[2],
+        &ip("232.3.4.8"),
+        &ip("192.0.2.99")
+    ));
+}
+
+#[test]
+fn test_source_filter_applies_to() {
+    let session = parse_sdp(SSM_SDP, true).unwrap();
+    let filters = media_source_filters(&session, &session.media[1]);
+    let wildcard = &filters[0];
+    assert_eq!(wildcard.address_type, None);
+    assert_eq!(wildcard.destination, None);
+    assert!(wildcard.applies_to(&ExplicitlyTypedAddress::from(ip("ff15::1"))));
+    assert!(wildcard.lists_source(&Address::Ip(ip("192.0.2.66"))));
+
+    let typed = &filters[1];
+    assert!(typed.applies_to(&ExplicitlyTypedAddress::from(ip("232.3.4.7"))));
+    assert!(!typed.applies_to(&ExplicitlyTypedAddress::from(ip("232.3.4.6"))));
+    assert!(!typed.applies_to(&ExplicitlyTypedAddress::from(ip("ff15::1"))));
+}
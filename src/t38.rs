@@ -0,0 +1,157 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{SdpAttribute, SdpT38FaxRateManagement, SdpT38FaxUdpEc};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use media_type::{SdpMedia, SdpMediaValue};
+use SdpSession;
+
+// The T.38 parameters of an image m-section, ITU-T T.38 Annex D
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpT38Parameters {
+    pub version: Option<u8>,
+    pub max_bit_rate: Option<u32>,
+    pub rate_management: Option<SdpT38FaxRateManagement>,
+    pub udp_ec: Option<SdpT38FaxUdpEc>,
+    pub max_buffer: Option<u32>,
+    pub max_datagram: Option<u32>,
+    pub fill_bit_removal: bool,
+    pub transcoding_mmr: bool,
+    pub transcoding_jbig: bool,
+}
+
+impl SdpT38Parameters {
+    pub fn from_media(media: &SdpMedia) -> SdpT38Parameters {
+        let mut parameters = SdpT38Parameters::default();
+        for attribute in media.get_attributes() {
+            match *attribute {
+                SdpAttribute::T38FaxVersion(v) => parameters.version = Some(v),
+                SdpAttribute::T38MaxBitRate(v) => parameters.max_bit_rate = Some(v),
+                SdpAttribute::T38FaxRateManagement(v) => parameters.rate_management = Some(v),
+                SdpAttribute::T38FaxUdpEc(v) => parameters.udp_ec = Some(v),
+                SdpAttribute::T38FaxMaxBuffer(v) => parameters.max_buffer = Some(v),
+                SdpAttribute::T38FaxMaxDatagram(v) => parameters.max_datagram = Some(v),
+                SdpAttribute::T38FaxFillBitRemoval(v) => parameters.fill_bit_removal = v,
+                SdpAttribute::T38FaxTranscodingMmr(v) => parameters.transcoding_mmr = v,
+                SdpAttribute::T38FaxTranscodingJbig(v) => parameters.transcoding_jbig = v,
+                _ => (),
+            }
+        }
+        parameters
+    }
+
+    // T.38 Annex D assumes version 0 if the offer does not declare one
+    pub fn version_or_default(&self) -> u8 {
+        self.version.unwrap_or(0)
+    }
+}
+
+fn is_t38(media: &SdpMedia) -> bool {
+    *media.get_type() == SdpMediaValue::Image
+}
+
+/*
+ * ITU-T T.38 Annex D
+ * The answerer may only lower the version, bit rate and error correction
+ * mode, has to accept the rate management method of the offer and must not
+ * enable options which were not offered.
+ */
+pub fn validate_t38_answer(
+    offer: &SdpMedia,
+    answer: &SdpMedia,
+) -> Result<(), SdpParserInternalError> {
+    if !is_t38(offer) || !is_t38(answer) || offer.get_port() == 0 || answer.get_port() == 0 {
+        return Ok(());
+    }
+    let offered = SdpT38Parameters::from_media(offer);
+    let answered = SdpT38Parameters::from_media(answer);
+    if answered.version_or_default() > offered.version_or_default() {
+        return Err(SdpParserInternalError::Generic(format!(
+            "T38FaxVersion {} of the answer is higher than the offered {}",
+            answered.version_or_default(),
+            offered.version_or_default()
+        ))
+        .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+    }
+    if let (Some(offered_rate), Some(answered_rate)) = (offered.max_bit_rate, answered.max_bit_rate)
+    {
+        if answered_rate > offered_rate {
+            return Err(SdpParserInternalError::Generic(format!(
+                "T38MaxBitRate {} of the answer is higher than the offered {}",
+                answered_rate, offered_rate
+            ))
+            .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+        }
+    }
+    if let (Some(offered_rm), Some(answered_rm)) =
+        (offered.rate_management, answered.rate_management)
+    {
+        if answered_rm != offered_rm {
+            return Err(SdpParserInternalError::Generic(format!(
+                "T38FaxRateManagement {} of the answer differs from the offered {}",
+                answered_rm, offered_rm
+            ))
+            .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+        }
+    }
+    if let Some(answered_ec) = answered.udp_ec {
+        if !matches!(offered.udp_ec, Some(offered_ec) if answered_ec <= offered_ec) {
+            return Err(SdpParserInternalError::Generic(format!(
+                "T38FaxUdpEC {} of the answer was not offered",
+                answered_ec
+            ))
+            .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+        }
+    }
+    let flags = [
+        (
+            "T38FaxFillBitRemoval",
+            offered.fill_bit_removal,
+            answered.fill_bit_removal,
+        ),
+        (
+            "T38FaxTranscodingMMR",
+            offered.transcoding_mmr,
+            answered.transcoding_mmr,
+        ),
+        (
+            "T38FaxTranscodingJBIG",
+            offered.transcoding_jbig,
+            answered.transcoding_jbig,
+        ),
+    ];
+    for (name, offered_flag, answered_flag) in flags.iter() {
+        if *answered_flag && !*offered_flag {
+            return Err(SdpParserInternalError::Generic(format!(
+                "{} of the answer was not offered",
+                name
+            ))
+            .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+        }
+    }
+    Ok(())
+}
+
+// Validates the T.38 parameters of every image m-section of the answer
+pub fn validate_t38_session_answer(
+    offer: &SdpSession,
+    answer: &SdpSession,
+) -> Result<(), SdpParserInternalError> {
+    if offer.media.len() != answer.media.len() {
+        return Err(SdpParserInternalError::Generic(
+            "Offer and answer have a different number of media sections".to_string(),
+        )
+        .with_kind(SdpParserErrorKind::T38AnswerNotAllowed));
+    }
+    offer
+        .media
+        .iter()
+        .zip(answer.media.iter())
+        .try_for_each(|(offer_media, answer_media)| validate_t38_answer(offer_media, answer_media))
+}
+
+#[cfg(test)]
+#[path = "./t38_tests.rs"]
+mod tests;
@@ -0,0 +1,110 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use error::SdpParserErrorKind;
+use tests::parse_dummy_sdp_session;
+
+fn create_session(port: u32, attributes: &[&str]) -> SdpSession {
+    let mut sdp = format!("m=image {} udptl t38\r\n", port);
+    for attribute in attributes {
+        sdp.push_str(&format!("a={}\r\n", attribute));
+    }
+    parse_dummy_sdp_session(&sdp)
+}
+
+const OFFER: &[&str] = &[
+    "T38FaxVersion:1",
+    "T38MaxBitRate:14400",
+    "T38FaxRateManagement:transferredTCF",
+    "T38FaxUdpEC:t38UDPRedundancy",
+    "T38FaxMaxDatagram:400",
+    "T38FaxFillBitRemoval",
+];
+
+#[test]
+fn test_t38_parameters_from_media() {
+    let offer = create_session(5000, OFFER);
+    let parameters = SdpT38Parameters::from_media(&offer.media[0]);
+    assert_eq!(parameters.version, Some(1));
+    assert_eq!(parameters.max_bit_rate, Some(14400));
+    assert_eq!(
+        parameters.rate_management,
+        Some(SdpT38FaxRateManagement::TransferredTcf)
+    );
+    assert_eq!(parameters.udp_ec, Some(SdpT38FaxUdpEc::Redundancy));
+    assert_eq!(parameters.max_buffer, None);
+    assert_eq!(parameters.max_datagram, Some(400));
+    assert!(parameters.fill_bit_removal);
+    assert!(!parameters.transcoding_mmr);
+
+    let parameters = SdpT38Parameters::from_media(&create_session(5000, &[]).media[0]);
+    assert_eq!(parameters.version_or_default(), 0);
+}
+
+#[test]
+fn test_validate_t38_answer() {
+    let offer = create_session(5000, OFFER);
+    let accepted = [
+        create_session(6000, OFFER),
+        create_session(
+            6000,
+            &[
+                "T38FaxVersion:0",
+                "T38MaxBitRate:9600",
+                "T38FaxRateManagement:transferredTCF",
+                "T38FaxUdpEC:t38UDPNoEC",
+                "T38FaxFillBitRemoval:0",
+            ],
+        ),
+        create_session(6000, &[]),
+        // Rejected m-sections are not validated
+        create_session(0, &["T38FaxVersion:3"]),
+    ];
+    for answer in &accepted {
+        assert!(validate_t38_session_answer(&offer, answer).is_ok());
+    }
+
+    let refused = [
+        "T38FaxVersion:2",
+        "T38MaxBitRate:33600",
+        "T38FaxRateManagement:localTCF",
+        "T38FaxUdpEC:t38UDPFEC",
+        "T38FaxTranscodingMMR",
+    ];
+    for attribute in &refused {
+        let answer = create_session(6000, &[attribute]);
+        assert_eq!(
+            validate_t38_answer(&offer.media[0], &answer.media[0])
+                .map_err(|error| error.kind())
+                .err(),
+            Some(SdpParserErrorKind::T38AnswerNotAllowed),
+            "{} was accepted",
+            attribute
+        );
+    }
+
+    // Error correction can not be answered if none was offered
+    let offer = create_session(5000, &[]);
+    let answer = create_session(6000, &["T38FaxUdpEC:t38UDPNoEC"]);
+    assert_eq!(
+        validate_t38_answer(&offer.media[0], &answer.media[0])
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::T38AnswerNotAllowed
+    );
+}
+
+#[test]
+fn test_validate_t38_answer_media_count() {
+    let offer = create_session(5000, OFFER);
+    let mut answer = create_session(6000, OFFER);
+    answer.media.clear();
+    assert_eq!(
+        validate_t38_session_answer(&offer, &answer)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::T38AnswerNotAllowed
+    );
+}
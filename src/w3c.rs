@@ -0,0 +1,240 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use attribute_type::{SdpAttribute, SdpAttributeCandidate, SdpAttributeType};
+use error::{SdpParserError, SdpParserErrorKind, SdpParserInternalError};
+use std::convert::TryFrom;
+use std::fmt;
+use {parse_sdp, SdpSession};
+
+/*
+ * The JSON shapes used by browsers for signalling, see the
+ * RTCSessionDescriptionInit and RTCIceCandidateInit dictionaries of
+ * https://www.w3.org/TR/webrtc/
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "lowercase"))]
+pub enum SdpDescriptionType {
+    Offer,
+    Pranswer,
+    Answer,
+    Rollback,
+}
+
+impl fmt::Display for SdpDescriptionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpDescriptionType::Offer => "offer",
+            SdpDescriptionType::Pranswer => "pranswer",
+            SdpDescriptionType::Answer => "answer",
+            SdpDescriptionType::Rollback => "rollback",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpSessionDescriptionInit {
+    #[cfg_attr(feature = "serialize", serde(rename = "type"))]
+    pub sdp_type: SdpDescriptionType,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub sdp: String,
+}
+
+impl SdpSessionDescriptionInit {
+    pub fn new(sdp_type: SdpDescriptionType, session: &SdpSession) -> SdpSessionDescriptionInit {
+        SdpSessionDescriptionInit {
+            sdp_type,
+            sdp: session.to_string(),
+        }
+    }
+
+    // A rollback does not carry a description and fails to parse
+    pub fn parse(&self, fail_on_warning: bool) -> Result<SdpSession, SdpParserError> {
+        parse_sdp(&self.sdp, fail_on_warning)
+    }
+}
+
+// Unsupported lines are kept as warnings of the session, like browsers do
+impl<'a> TryFrom<&'a SdpSessionDescriptionInit> for SdpSession {
+    type Error = SdpParserError;
+
+    fn try_from(description: &'a SdpSessionDescriptionInit) -> Result<Self, Self::Error> {
+        description.parse(false)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serialize", serde(rename_all = "camelCase"))]
+pub struct SdpIceCandidateInit {
+    // The a=candidate line without the "a=", empty for the end of candidates
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub candidate: String,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub sdp_mid: Option<String>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub sdp_m_line_index: Option<u16>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub username_fragment: Option<String>,
+}
+
+impl SdpIceCandidateInit {
+    /*
+     * Exports a local candidate of the m-section at index. The username
+     * fragment is the one of the candidate, or otherwise the effective
+     * ice-ufrag of the m-section.
+     */
+    pub fn new(
+        session: &SdpSession,
+        index: usize,
+        candidate: &SdpAttributeCandidate,
+    ) -> Result<SdpIceCandidateInit, SdpParserInternalError> {
+        let media = session.media.get(index).ok_or_else(|| {
+            SdpParserInternalError::Generic(format!("there is no m-section at index {}", index))
+                .with_kind(SdpParserErrorKind::CandidateInitMediaIndexOutOfRange)
+        })?;
+        let sdp_m_line_index = u16::try_from(index).map_err(|_| {
+            SdpParserInternalError::Generic(format!(
+                "m-section index {} does not fit into sdpMLineIndex",
+                index
+            ))
+            .with_kind(SdpParserErrorKind::CandidateInitMediaIndexOutOfRange)
+        })?;
+        let string_attribute = |t: SdpAttributeType| match media
+            .get_attribute(t.clone())
+            .or_else(|| session.get_attribute(t))
+        {
+            Some(SdpAttribute::Mid(value)) | Some(SdpAttribute::IceUfrag(value)) => {
+                Some(value.clone())
+            }
+            _ => None,
+        };
+        Ok(SdpIceCandidateInit {
+            candidate: SdpAttribute::Candidate(candidate.clone()).to_string(),
+            sdp_mid: string_attribute(SdpAttributeType::Mid),
+            sdp_m_line_index: Some(sdp_m_line_index),
+            username_fragment: candidate
+                .ufrag
+                .clone()
+                .or_else(|| string_attribute(SdpAttributeType::IceUfrag)),
+        })
+    }
+
+    pub fn end_of_candidates(&self) -> bool {
+        self.candidate.trim().is_empty()
+    }
+
+    // Parses the candidate, None is returned for the end of candidates
+    pub fn parse_candidate(&self) -> Result<Option<SdpAttributeCandidate>, SdpParserInternalError> {
+        if self.end_of_candidates() {
+            return Ok(None);
+        }
+        let candidate = self.candidate.trim();
+        let candidate = candidate.strip_prefix("a=").unwrap_or(candidate);
+        match candidate.parse::<SdpAttribute>()? {
+            SdpAttribute::Candidate(candidate) => Ok(Some(candidate)),
+            attribute => Err(SdpParserInternalError::Generic(format!(
+                "expected a candidate attribute but found {}",
+                attribute
+            ))
+            .with_kind(SdpParserErrorKind::CandidateInitNotACandidate)),
+        }
+    }
+
+    /*
+     * JSEP section 5.9
+     * The m-section is identified by the mid if present and otherwise by
+     * the index.
+     */
+    pub fn media_index(&self, session: &SdpSession) -> Result<usize, SdpParserInternalError> {
+        if let Some(ref mid) = self.sdp_mid {
+            return session
+                .media
+                .iter()
+                .position(|media| match media.get_attribute(SdpAttributeType::Mid) {
+                    Some(SdpAttribute::Mid(value)) => value == mid,
+                    _ => false,
+                })
+                .ok_or_else(|| {
+                    SdpParserInternalError::Generic(format!(
+                        "there is no m-section with mid {}",
+                        mid
+                    ))
+                    .with_kind(SdpParserErrorKind::CandidateInitUnknownMid)
+                });
+        }
+        match self.sdp_m_line_index {
+            Some(index) if (index as usize) < session.media.len() => Ok(index as usize),
+            Some(index) => Err(SdpParserInternalError::Generic(format!(
+                "there is no m-section at index {}",
+                index
+            ))
+            .with_kind(SdpParserErrorKind::CandidateInitMediaIndexOutOfRange)),
+            None => Err(SdpParserInternalError::Generic(
+                "candidate has neither sdpMid nor sdpMLineIndex".to_string(),
+            )
+            .with_kind(SdpParserErrorKind::CandidateInitMissingMedia)),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a SdpIceCandidateInit> for SdpAttributeCandidate {
+    type Error = SdpParserInternalError;
+
+    fn try_from(init: &'a SdpIceCandidateInit) -> Result<Self, Self::Error> {
+        init.parse_candidate()?.ok_or_else(|| {
+            SdpParserInternalError::Generic("end of candidates is not a candidate".to_string())
+                .with_kind(SdpParserErrorKind::CandidateInitNotACandidate)
+        })
+    }
+}
+
+/*
+ * Adds a trickled remote candidate to its m-section, or a=end-of-candidates
+ * for an empty candidate, and returns the index of the m-section. Candidates
+ * with a username fragment of a different ICE generation are rejected.
+ */
+pub fn add_ice_candidate(
+    session: &mut SdpSession,
+    init: &SdpIceCandidateInit,
+) -> Result<usize, SdpParserInternalError> {
+    let index = init.media_index(session)?;
+    let candidate = init.parse_candidate()?;
+
+    if let Some(ref ufrag) = init.username_fragment {
+        let current = session.media[index]
+            .get_attribute(SdpAttributeType::IceUfrag)
+            .or_else(|| session.get_attribute(SdpAttributeType::IceUfrag));
+        if let Some(SdpAttribute::IceUfrag(current)) = current {
+            if current != ufrag {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "candidate ufrag {} does not match the ice-ufrag {} of m-section {}",
+                    ufrag, current, index
+                ))
+                .with_kind(SdpParserErrorKind::CandidateInitUfragMismatch));
+            }
+        }
+    }
+
+    let media = &mut session.media[index];
+    match candidate {
+        Some(candidate) => media.add_attribute(SdpAttribute::Candidate(candidate))?,
+        None => {
+            if media
+                .get_attribute(SdpAttributeType::EndOfCandidates)
+                .is_none()
+            {
+                media.add_attribute(SdpAttribute::EndOfCandidates)?
+            }
+        }
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+#[path = "./w3c_tests.rs"]
+mod tests;
@@ -0,0 +1,154 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+
+const SDP: &str = "v=0\r\n\
+                   o=- 1 1 IN IP4 0.0.0.0\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   c=IN IP4 0.0.0.0\r\n\
+                   a=ice-ufrag:foo\r\n\
+                   a=ice-pwd:bar\r\n\
+                   m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+                   a=mid:audio\r\n\
+                   m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+                   a=mid:video\r\n\
+                   a=ice-ufrag:baz\r\n";
+
+const CANDIDATE: &str = "candidate:842163049 1 udp 1677729535 192.0.2.1 51556 typ srflx \
+                         raddr 10.0.0.1 rport 51556";
+
+fn candidate_init(
+    mid: Option<&str>,
+    index: Option<u16>,
+    ufrag: Option<&str>,
+) -> SdpIceCandidateInit {
+    SdpIceCandidateInit {
+        candidate: CANDIDATE.to_string(),
+        sdp_mid: mid.map(ToString::to_string),
+        sdp_m_line_index: index,
+        username_fragment: ufrag.map(ToString::to_string),
+    }
+}
+
+#[test]
+fn test_session_description_init() {
+    let description = SdpSessionDescriptionInit {
+        sdp_type: SdpDescriptionType::Offer,
+        sdp: SDP.to_string(),
+    };
+    let session = SdpSession::try_from(&description).unwrap();
+    assert_eq!(session.media.len(), 2);
+
+    let exported = SdpSessionDescriptionInit::new(SdpDescriptionType::Answer, &session);
+    assert_eq!(exported.sdp_type.to_string(), "answer");
+    assert_eq!(
+        exported.parse(true).unwrap().to_string(),
+        session.to_string()
+    );
+
+    let rollback = SdpSessionDescriptionInit {
+        sdp_type: SdpDescriptionType::Rollback,
+        sdp: String::new(),
+    };
+    assert!(rollback.parse(false).is_err());
+}
+
+#[test]
+fn test_add_ice_candidate() {
+    let mut session = parse_sdp(SDP, true).unwrap();
+
+    // The mid takes precedence over the index
+    let init = candidate_init(Some("video"), Some(0), Some("baz"));
+    assert_eq!(add_ice_candidate(&mut session, &init).unwrap(), 1);
+    assert_eq!(
+        session.media[1]
+            .get_attributes_of_type(SdpAttributeType::Candidate)
+            .len(),
+        1
+    );
+
+    // The session level ufrag is inherited by the first m-section
+    let init = candidate_init(None, Some(0), Some("foo"));
+    assert_eq!(add_ice_candidate(&mut session, &init).unwrap(), 0);
+
+    let mut error_kind = |init: SdpIceCandidateInit| {
+        add_ice_candidate(&mut session, &init)
+            .map_err(|error| error.kind())
+            .err()
+    };
+    assert_eq!(
+        error_kind(candidate_init(None, Some(0), Some("baz"))),
+        Some(SdpParserErrorKind::CandidateInitUfragMismatch)
+    );
+    assert_eq!(
+        error_kind(candidate_init(Some("data"), None, None)),
+        Some(SdpParserErrorKind::CandidateInitUnknownMid)
+    );
+    assert_eq!(
+        error_kind(candidate_init(None, Some(2), None)),
+        Some(SdpParserErrorKind::CandidateInitMediaIndexOutOfRange)
+    );
+    assert_eq!(
+        error_kind(candidate_init(None, None, None)),
+        Some(SdpParserErrorKind::CandidateInitMissingMedia)
+    );
+
+    let end = SdpIceCandidateInit {
+        sdp_mid: Some("audio".to_string()),
+        ..Default::default()
+    };
+    assert!(end.end_of_candidates());
+    assert_eq!(
+        SdpAttributeCandidate::try_from(&end).unwrap_err().kind(),
+        SdpParserErrorKind::CandidateInitNotACandidate
+    );
+    add_ice_candidate(&mut session, &end).unwrap();
+    add_ice_candidate(&mut session, &end).unwrap();
+    assert_eq!(
+        session.media[0]
+            .get_attributes_of_type(SdpAttributeType::EndOfCandidates)
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_export_ice_candidate() {
+    let session = parse_sdp(SDP, true).unwrap();
+    let candidate = SdpAttributeCandidate::try_from(&candidate_init(None, None, None)).unwrap();
+    assert_eq!(candidate.port, 51556);
+
+    let init = SdpIceCandidateInit::new(&session, 1, &candidate).unwrap();
+    assert_eq!(init.sdp_mid, Some("video".to_string()));
+    assert_eq!(init.sdp_m_line_index, Some(1));
+    assert_eq!(init.username_fragment, Some("baz".to_string()));
+    assert_eq!(
+        init.parse_candidate().unwrap().unwrap().to_string(),
+        candidate.to_string()
+    );
+
+    let init = SdpIceCandidateInit::new(&session, 0, &candidate).unwrap();
+    assert_eq!(init.username_fragment, Some("foo".to_string()));
+    assert_eq!(
+        SdpIceCandidateInit::new(&session, 2, &candidate)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::CandidateInitMediaIndexOutOfRange
+    );
+
+    // sdpMLineIndex can't address more than 65536 m-sections
+    let mut session = session;
+    while session.media.len() <= 65536 {
+        let media = session.media[0].clone();
+        session.media.push(media);
+    }
+    assert_eq!(
+        SdpIceCandidateInit::new(&session, 65536, &candidate)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::CandidateInitMediaIndexOutOfRange
+    );
+}
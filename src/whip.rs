@@ -0,0 +1,362 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use address::{Address, ExplicitlyTypedAddress};
+use attribute_type::{
+    SdpAttribute, SdpAttributeCandidate, SdpAttributeCandidateTransport, SdpAttributeCandidateType,
+    SdpAttributeFingerprint, SdpAttributeGroup, SdpAttributeGroupSemantic, SdpAttributeType,
+};
+use dtls::{answer_setup, get_setup};
+use error::{SdpParserErrorKind, SdpParserInternalError};
+use media_type::{SdpMedia, SdpMediaLine};
+use sdpfrag::{merge_sdp_fragment, SdpFragment};
+use simulcast::{answer_simulcast, SimulcastCapabilities};
+use std::net::SocketAddr;
+use {SdpConnection, SdpOrigin, SdpSession, SdpTiming};
+
+/*
+ * WHIP (RFC 9725) ingest endpoints receive media from the client, WHEP
+ * playback endpoints send media to it. The client always sends the offer.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum SdpWhipEndpoint {
+    Whip,
+    Whep,
+}
+
+impl SdpWhipEndpoint {
+    // The direction every m-section of the client offer must have
+    pub fn offer_direction(self) -> SdpAttributeType {
+        match self {
+            SdpWhipEndpoint::Whip => SdpAttributeType::Sendonly,
+            SdpWhipEndpoint::Whep => SdpAttributeType::Recvonly,
+        }
+    }
+
+    pub fn answer_direction(self) -> SdpAttribute {
+        match self {
+            SdpWhipEndpoint::Whip => SdpAttribute::Recvonly,
+            SdpWhipEndpoint::Whep => SdpAttribute::Sendonly,
+        }
+    }
+}
+
+/*
+ * The transport of the endpoint. The answer offers a host candidate for
+ * each address, the first one is also used for the m= and c= lines.
+ */
+#[derive(Clone)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpWhipAnswerOptions {
+    pub ice_ufrag: String,
+    pub ice_pwd: String,
+    pub fingerprint: SdpAttributeFingerprint,
+    pub host_candidates: Vec<SocketAddr>,
+    pub session_id: u64,
+}
+
+fn get_mid(media: &SdpMedia) -> Option<&String> {
+    match media.get_attribute(SdpAttributeType::Mid) {
+        Some(SdpAttribute::Mid(mid)) => Some(mid),
+        _ => None,
+    }
+}
+
+fn bundle_groups(session: &SdpSession) -> Vec<&SdpAttributeGroup> {
+    session
+        .attribute
+        .iter()
+        .filter_map(|attr| match attr {
+            SdpAttribute::Group(group) => match group.semantics {
+                SdpAttributeGroupSemantic::Bundle => Some(group),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/*
+ * RFC 9725 section 4.2
+ * The offer must bundle all of its m-sections into a single group and only
+ * send (WHIP) or receive (WHEP) media. Rejected m-sections are ignored.
+ */
+pub fn validate_whip_offer(
+    offer: &SdpSession,
+    endpoint: SdpWhipEndpoint,
+) -> Result<(), SdpParserInternalError> {
+    let groups = bundle_groups(offer);
+    let group = match groups.as_slice() {
+        [group] => group,
+        _ => {
+            return Err(SdpParserInternalError::Generic(format!(
+                "offer must have exactly one BUNDLE group, found {}",
+                groups.len()
+            ))
+            .with_kind(SdpParserErrorKind::WhipOfferBundleGroupCount))
+        }
+    };
+    for (index, media) in offer.media.iter().enumerate() {
+        if media.get_port() == 0 && media.get_attribute(SdpAttributeType::BundleOnly).is_none() {
+            continue;
+        }
+        let mid = get_mid(media).ok_or_else(|| {
+            SdpParserInternalError::Generic(format!("m-section {} has no mid", index))
+                .with_kind(SdpParserErrorKind::WhipOfferMissingMid)
+        })?;
+        if !group.tags.contains(mid) {
+            return Err(SdpParserInternalError::Generic(format!(
+                "m-section with mid {} is not part of the BUNDLE group",
+                mid
+            ))
+            .with_kind(SdpParserErrorKind::WhipOfferNotBundled));
+        }
+        if media.get_attribute(endpoint.offer_direction()).is_none() {
+            return Err(SdpParserInternalError::Generic(format!(
+                "m-section with mid {} has the wrong direction for {:?}",
+                mid, endpoint
+            ))
+            .with_kind(SdpParserErrorKind::WhipOfferWrongDirection));
+        }
+        for (t, name) in &[
+            (SdpAttributeType::IceUfrag, "ice-ufrag"),
+            (SdpAttributeType::Fingerprint, "fingerprint"),
+        ] {
+            if media.get_attribute(t.clone()).is_none() && offer.get_attribute(t.clone()).is_none()
+            {
+                return Err(SdpParserInternalError::Generic(format!(
+                    "m-section with mid {} has no {}",
+                    mid, name
+                ))
+                .with_kind(SdpParserErrorKind::WhipOfferMissingTransport));
+            }
+        }
+    }
+    Ok(())
+}
+
+// The attributes of the offer which describe the transport or the sender
+const OFFER_ONLY_ATTRIBUTES: &[SdpAttributeType] = &[
+    SdpAttributeType::BundleOnly,
+    SdpAttributeType::Candidate,
+    SdpAttributeType::EndOfCandidates,
+    SdpAttributeType::Fingerprint,
+    SdpAttributeType::IceOptions,
+    SdpAttributeType::IcePacing,
+    SdpAttributeType::IcePwd,
+    SdpAttributeType::IceUfrag,
+    SdpAttributeType::Identity,
+    SdpAttributeType::Inactive,
+    SdpAttributeType::Msid,
+    SdpAttributeType::Recvonly,
+    SdpAttributeType::RemoteCandidate,
+    SdpAttributeType::Rid,
+    SdpAttributeType::Rtcp,
+    SdpAttributeType::Sendonly,
+    SdpAttributeType::Sendrecv,
+    SdpAttributeType::Setup,
+    SdpAttributeType::Simulcast,
+    SdpAttributeType::Ssrc,
+    SdpAttributeType::SsrcGroup,
+];
+
+// Host candidates with decreasing local preferences in the given order
+fn host_candidate(
+    index: usize,
+    address: &SocketAddr,
+) -> Result<SdpAttributeCandidate, SdpParserInternalError> {
+    let mut candidate = SdpAttributeCandidate::new(
+        String::new(),
+        1,
+        SdpAttributeCandidateTransport::Udp,
+        0,
+        Address::Ip(address.ip()),
+        u32::from(address.port()),
+        SdpAttributeCandidateType::Host,
+    );
+    candidate.set_computed_priority(65535 - index as u16)?;
+    candidate.set_computed_foundation(None);
+    Ok(candidate)
+}
+
+/*
+ * Creates the answer of an ICE-lite WHIP or WHEP endpoint. The codecs and
+ * header extensions of the offer are accepted as they are, simulcast offers
+ * are answered with all their layers. Each accepted m-section gets the host
+ * candidates followed by a=end-of-candidates, as an ICE-lite endpoint does
+ * not trickle.
+ */
+pub fn create_whip_answer(
+    offer: &SdpSession,
+    endpoint: SdpWhipEndpoint,
+    options: &SdpWhipAnswerOptions,
+) -> Result<SdpSession, SdpParserInternalError> {
+    validate_whip_offer(offer, endpoint)?;
+    let first = options.host_candidates.first().ok_or_else(|| {
+        SdpParserInternalError::Generic("answer needs at least one host candidate".to_string())
+            .with_kind(SdpParserErrorKind::WhipAnswerMissingCandidate)
+    })?;
+
+    let mut answer = SdpSession::new(
+        0,
+        SdpOrigin {
+            username: "-".to_string(),
+            session_id: options.session_id,
+            session_version: 1,
+            unicast_addr: ExplicitlyTypedAddress::Ip(first.ip()),
+        },
+        "-".to_string(),
+    );
+    answer.set_timing(SdpTiming { start: 0, stop: 0 });
+    answer.add_attribute(SdpAttribute::IceLite)?;
+    answer.add_attribute(SdpAttribute::Fingerprint(options.fingerprint.clone()))?;
+
+    let mut bundle = Vec::new();
+    for offered in &offer.media {
+        let mut media = offered.clone();
+        for t in OFFER_ONLY_ATTRIBUTES {
+            media.remove_attribute(t.clone());
+        }
+        if offered.get_port() == 0
+            && offered
+                .get_attribute(SdpAttributeType::BundleOnly)
+                .is_none()
+        {
+            answer.media.push(media);
+            continue;
+        }
+        media.set_port(u32::from(first.port()));
+        media.set_connection(SdpConnection {
+            address: ExplicitlyTypedAddress::Ip(first.ip()),
+            ttl: None,
+            amount: None,
+        });
+        media.add_attribute(endpoint.answer_direction())?;
+        media.add_attribute(SdpAttribute::IceUfrag(options.ice_ufrag.clone()))?;
+        media.add_attribute(SdpAttribute::IcePwd(options.ice_pwd.clone()))?;
+        media.add_attribute(SdpAttribute::Setup(answer_setup(&get_setup(
+            offer, offered, true,
+        ))))?;
+        if let Some(simulcast) = answer_simulcast(offered, &SimulcastCapabilities::default()) {
+            simulcast.apply(&mut media)?;
+        }
+        for (index, address) in options.host_candidates.iter().enumerate() {
+            media.add_attribute(SdpAttribute::Candidate(host_candidate(index, address)?))?;
+        }
+        media.add_attribute(SdpAttribute::EndOfCandidates)?;
+        if let Some(mid) = get_mid(&media) {
+            bundle.push(mid.clone());
+        }
+        answer.media.push(media);
+    }
+    answer.add_attribute(SdpAttribute::Group(SdpAttributeGroup {
+        semantics: SdpAttributeGroupSemantic::Bundle,
+        tags: bundle,
+    }))?;
+    Ok(answer)
+}
+
+/*
+ * RFC 9725 section 4.3
+ * Creates the sdpfrag body of a trickle or ICE restart PATCH request, or of
+ * the response to an ICE restart. New credentials are only included for an
+ * ICE restart. The candidates are added to the first m-section of the
+ * BUNDLE group, which carries the transport of all of them.
+ */
+pub fn create_ice_fragment(
+    session: &SdpSession,
+    credentials: Option<(&str, &str)>,
+    candidates: &[SdpAttributeCandidate],
+    end_of_candidates: bool,
+) -> Result<SdpFragment, SdpParserInternalError> {
+    let tag = bundle_groups(session)
+        .first()
+        .and_then(|group| group.tags.first())
+        .ok_or_else(|| {
+            SdpParserInternalError::Generic("session has no BUNDLE group".to_string())
+                .with_kind(SdpParserErrorKind::WhipSessionNotBundled)
+        })?;
+    let bundled = session
+        .media
+        .iter()
+        .find(|media| get_mid(media) == Some(tag))
+        .ok_or_else(|| {
+            SdpParserInternalError::Generic(format!("there is no m-section with mid {}", tag))
+                .with_kind(SdpParserErrorKind::WhipSessionNotBundled)
+        })?;
+
+    let mut fragment = SdpFragment::new();
+    if let Some((ufrag, pwd)) = credentials {
+        fragment
+            .attribute
+            .push(SdpAttribute::IceUfrag(ufrag.to_string()));
+        fragment
+            .attribute
+            .push(SdpAttribute::IcePwd(pwd.to_string()));
+    }
+    let mut media = SdpMedia::new(SdpMediaLine {
+        media: bundled.get_type().clone(),
+        port: bundled.get_port(),
+        port_count: bundled.get_port_count(),
+        proto: bundled.get_proto().clone(),
+        formats: bundled.get_formats().clone(),
+    });
+    media.add_attribute(SdpAttribute::Mid(tag.clone()))?;
+    for candidate in candidates {
+        media.add_attribute(SdpAttribute::Candidate(candidate.clone()))?;
+    }
+    if end_of_candidates {
+        media.add_attribute(SdpAttribute::EndOfCandidates)?;
+    }
+    fragment.media.push(media);
+    Ok(fragment)
+}
+
+/*
+ * Applies an sdpfrag with new ICE credentials to the remote description:
+ * the credentials replace the old ones at the session level and all known
+ * candidates are dropped before the candidates of the fragment are merged.
+ * Fragments without credentials are merged as trickled candidates.
+ */
+pub fn apply_ice_fragment(
+    session: &mut SdpSession,
+    fragment: &SdpFragment,
+) -> Result<(), SdpParserInternalError> {
+    let ufrag = fragment.get_attribute(SdpAttributeType::IceUfrag);
+    let pwd = fragment.get_attribute(SdpAttributeType::IcePwd);
+    // Work on a copy so that a failed merge leaves the session untouched
+    let mut updated = session.clone();
+    if let (Some(ufrag), Some(pwd)) = (ufrag, pwd) {
+        updated.attribute.retain(|attr| {
+            let t = SdpAttributeType::from(attr);
+            t != SdpAttributeType::IceUfrag && t != SdpAttributeType::IcePwd
+        });
+        updated.add_attribute(ufrag.clone())?;
+        updated.add_attribute(pwd.clone())?;
+        for media in &mut updated.media {
+            for t in &[
+                SdpAttributeType::IceUfrag,
+                SdpAttributeType::IcePwd,
+                SdpAttributeType::Candidate,
+                SdpAttributeType::EndOfCandidates,
+            ] {
+                media.remove_attribute(t.clone());
+            }
+        }
+    } else if ufrag.is_some() || pwd.is_some() {
+        return Err(SdpParserInternalError::Generic(
+            "ICE restart requires both ice-ufrag and ice-pwd".to_string(),
+        )
+        .with_kind(SdpParserErrorKind::WhipIncompleteIceCredentials));
+    }
+    merge_sdp_fragment(&mut updated, fragment)?;
+    *session = updated;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "./whip_tests.rs"]
+mod tests;
@@ -0,0 +1,208 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use attribute_type::SdpAttributeFingerprintHashType;
+use parse_sdp;
+use sdpfrag::parse_sdp_fragment;
+use std::convert::TryFrom;
+use tests::parse_dummy_sdp_session;
+
+fn create_offer(direction: &str, group: &str) -> SdpSession {
+    parse_dummy_sdp_session(&format!(
+        "a=ice-ufrag:client\r\n\
+         a=ice-pwd:clientpassword\r\n\
+         a=fingerprint:sha-256 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:\
+         23:73:AC:BC:BF:2F:E3:91:CB:57:A9:9D:4A:A2:0B:40\r\n\
+         {group}\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:0\r\n\
+         a={direction}\r\n\
+         a=setup:actpass\r\n\
+         a=rtcp-mux\r\n\
+         a=rtpmap:111 opus/48000/2\r\n\
+         a=ssrc:1234 cname:foo\r\n\
+         a=candidate:1 1 UDP 2122252543 192.0.2.1 50000 typ host\r\n\
+         m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+         a=mid:1\r\n\
+         a={direction}\r\n\
+         a=setup:actpass\r\n\
+         a=rtcp-mux\r\n\
+         a=rtpmap:96 VP8/90000\r\n\
+         a=rid:hi {rid}\r\n\
+         a=rid:lo {rid}\r\n\
+         a=simulcast:{rid} hi;lo\r\n",
+        direction = direction,
+        group = group,
+        rid = if direction == "recvonly" {
+            "recv"
+        } else {
+            "send"
+        }
+    ))
+}
+
+fn create_options() -> SdpWhipAnswerOptions {
+    SdpWhipAnswerOptions {
+        ice_ufrag: "server".to_string(),
+        ice_pwd: "serverpassword".to_string(),
+        fingerprint: SdpAttributeFingerprint::try_from((
+            SdpAttributeFingerprintHashType::Sha256,
+            vec![0xab; 32],
+        ))
+        .unwrap(),
+        host_candidates: vec![
+            "198.51.100.1:4000".parse().unwrap(),
+            "[2001:db8::1]:4000".parse().unwrap(),
+        ],
+        session_id: 42,
+    }
+}
+
+const BUNDLE: &str = "a=group:BUNDLE 0 1\r\n";
+
+#[test]
+fn test_validate_whip_offer() {
+    assert!(validate_whip_offer(&create_offer("sendonly", BUNDLE), SdpWhipEndpoint::Whip).is_ok());
+    assert!(validate_whip_offer(&create_offer("recvonly", BUNDLE), SdpWhipEndpoint::Whep).is_ok());
+    let error_kind = |directions: &str, groups: &str, endpoint: SdpWhipEndpoint| {
+        validate_whip_offer(&create_offer(directions, groups), endpoint)
+            .map_err(|error| error.kind())
+            .err()
+    };
+    assert_eq!(
+        error_kind("sendrecv", BUNDLE, SdpWhipEndpoint::Whip),
+        Some(SdpParserErrorKind::WhipOfferWrongDirection)
+    );
+    assert_eq!(
+        error_kind("sendonly", BUNDLE, SdpWhipEndpoint::Whep),
+        Some(SdpParserErrorKind::WhipOfferWrongDirection)
+    );
+    assert_eq!(
+        error_kind("sendonly", "", SdpWhipEndpoint::Whip),
+        Some(SdpParserErrorKind::WhipOfferBundleGroupCount)
+    );
+    assert_eq!(
+        error_kind("sendonly", "a=group:BUNDLE 0\r\n", SdpWhipEndpoint::Whip),
+        Some(SdpParserErrorKind::WhipOfferNotBundled)
+    );
+    assert_eq!(
+        error_kind(
+            "sendonly",
+            "a=group:BUNDLE 0\r\na=group:BUNDLE 1\r\n",
+            SdpWhipEndpoint::Whip
+        ),
+        Some(SdpParserErrorKind::WhipOfferBundleGroupCount)
+    );
+}
+
+#[test]
+fn test_create_whip_answer() {
+    let offer = create_offer("sendonly", BUNDLE);
+    let answer = create_whip_answer(&offer, SdpWhipEndpoint::Whip, &create_options()).unwrap();
+
+    // The answer must be valid SDP
+    let answer = parse_sdp(&answer.to_string(), true).unwrap();
+    assert!(answer.get_attribute(SdpAttributeType::IceLite).is_some());
+    match answer.get_attribute(SdpAttributeType::Group) {
+        Some(SdpAttribute::Group(group)) => assert_eq!(group.tags, vec!["0", "1"]),
+        _ => unreachable!(),
+    }
+    for media in &answer.media {
+        assert_eq!(media.get_port(), 4000);
+        assert!(media.get_attribute(SdpAttributeType::Recvonly).is_some());
+        assert!(media.get_attribute(SdpAttributeType::Sendonly).is_none());
+        assert!(media.get_attribute(SdpAttributeType::Ssrc).is_none());
+        assert!(media.get_attribute(SdpAttributeType::Rtpmap).is_some());
+        assert!(media
+            .get_attribute(SdpAttributeType::EndOfCandidates)
+            .is_some());
+        assert_eq!(
+            media
+                .get_attributes_of_type(SdpAttributeType::Candidate)
+                .len(),
+            2
+        );
+        match media.get_attribute(SdpAttributeType::Setup) {
+            Some(SdpAttribute::Setup(setup)) => assert_eq!(setup.to_string(), "active"),
+            _ => unreachable!(),
+        }
+    }
+    // The simulcast offer is answered in the opposite direction
+    match answer.media[1].get_attribute(SdpAttributeType::Simulcast) {
+        Some(SdpAttribute::Simulcast(simulcast)) => {
+            assert!(simulcast.send.is_empty());
+            assert_eq!(simulcast.receive.len(), 2);
+        }
+        _ => unreachable!(),
+    }
+
+    let mut options = create_options();
+    options.host_candidates.clear();
+    assert_eq!(
+        create_whip_answer(&offer, SdpWhipEndpoint::Whip, &options)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::WhipAnswerMissingCandidate
+    );
+    assert_eq!(
+        create_whip_answer(&offer, SdpWhipEndpoint::Whep, &create_options())
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::WhipOfferWrongDirection
+    );
+}
+
+#[test]
+fn test_ice_restart_fragment() {
+    let mut offer = create_offer("sendonly", BUNDLE);
+    let candidate = host_candidate(0, &"192.0.2.2:5000".parse().unwrap()).unwrap();
+
+    let fragment = create_ice_fragment(
+        &offer,
+        Some(("restart", "restartpassword")),
+        &[candidate],
+        true,
+    )
+    .unwrap();
+    let body = fragment.to_string();
+    assert_eq!(
+        body,
+        "a=ice-ufrag:restart\r\n\
+         a=ice-pwd:restartpassword\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=mid:0\r\n\
+         a=candidate:3059587882 1 UDP 2130706431 192.0.2.2 5000 typ host\r\n\
+         a=end-of-candidates\r\n"
+    );
+
+    apply_ice_fragment(&mut offer, &parse_sdp_fragment(&body, true).unwrap()).unwrap();
+    match offer.get_attribute(SdpAttributeType::IceUfrag) {
+        Some(SdpAttribute::IceUfrag(ufrag)) => assert_eq!(ufrag, "restart"),
+        _ => unreachable!(),
+    }
+    let candidates = offer.media[0].get_attributes_of_type(SdpAttributeType::Candidate);
+    assert_eq!(candidates.len(), 1);
+    assert!(candidates[0].to_string().contains("192.0.2.2"));
+
+    // Trickled candidates of the old ICE session are rejected
+    let stale = parse_sdp_fragment("a=ice-ufrag:client\r\na=end-of-candidates\r\n", true).unwrap();
+    assert!(apply_ice_fragment(&mut offer, &stale).is_err());
+
+    let incomplete = parse_sdp_fragment("a=ice-ufrag:other\r\n", true).unwrap();
+    assert_eq!(
+        apply_ice_fragment(&mut offer, &incomplete)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::WhipIncompleteIceCredentials
+    );
+
+    let unbundled = create_offer("sendonly", "");
+    assert_eq!(
+        create_ice_fragment(&unbundled, None, &[], true)
+            .unwrap_err()
+            .kind(),
+        SdpParserErrorKind::WhipSessionNotBundled
+    );
+}
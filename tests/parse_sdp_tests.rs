@@ -2,6 +2,8 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+#![allow(clippy::nonminimal_bool)]
+
 extern crate webrtc_sdp;
 
 #[cfg(test)]
@@ -666,6 +668,110 @@ fn parse_firefox_simulcast_answer() {
     assert_eq!(sdp.media.len(), 1);
 }
 
+#[test]
+fn parse_st2110_video_sdp() {
+    let sdp_str = "v=0\r\n\
+                   o=- 123456 11 IN IP4 192.0.2.100\r\n\
+                   s=ST 2110 video\r\n\
+                   t=0 0\r\n\
+                   m=video 50000 RTP/AVP 96\r\n\
+                   c=IN IP4 239.100.9.10/32\r\n\
+                   a=source-filter:incl IN IP4 239.100.9.10 192.0.2.100\r\n\
+                   a=rtpmap:96 raw/90000\r\n\
+                   a=fmtp:96 sampling=YCbCr-4:2:2; width=1920; height=1080; \
+                   exactframerate=30000/1001; depth=10; TCS=SDR; colorimetry=BT709; \
+                   PM=2110GPM; SSN=ST2110-20:2017; TP=2110TPN; interlace\r\n\
+                   a=ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:37\r\n\
+                   a=mediaclk:direct=0\r\n";
+    check_parse_and_serialize(sdp_str);
+
+    let sdp = webrtc_sdp::parse_sdp(sdp_str, true).unwrap();
+    let media = &sdp.media[0];
+    match media.get_attribute(webrtc_sdp::attribute_type::SdpAttributeType::Fmtp) {
+        Some(webrtc_sdp::attribute_type::SdpAttribute::Fmtp(fmtp)) => {
+            let video = fmtp.parameters.st2110_video.as_ref().unwrap();
+            assert_eq!((video.width, video.height), (1920, 1080));
+            assert!(video.interlace);
+        }
+        _ => unreachable!(),
+    }
+    assert!(media
+        .get_attribute(webrtc_sdp::attribute_type::SdpAttributeType::TsRefclk)
+        .is_some());
+    assert!(media
+        .get_attribute(webrtc_sdp::attribute_type::SdpAttributeType::MediaClk)
+        .is_some());
+}
+
+#[test]
+fn parse_t38_image_sdp() {
+    let sdp_str = "v=0\r\n\
+                   o=- 4711 1 IN IP4 192.0.2.20\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   c=IN IP4 192.0.2.20\r\n\
+                   m=image 49170 udptl t38\r\n\
+                   a=T38FaxVersion:0\r\n\
+                   a=T38MaxBitRate:14400\r\n\
+                   a=T38FaxRateManagement:transferredTCF\r\n\
+                   a=T38FaxMaxDatagram:400\r\n\
+                   a=T38FaxUdpEC:t38UDPRedundancy\r\n\
+                   a=T38FaxFillBitRemoval:0\r\n\
+                   m=image 0 UDP/TLS/UDPTL t38\r\n";
+    check_parse_and_serialize(sdp_str);
+
+    let sdp = webrtc_sdp::parse_sdp(sdp_str, true).unwrap();
+    assert_eq!(
+        *sdp.media[0].get_type(),
+        webrtc_sdp::media_type::SdpMediaValue::Image
+    );
+    assert_eq!(
+        *sdp.media[0].get_proto(),
+        webrtc_sdp::media_type::SdpProtocolValue::Udptl
+    );
+    assert_eq!(
+        *sdp.media[1].get_proto(),
+        webrtc_sdp::media_type::SdpProtocolValue::UdpTlsUdptl
+    );
+    let t38 = webrtc_sdp::t38::SdpT38Parameters::from_media(&sdp.media[0]);
+    assert_eq!(t38.max_bit_rate, Some(14400));
+    assert!(!t38.fill_bit_removal);
+    assert!(webrtc_sdp::t38::validate_t38_session_answer(&sdp, &sdp).is_ok());
+}
+
+#[test]
+fn parse_real_time_text_sdp() {
+    let sdp_str = "v=0\r\n\
+                   o=- 2890844526 2890842807 IN IP4 192.0.2.30\r\n\
+                   s=-\r\n\
+                   t=0 0\r\n\
+                   c=IN IP4 192.0.2.30\r\n\
+                   m=text 11000 RTP/AVP 100 98\r\n\
+                   a=rtpmap:98 t140/1000\r\n\
+                   a=fmtp:98 cps=30\r\n\
+                   a=rtpmap:100 red/1000\r\n\
+                   a=fmtp:100 98/98/98\r\n";
+    check_parse_and_serialize(sdp_str);
+
+    let sdp = webrtc_sdp::parse_sdp(sdp_str, true).unwrap();
+    let media = &sdp.media[0];
+    assert_eq!(
+        *media.get_type(),
+        webrtc_sdp::media_type::SdpMediaValue::Text
+    );
+    let fmtps = media.get_attributes_of_type(webrtc_sdp::attribute_type::SdpAttributeType::Fmtp);
+    match (fmtps[0], fmtps[1]) {
+        (
+            webrtc_sdp::attribute_type::SdpAttribute::Fmtp(t140),
+            webrtc_sdp::attribute_type::SdpAttribute::Fmtp(red),
+        ) => {
+            assert_eq!(t140.parameters.cps, 30);
+            assert_eq!(red.parameters.encodings, vec![98, 98, 98]);
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn parse_and_serialize_sdp_with_unusual_attributes() {
     let sdp_str = "v=0\r\n\
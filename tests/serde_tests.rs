@@ -0,0 +1,158 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+#![cfg(feature = "serialize")]
+
+extern crate serde_json;
+extern crate webrtc_sdp;
+
+use std::fs;
+use webrtc_sdp::error::SdpParserError;
+use webrtc_sdp::SdpSession;
+
+fn read_example_sdps() -> Vec<(String, String)> {
+    let mut sdps: Vec<(String, String)> = fs::read_dir("examples/sdps")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("sdp"))
+        .map(|path| {
+            // Remove comment lines like the file_parser example does
+            let sdp = fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .filter(|l| !l.trim_start().starts_with(';'))
+                .collect::<Vec<&str>>()
+                .join("\r\n");
+            (path.display().to_string(), sdp)
+        })
+        .collect();
+    sdps.sort();
+    sdps
+}
+
+#[test]
+fn round_trip_example_sdps() {
+    let mut sessions = 0;
+    for (name, sdp) in read_example_sdps() {
+        match webrtc_sdp::parse_sdp(&sdp, true) {
+            Ok(session) => {
+                let json = serde_json::to_string(&session).unwrap();
+                let restored: SdpSession = serde_json::from_str(&json)
+                    .unwrap_or_else(|e| panic!("{}: failed to deserialize: {}", name, e));
+                assert_eq!(serde_json::to_string(&restored).unwrap(), json, "{}", name);
+                assert_eq!(restored.to_string(), session.to_string(), "{}", name);
+                sessions += 1;
+            }
+            Err(error) => {
+                let json = serde_json::to_string(&error).unwrap();
+                let restored: SdpParserError = serde_json::from_str(&json).unwrap();
+                assert_eq!(serde_json::to_string(&restored).unwrap(), json, "{}", name);
+                assert_eq!(restored.to_string(), error.to_string(), "{}", name);
+                assert_eq!(restored.kind(), error.kind(), "{}", name);
+            }
+        }
+    }
+    assert!(sessions > 0);
+}
+
+#[test]
+fn round_trip_parser_errors() {
+    let sdps = [
+        // Line error with a located token
+        "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nt=0 0\r\nc=IN IP4 0.0.0.0\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=fmtp:111 max-fs=foo\r\n",
+        // Integer parsing error
+        "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nt=foo 0\r\n",
+        // Sequence error with several lines
+        "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nt=0 0\r\nc=IN IP4 0.0.0.0\r\n\
+         a=extmap:1 urn:ietf:params:rtp-hdrext:sdes:mid\r\n\
+         m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n\
+         a=extmap:2 urn:ietf:params:rtp-hdrext:sdes:mid\r\n",
+    ];
+    for sdp in sdps.iter() {
+        let error = webrtc_sdp::parse_sdp(sdp, true).unwrap_err();
+        let json = serde_json::to_string(&error).unwrap();
+        let restored: SdpParserError = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_string(), error.to_string());
+        assert_eq!(restored.kind(), error.kind());
+        assert_eq!(restored.span(), error.span());
+        assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    }
+}
+
+#[test]
+fn round_trip_session_with_warnings() {
+    let sdp = "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nt=0 0\r\nc=IN IP4 0.0.0.0\r\n\
+               a=unknown-attribute\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 111\r\n";
+    let session = webrtc_sdp::parse_sdp(sdp, false).unwrap();
+    assert!(!session.warnings.is_empty());
+    let json = serde_json::to_string(&session).unwrap();
+    let restored: SdpSession = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.warnings.len(), session.warnings.len());
+    assert_eq!(
+        restored.warnings[0].to_string(),
+        session.warnings[0].to_string()
+    );
+}
+
+#[test]
+fn browser_shaped_json() {
+    use webrtc_sdp::w3c::{SdpDescriptionType, SdpIceCandidateInit, SdpSessionDescriptionInit};
+
+    let description: SdpSessionDescriptionInit = serde_json::from_str(
+        r#"{"type": "offer", "sdp": "v=0\r\no=- 1 1 IN IP4 0.0.0.0\r\ns=-\r\nt=0 0\r\nc=IN IP4 0.0.0.0\r\nm=audio 9 UDP/TLS/RTP/SAVPF 111\r\na=mid:0\r\n"}"#,
+    )
+    .unwrap();
+    assert_eq!(description.sdp_type, SdpDescriptionType::Offer);
+    let mut session = description.parse(true).unwrap();
+
+    let init: SdpIceCandidateInit = serde_json::from_str(
+        r#"{"candidate": "candidate:1 1 udp 2122252543 192.0.2.1 50000 typ host",
+            "sdpMid": "0", "sdpMLineIndex": 0, "usernameFragment": null}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        webrtc_sdp::w3c::add_ice_candidate(&mut session, &init).unwrap(),
+        0
+    );
+
+    let json = serde_json::to_value(&init).unwrap();
+    assert_eq!(json["sdpMid"], "0");
+    assert_eq!(json["sdpMLineIndex"], 0);
+    let json = serde_json::to_value(SdpSessionDescriptionInit::new(
+        SdpDescriptionType::Answer,
+        &session,
+    ))
+    .unwrap();
+    assert_eq!(json["type"], "answer");
+}
+
+#[test]
+fn round_trip_anonymizer_state() {
+    use webrtc_sdp::anonymizer::{AnonymizingClone, SdpAnonymizerState, StatefulSdpAnonymizer};
+
+    let session = webrtc_sdp::parse_sdp(
+        "v=0\r\no=- 1 1 IN IP4 192.0.2.1\r\ns=-\r\nt=0 0\r\nc=IN IP4 192.0.2.3\r\n\
+         m=audio 5000 UDP/TLS/RTP/SAVPF 111\r\na=ice-ufrag:user\r\n",
+        true,
+    )
+    .unwrap();
+    let mut anon = StatefulSdpAnonymizer::new();
+    let masked = session.masked_clone(&mut anon);
+
+    let json = serde_json::to_string(&anon.export_state()).unwrap();
+    let state: SdpAnonymizerState = serde_json::from_str(&json).unwrap();
+    assert_eq!(state, anon.export_state());
+    let restored = StatefulSdpAnonymizer::from_state(&state, None).unwrap();
+    let masked_connection = match masked.connection {
+        Some(ref connection) => connection.address.to_string(),
+        None => panic!(),
+    };
+    assert_eq!(masked_connection, "IN IP4 0.0.0.2");
+    assert_eq!(
+        restored.unmask_ip(&"0.0.0.2".parse().unwrap()),
+        Some("192.0.2.3".parse().unwrap())
+    );
+}